@@ -47,8 +47,8 @@ call rpcnotify(id, 'api')
 
 sleep 20
 
-call add(l, 'File Neovim-Lib: ' . g:finished_file)
-call add(l, 'Buffer Neovim-Lib: ' . g:finished_buffer)
-call add(l, 'API Neovim-Lib: ' . g:finished_api)
+call add(l, 'File Blocking: ' . g:finished_file)
+call add(l, 'Buffer Blocking: ' . g:finished_buffer)
+call add(l, 'API Blocking: ' . g:finished_api)
 
 call nvim_buf_set_lines(0, 0, -1, v:false, l)
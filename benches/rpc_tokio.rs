@@ -1,4 +1,6 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use futures::future::join_all;
 
 use nvim_rs::{
   call_args,
@@ -84,5 +86,58 @@ fn request_file(c: &mut Criterion) {
   });
 }
 
-criterion_group!(name = requests; config = Criterion::default().without_plots(); targets = simple_requests, request_file);
+fn concurrent_requests(c: &mut Criterion) {
+  let handler = Dummy::new();
+
+  let rt = Builder::new_current_thread()
+    .enable_io()
+    .build()
+    .unwrap();
+
+  let (nvim, _io_handler, _child) = rt
+    .block_on(create::new_child_cmd(
+      Command::new(nvim_path()).args([
+        "-u",
+        "NONE",
+        "--embed",
+        "--headless",
+        "Cargo.lock",
+      ]),
+      handler,
+    ))
+    .unwrap();
+
+  let nvim1 = nvim.clone();
+  rt.block_on(async move { nvim1.command("set noswapfile").await })
+    .expect("0");
+
+  // Pipeline many requests at once, so that many of them are waiting in the
+  // queue for their response at the same time
+  let mut group = c.benchmark_group("concurrent_requests");
+  for concurrency in [1, 10, 100, 500] {
+    group.bench_with_input(
+      BenchmarkId::from_parameter(concurrency),
+      &concurrency,
+      |b, &concurrency| {
+        b.iter(|| {
+          let nvim = nvim.clone();
+          rt.block_on(async move {
+            let calls = (0..concurrency).map(|_| {
+              nvim.call(
+                "nvim_buf_get_lines",
+                call_args![0i64, 0i64, -1i64, false],
+              )
+            });
+            for res in join_all(calls).await {
+              res.expect("1").expect("2");
+            }
+          });
+        })
+      },
+    );
+  }
+  group.finish();
+}
+
+criterion_group!(name = requests; config = Criterion::default().without_plots(); targets = simple_requests, request_file, concurrent_requests);
 criterion_main!(requests);
@@ -0,0 +1,49 @@
+//! The auto generated API for [`blocking::Neovim`](crate::blocking::Neovim)
+//!
+//! Every function blocks the calling thread until the corresponding function
+//! of [`neovim_api`](crate::neovim_api) has finished.
+//!
+//! Auto generated {{date}}
+use futures::executor::block_on;
+
+use crate::{
+  blocking::{Buffer, Neovim, Tabpage, Window},
+  error::CallError,
+  rpc::*,
+};
+
+{% for etype in exttypes %}
+
+impl {{ etype.name }}
+  {
+
+    {% for f in functions if f.ext and f.name.startswith(etype.prefix) %}
+    /// since: {{f.since}}
+    pub fn {{f.name|replace(etype.prefix, '')}}(&self, {{f.argstring|replace("<W>", "")}}) -> Result<{{f.return_type.native_type_ret}}, Box<CallError>>
+    {
+        block_on(self.inner.{{f.name|replace(etype.prefix, '')}}({% for p in f.parameters %}{% if p.ext %}&{{p["name"]}}.inner{% else %}{{p["name"]}}{% endif %}{% if not loop.last %}, {% endif %}{% endfor %}))
+    }
+    /// since: {{f.since}}
+    pub fn {{f.name|replace(etype.prefix, '')}}_notify(&self, {{f.argstring|replace("<W>", "")}}) -> Result<(), Box<CallError>>
+    {
+        block_on(self.inner.{{f.name|replace(etype.prefix, '')}}_notify({% for p in f.parameters %}{% if p.ext %}&{{p["name"]}}.inner{% else %}{{p["name"]}}{% endif %}{% if not loop.last %}, {% endif %}{% endfor %}))
+    }
+    {% endfor %}
+}
+
+{% endfor %}
+
+
+impl Neovim
+{
+    {% for f in functions if not f.ext %}
+    pub fn {{f.name|replace('nvim_', '')}}(&self, {{f.argstring|replace("<W>", "")}}) -> Result<{{f.return_type.native_type_ret}}, Box<CallError>> {
+        block_on(self.inner.{{f.name|replace('nvim_', '')}}({% for p in f.parameters %}{% if p.ext %}&{{p["name"]}}.inner{% else %}{{p["name"]}}{% endif %}{% if not loop.last %}, {% endif %}{% endfor %}))
+    }
+
+    pub fn {{f.name|replace('nvim_', '')}}_notify(&self, {{f.argstring|replace("<W>", "")}}) -> Result<(), Box<CallError>> {
+        block_on(self.inner.{{f.name|replace('nvim_', '')}}_notify({% for p in f.parameters %}{% if p.ext %}&{{p["name"]}}.inner{% else %}{{p["name"]}}{% endif %}{% if not loop.last %}, {% endif %}{% endfor %}))
+    }
+
+    {% endfor %}
+}
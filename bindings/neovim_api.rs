@@ -1,5 +1,10 @@
 //! The auto generated API for [`neovim`](crate::neovim::Neovim)
 //!
+//! Every function has a `_notify` variant that sends the request as a
+//! notification via
+//! [`send_notification`](crate::neovim::Neovim::send_notification), without
+//! waiting for a response.
+//!
 //! Auto generated {{date}}
 use futures::io::AsyncWrite;
 
@@ -44,6 +49,17 @@ impl<W> {{ etype.name }}<W>
                     .try_unpack()
                     .map_err(|v| Box::new(CallError::WrongValueType(v)))
     }
+    /// since: {{f.since}}
+    pub async fn {{f.name|replace(etype.prefix, '')}}_notify(&self, {{f.argstring}}) -> Result<(), Box<CallError>>
+    {
+        self.neovim.send_notification("{{f.name}}",
+                          call_args![self.code_data.clone()
+                          {% if f.parameters|count > 0 %}
+                          , {{ f.parameters|map(attribute = "name")|join(", ") }}
+                          {% endif %}
+                          ])
+                    .await
+    }
     {% endfor %}
 }
 
@@ -63,5 +79,11 @@ where
                     .map_err(|v| Box::new(CallError::WrongValueType(v)))
     }
 
+    pub async fn {{f.name|replace('nvim_', '')}}_notify(&self, {{f.argstring}}) -> Result<(), Box<CallError>> {
+        self.send_notification("{{f.name}}",
+                          call_args![{{ f.parameters|map(attribute = "name")|join(", ") }}])
+                    .await
+    }
+
     {% endfor %}
 }
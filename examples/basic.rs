@@ -61,20 +61,17 @@ async fn main() {
           });
       }
 
-      if !err.is_channel_closed() {
-        // Closed channel usually means neovim quit itself, or this plugin was
-        // told to quit by closing the channel, so it's not always an error
-        // condition.
-        eprintln!("Error: '{}'", err);
+      eprintln!("Error: '{}'", err);
 
-        let mut source = err.source();
+      let mut source = err.source();
 
-        while let Some(e) = source {
-          eprintln!("Caused by: '{}'", e);
-          source = e.source();
-        }
+      while let Some(e) = source {
+        eprintln!("Caused by: '{}'", e);
+        source = e.source();
       }
     }
-    Ok(Ok(())) => {}
+    // Neovim quit itself, or this plugin was told to quit by closing the
+    // channel, so this isn't an error
+    Ok(Ok(_reason)) => {}
   }
 }
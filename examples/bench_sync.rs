@@ -1,16 +1,17 @@
-use neovim_lib::{Neovim, NeovimApi, Session};
+use nvim_rs::{
+  blocking::{self, Handler, Neovim},
+  Value,
+};
 
-fn main() {
-  let mut session = Session::new_parent().unwrap();
-  let receiver = session.start_event_loop_channel();
-  let mut nvim = Neovim::new(session);
+struct NeovimHandler;
 
-  loop {
-    match receiver.recv().unwrap().0.as_ref() {
+impl Handler for NeovimHandler {
+  fn handle_notify(&self, name: String, _args: Vec<Value>, nvim: Neovim) {
+    match name.as_ref() {
       "file" => {
         let c = nvim.get_current_buf().unwrap();
         for _ in 0..1_000_usize {
-          let _x = c.get_lines(&mut nvim, 0, -1, false);
+          let _x = c.get_lines(0, -1, false);
         }
         nvim
           .command("let g:finished_file = reltimestr(reltime(g:started_file))")
@@ -36,7 +37,14 @@ fn main() {
           .command("let g:finished_api = reltimestr(reltime(g:started_api))")
           .unwrap();
       }
-      _ => break,
+      _ => {}
     }
   }
 }
+
+fn main() {
+  let (_nvim, io) = blocking::new_parent(NeovimHandler).unwrap();
+
+  // Neovim quitting ends the session, and the benchmark with it
+  let _ = io.join();
+}
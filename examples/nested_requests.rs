@@ -157,12 +157,10 @@ async fn main() {
   let nv = nvim.clone();
   spawn(async move { nv.set_var("oogle", Value::from("doodle")).await });
 
-  // The 2nd timer closes the channel, which will be returned as an error from
-  // the io handler. We only fail the test if we got another error
+  // The 2nd timer closes the channel, which ends the io handler regularly.
+  // We only fail the test if we got an error
   if let Err(err) = io.await.unwrap() {
-    if !err.is_channel_closed() {
-      panic!("Error in io: '{:?}'", err);
-    }
+    panic!("Error in io: '{:?}'", err);
   }
 
   assert_eq!(
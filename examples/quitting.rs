@@ -1,8 +1,6 @@
 //! Quitting. See src/examples/quitting.rs for documentation
 use nvim_rs::{create::tokio as create, rpc::handler::Dummy as DummyHandler};
 
-use std::error::Error;
-
 use tokio::process::Command;
 
 const NVIMPATH: &str = "neovim/build/bin/nvim";
@@ -11,7 +9,7 @@ const NVIMPATH: &str = "neovim/build/bin/nvim";
 async fn main() {
   let handler = DummyHandler::new();
 
-  let (nvim, _io_handle, _child) = create::new_child_cmd(
+  let (nvim, io_handle, _child) = create::new_child_cmd(
     Command::new(NVIMPATH)
       .args(&["-u", "NONE", "--embed", "--headless"])
       .env("NVIM_LOG_FILE", "nvimlog"),
@@ -23,14 +21,12 @@ async fn main() {
   let chan = nvim.get_api_info().await.unwrap()[0].as_i64().unwrap();
   let close = format!("call chanclose({})", chan);
 
-  if let Err(e) = nvim.command(&close).await {
-    eprintln!("Error in last command: {}", e);
-    eprintln!("Caused by : {:?}", e.as_ref().source());
+  // Neovim closes the channel before answering, so this request fails
+  let _ = nvim.command(&close).await;
 
-    if e.is_channel_closed() {
-      eprintln!("Channel closed, quitting!");
-    } else {
-      eprintln!("Channel was not closed, no idea what happened!");
-    }
+  match io_handle.await {
+    Err(joinerr) => eprintln!("Error joining IO loop: '{}'", joinerr),
+    Ok(Ok(reason)) => eprintln!("Channel closed ({:?}), quitting!", reason),
+    Ok(Err(err)) => eprintln!("Error in the IO loop: '{}'", err),
   }
 }
@@ -1,19 +1,25 @@
 //! Scorched earth. See src/examples/scorched_earth.rs for documentation
-use std::{error::Error, sync::Arc, fs::File};
+//!
+//! Run with `--child` to spawn an embedded neovim instead of being run as a
+//! plugin. The example then sets up the autocommands itself, types some text,
+//! and prints the resulting syntax region.
+use std::{
+  error::Error, fs::File, marker::PhantomData, sync::Arc, time::Duration,
+};
 
 use rmpv::Value;
 
-use futures::lock::Mutex;
+use futures::{io::AsyncWrite, lock::Mutex};
 
 use smol_macros::main;
 
 use smol::{
-  Unblock,
+  process::{ChildStdin, Command},
+  Timer, Unblock,
 };
 
 use nvim_rs::{create::smol as create, Handler, Neovim};
 
-
 struct Posis {
   cursor_start: Option<(u64, u64)>,
   cursor_end: Option<(u64, u64)>,
@@ -41,17 +47,27 @@ fn the_smaller(
   }
 }
 
-#[derive(Clone)]
-struct NeovimHandler(Arc<Mutex<Posis>>);
+// Generic over the writer, so it can be used both as a plugin and with an
+// embedded neovim
+struct NeovimHandler<W>(Arc<Mutex<Posis>>, PhantomData<fn() -> W>);
+
+impl<W> Clone for NeovimHandler<W> {
+  fn clone(&self) -> Self {
+    NeovimHandler(self.0.clone(), PhantomData)
+  }
+}
 
-impl Handler for NeovimHandler {
-  type Writer = Unblock<File>;
+impl<W> Handler for NeovimHandler<W>
+where
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  type Writer = W;
 
   async fn handle_notify(
     &self,
     name: String,
     args: Vec<Value>,
-    neovim: Neovim<Unblock<File>>,
+    neovim: Neovim<W>,
   ) {
     match name.as_ref() {
       "cursor-moved-i" => {
@@ -95,13 +111,73 @@ impl Handler for NeovimHandler {
   }
 }
 
+/// Spawn an embedded neovim, and play a plugin manager by setting up the
+/// autocommands that notify us.
+async fn run_child(handler: NeovimHandler<ChildStdin>) {
+  let (nvim, _io_handler, mut child, info) = create::new_child_handshake_cmd(
+    Command::new("nvim").args(["-u", "NONE", "--embed", "--headless"]),
+    handler,
+    "scorched_earth",
+  )
+  .await
+  .unwrap();
+
+  let chan = info.channel_id;
+  for autocmd in [
+    format!(
+      "InsertEnter * call rpcnotify({chan}, 'insert-enter', v:insertmode, \
+       line('.'), col('.'))"
+    ),
+    format!(
+      "CursorMovedI * call rpcnotify({chan}, 'cursor-moved-i', line('.'), \
+       col('.'))"
+    ),
+    format!("InsertLeave * call rpcnotify({chan}, 'insert-leave')"),
+  ] {
+    nvim.command(&format!("autocmd {autocmd}")).await.unwrap();
+  }
+
+  nvim.input("iscorched earth").await.unwrap();
+  // Give the notifications some time to arrive
+  Timer::after(Duration::from_millis(200)).await;
+
+  let region = nvim
+    .exec2(
+      "syntax list ScorchedEarth",
+      vec![("output".into(), true.into())],
+    )
+    .await;
+  match region {
+    Ok(region) => {
+      for (key, val) in region {
+        if key.as_str() == Some("output") {
+          println!("{}", val.as_str().unwrap_or_default());
+        }
+      }
+    }
+    // Neovim fails if the region hasn't been defined
+    Err(e) => eprintln!("No scorched earth: '{}'", e),
+  }
+
+  // Neovim quits before answering
+  let _ = nvim.command("qa!").await;
+  println!("Neovim exited with {}", child.status().await.unwrap());
+}
+
 main! {
 async fn main() {
   let p = Posis {
     cursor_start: None,
     cursor_end: None,
   };
-  let handler: NeovimHandler = NeovimHandler(Arc::new(Mutex::new(p)));
+
+  if std::env::args().any(|arg| arg == "--child") {
+    run_child(NeovimHandler(Arc::new(Mutex::new(p)), PhantomData)).await;
+    return;
+  }
+
+  let handler: NeovimHandler<Unblock<File>> =
+    NeovimHandler(Arc::new(Mutex::new(p)), PhantomData);
 
   let (nvim, io_handler) = create::new_parent(handler).await.unwrap();
 
@@ -123,21 +199,18 @@ async fn main() {
           });
       }
 
-      if !err.is_channel_closed() {
-        // Closed channel usually means neovim quit itself, or this plugin was
-        // told to quit by closing the channel, so it's not always an error
-        // condition.
-        eprintln!("Error: '{}'", err);
+      eprintln!("Error: '{}'", err);
 
-        let mut source = err.source();
+      let mut source = err.source();
 
-        while let Some(e) = source {
-          eprintln!("Caused by: '{}'", e);
-          source = e.source();
-        }
+      while let Some(e) = source {
+        eprintln!("Caused by: '{}'", e);
+        source = e.source();
       }
     }
-    Ok(()) => {}
+    // Neovim quit itself, or this plugin was told to quit by closing the
+    // channel, so this isn't an error
+    Ok(_reason) => {}
   }
 }
 }
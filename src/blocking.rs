@@ -0,0 +1,633 @@
+//! A blocking client for synchronous programs
+//!
+//! Each session runs on a small runtime thread of its own, and every call
+//! blocks the calling thread until neovim has answered. All API functions are
+//! available on [`Neovim`], [`Buffer`], [`Window`] and [`Tabpage`], see
+//! [`blocking_api`](crate::blocking_api).
+//!
+//! Requests and notifications from neovim are passed to a synchronous
+//! [`Handler`]. It's called on a thread pool, so it can call neovim in turn.
+//! Notifications are still handled one at a time, in the order in which they
+//! were received.
+//!
+//! The sessions don't depend on the async backend, so this module works the
+//! same whether or not `use_tokio` or `use_smol` is enabled as well.
+//!
+//! Don't call the blocking functions from inside an async context, use
+//! [`Neovim::inner`] there instead.
+//!
+//! ```no_run
+//! use nvim_rs::blocking::{self, Dummy};
+//!
+//! # fn main() -> std::io::Result<()> {
+//! let (nvim, io) = blocking::new_tcp("127.0.0.1:6666", Dummy)?;
+//!
+//! let buf = nvim.get_current_buf().unwrap();
+//! buf.set_lines(0, -1, false, vec!["Hello".into()]).unwrap();
+//!
+//! nvim.command("quit!").unwrap_or(());
+//! let _ = io.join();
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+  future::Future,
+  io,
+  net::{TcpStream, ToSocketAddrs},
+  path::Path,
+  process::{Child, Command, Stdio},
+  sync::Arc,
+  thread::{self, JoinHandle},
+};
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+
+use async_io::Async;
+use blocking::{unblock, Unblock};
+use futures::{
+  channel::mpsc::{unbounded, UnboundedReceiver},
+  executor::{block_on, LocalPool},
+  future::BoxFuture,
+  io::{AsyncRead, AsyncReadExt, AsyncWrite},
+  task::LocalSpawnExt,
+  StreamExt,
+};
+
+use crate::{
+  create::{generic, unbuffered_stdout},
+  error::{CallError, HandshakeError, LoopError},
+  neovim::{HandshakeInfo, ShutdownReason},
+  sessionoptions::SessionOptions,
+  uioptions::UiAttachOptions,
+  Value,
+};
+
+/// The writer of every blocking session.
+pub type Writer = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// Handling requests and notifications from neovim, like
+/// [`rpc::handler::Handler`](crate::rpc::handler::Handler), but
+/// synchronously.
+pub trait Handler: Send + Sync + 'static {
+  /// Handling an rpc request.
+  fn handle_request(
+    &self,
+    _name: String,
+    _args: Vec<Value>,
+    _neovim: Neovim,
+  ) -> Result<Value, Value> {
+    Err(Value::from("Not implemented"))
+  }
+
+  /// Handling an rpc notification.
+  fn handle_notify(&self, _name: String, _args: Vec<Value>, _neovim: Neovim) {}
+}
+
+/// A handler that does nothing with notifications, and returns a generic
+/// error for requests.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Dummy;
+
+impl Handler for Dummy {}
+
+/// Runs a synchronous handler on the thread pool of [`blocking`].
+struct SyncHandler<H>(Arc<H>);
+
+impl<H> Clone for SyncHandler<H> {
+  fn clone(&self) -> Self {
+    SyncHandler(self.0.clone())
+  }
+}
+
+impl<H> crate::Handler for SyncHandler<H>
+where
+  H: Handler,
+{
+  type Writer = Writer;
+
+  async fn handle_request(
+    &self,
+    name: String,
+    args: Vec<Value>,
+    neovim: crate::Neovim<Writer>,
+  ) -> Result<Value, Value> {
+    let handler = self.0.clone();
+    let neovim = Neovim { inner: neovim };
+    unblock(move || handler.handle_request(name, args, neovim)).await
+  }
+
+  async fn handle_notify(
+    &self,
+    name: String,
+    args: Vec<Value>,
+    neovim: crate::Neovim<Writer>,
+  ) {
+    let handler = self.0.clone();
+    let neovim = Neovim { inner: neovim };
+    unblock(move || handler.handle_notify(name, args, neovim)).await;
+  }
+}
+
+/// A blocking neovim session. Cloning it is cheap, all clones use the same
+/// session.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Neovim {
+  pub(crate) inner: crate::Neovim<Writer>,
+}
+
+impl Neovim {
+  /// The async session underneath, e.g. to use it from an async context, or
+  /// to use functions that aren't available here.
+  #[must_use]
+  pub fn inner(&self) -> &crate::Neovim<Writer> {
+    &self.inner
+  }
+
+  /// See [`Neovim::call`](crate::neovim::Neovim::call).
+  pub fn call(
+    &self,
+    method: &str,
+    args: Vec<Value>,
+  ) -> Result<Result<Value, Value>, Box<CallError>> {
+    block_on(self.inner.call(method, args))
+  }
+
+  /// See
+  /// [`Neovim::send_notification`](crate::neovim::Neovim::send_notification).
+  pub fn send_notification(
+    &self,
+    method: &str,
+    args: Vec<Value>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.send_notification(method, args))
+  }
+
+  /// See [`Neovim::close`](crate::neovim::Neovim::close).
+  pub fn close(&self) {
+    block_on(self.inner.close());
+  }
+
+  /// See [`Neovim::ui_attach`](crate::neovim::Neovim::ui_attach).
+  pub fn ui_attach(
+    &self,
+    width: i64,
+    height: i64,
+    opts: &UiAttachOptions,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_attach(width, height, opts))
+  }
+
+  pub fn list_bufs(&self) -> Result<Vec<Buffer>, Box<CallError>> {
+    let bufs = block_on(self.inner.list_bufs())?;
+    Ok(bufs.into_iter().map(|inner| Buffer { inner }).collect())
+  }
+
+  pub fn get_current_buf(&self) -> Result<Buffer, Box<CallError>> {
+    let inner = block_on(self.inner.get_current_buf())?;
+    Ok(Buffer { inner })
+  }
+
+  pub fn list_wins(&self) -> Result<Vec<Window>, Box<CallError>> {
+    let wins = block_on(self.inner.list_wins())?;
+    Ok(wins.into_iter().map(|inner| Window { inner }).collect())
+  }
+
+  pub fn get_current_win(&self) -> Result<Window, Box<CallError>> {
+    let inner = block_on(self.inner.get_current_win())?;
+    Ok(Window { inner })
+  }
+
+  pub fn create_buf(
+    &self,
+    listed: bool,
+    scratch: bool,
+  ) -> Result<Buffer, Box<CallError>> {
+    let inner = block_on(self.inner.create_buf(listed, scratch))?;
+    Ok(Buffer { inner })
+  }
+
+  pub fn open_win(
+    &self,
+    buffer: &Buffer,
+    enter: bool,
+    config: Vec<(Value, Value)>,
+  ) -> Result<Window, Box<CallError>> {
+    let inner = block_on(self.inner.open_win(&buffer.inner, enter, config))?;
+    Ok(Window { inner })
+  }
+
+  pub fn list_tabpages(&self) -> Result<Vec<Tabpage>, Box<CallError>> {
+    let tabpages = block_on(self.inner.list_tabpages())?;
+    Ok(
+      tabpages
+        .into_iter()
+        .map(|inner| Tabpage { inner })
+        .collect(),
+    )
+  }
+
+  pub fn get_current_tabpage(&self) -> Result<Tabpage, Box<CallError>> {
+    let inner = block_on(self.inner.get_current_tabpage())?;
+    Ok(Tabpage { inner })
+  }
+}
+
+/// A neovim buffer, see [`Buffer`](crate::exttypes::Buffer).
+#[derive(Clone, PartialEq, Eq)]
+pub struct Buffer {
+  pub(crate) inner: crate::Buffer<Writer>,
+}
+
+impl Buffer {
+  /// The async buffer underneath.
+  #[must_use]
+  pub fn inner(&self) -> &crate::Buffer<Writer> {
+    &self.inner
+  }
+
+  /// Internal value, that represent type
+  #[must_use]
+  pub fn get_value(&self) -> &Value {
+    self.inner.get_value()
+  }
+}
+
+/// A neovim window, see [`Window`](crate::exttypes::Window).
+#[derive(Clone, PartialEq, Eq)]
+pub struct Window {
+  pub(crate) inner: crate::Window<Writer>,
+}
+
+impl Window {
+  /// The async window underneath.
+  #[must_use]
+  pub fn inner(&self) -> &crate::Window<Writer> {
+    &self.inner
+  }
+
+  /// Internal value, that represent type
+  #[must_use]
+  pub fn get_value(&self) -> &Value {
+    self.inner.get_value()
+  }
+
+  /// since: 1
+  pub fn get_buf(&self) -> Result<Buffer, Box<CallError>> {
+    let inner = block_on(self.inner.get_buf())?;
+    Ok(Buffer { inner })
+  }
+
+  /// since: 1
+  pub fn get_tabpage(&self) -> Result<Tabpage, Box<CallError>> {
+    let inner = block_on(self.inner.get_tabpage())?;
+    Ok(Tabpage { inner })
+  }
+}
+
+/// A neovim tabpage, see [`Tabpage`](crate::exttypes::Tabpage).
+#[derive(Clone, PartialEq, Eq)]
+pub struct Tabpage {
+  pub(crate) inner: crate::Tabpage<Writer>,
+}
+
+impl Tabpage {
+  /// The async tabpage underneath.
+  #[must_use]
+  pub fn inner(&self) -> &crate::Tabpage<Writer> {
+    &self.inner
+  }
+
+  /// Internal value, that represent type
+  #[must_use]
+  pub fn get_value(&self) -> &Value {
+    self.inner.get_value()
+  }
+
+  /// since: 1
+  pub fn list_wins(&self) -> Result<Vec<Window>, Box<CallError>> {
+    let wins = block_on(self.inner.list_wins())?;
+    Ok(wins.into_iter().map(|inner| Window { inner }).collect())
+  }
+
+  /// since: 1
+  pub fn get_win(&self) -> Result<Window, Box<CallError>> {
+    let inner = block_on(self.inner.get_win())?;
+    Ok(Window { inner })
+  }
+}
+
+/// The runtime thread of a session. It finishes when the session ends.
+pub struct IoHandle {
+  thread: JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+}
+
+impl IoHandle {
+  /// Wait for the session to end, and return how it ended.
+  pub fn join(self) -> Result<ShutdownReason, Box<LoopError>> {
+    match self.thread.join() {
+      Ok(res) => res,
+      Err(panic) => std::panic::resume_unwind(panic),
+    }
+  }
+
+  /// Whether the session has ended.
+  #[must_use]
+  pub fn is_finished(&self) -> bool {
+    self.thread.is_finished()
+  }
+}
+
+/// The tasks spawned by a session, to be run by [`run`].
+type Tasks = UnboundedReceiver<BoxFuture<'static, ()>>;
+
+/// Create a function spawning tasks by sending them to the returned receiver.
+fn spawning() -> (generic::SpawnFn, Tasks) {
+  let (sender, tasks) = unbounded();
+  let spawn = move |task| {
+    // This only fails if the runtime thread is gone, and the session with it
+    let _ = sender.unbounded_send(task);
+  };
+
+  (Arc::new(spawn), tasks)
+}
+
+/// Run the io future of a session, and the tasks it spawns, on a new thread.
+fn run<F>(io: F, mut tasks: Tasks) -> io::Result<IoHandle>
+where
+  F: Future<Output = Result<ShutdownReason, Box<LoopError>>> + Send + 'static,
+{
+  let thread =
+    thread::Builder::new()
+      .name("nvim-rs".to_owned())
+      .spawn(move || {
+        let mut pool = LocalPool::new();
+        let spawner = pool.spawner();
+        let receive = {
+          let spawner = spawner.clone();
+          async move {
+            while let Some(task) = tasks.next().await {
+              // This only fails once the pool is gone
+              let _ = spawner.spawn_local(task);
+            }
+          }
+        };
+        // Can't fail, the pool is still there
+        let _ = spawner.spawn_local(receive);
+        pool.run_until(io)
+      })?;
+
+  Ok(IoHandle { thread })
+}
+
+/// Start a session on `reader` and `writer`.
+fn start<R, W, H>(
+  reader: R,
+  writer: W,
+  handler: H,
+) -> io::Result<(Neovim, IoHandle)>
+where
+  R: AsyncRead + Send + Unpin + 'static,
+  W: AsyncWrite + Send + Unpin + 'static,
+  H: Handler,
+{
+  let (spawn, tasks) = spawning();
+  let (inner, io) = generic::new(
+    reader,
+    Box::new(writer) as Writer,
+    SyncHandler(Arc::new(handler)),
+    move |task| spawn(task),
+  );
+  let io_handle = run(io, tasks)?;
+
+  Ok((Neovim { inner }, io_handle))
+}
+
+/// Connect to a neovim instance via tcp
+pub fn new_tcp<A, H>(addr: A, handler: H) -> io::Result<(Neovim, IoHandle)>
+where
+  A: ToSocketAddrs,
+  H: Handler,
+{
+  let stream = Async::new(TcpStream::connect(addr)?)?;
+  let (reader, writer) = stream.split();
+
+  start(reader, writer, handler)
+}
+
+#[cfg(unix)]
+/// Connect to a neovim instance via unix socket by path. This is currently
+/// only available on Unix.
+pub fn new_path<P, H>(path: P, handler: H) -> io::Result<(Neovim, IoHandle)>
+where
+  P: AsRef<Path>,
+  H: Handler,
+{
+  let stream = Async::new(UnixStream::connect(path)?)?;
+  let (reader, writer) = stream.split();
+
+  start(reader, writer, handler)
+}
+
+/// Connect to the neovim instance that spawned this process over stdin/stdout
+pub fn new_parent<H>(handler: H) -> io::Result<(Neovim, IoHandle)>
+where
+  H: Handler,
+{
+  let sout = Unblock::new(unbuffered_stdout()?);
+  let sin = Unblock::new(io::stdin());
+
+  start(sin, sout, handler)
+}
+
+/// Connect to a neovim instance by spawning a new one
+pub fn new_child<H>(handler: H) -> io::Result<(Neovim, IoHandle, Child)>
+where
+  H: Handler,
+{
+  if cfg!(target_os = "windows") {
+    new_child_path("nvim.exe", handler)
+  } else {
+    new_child_path("nvim", handler)
+  }
+}
+
+/// Connect to a neovim instance by spawning a new one
+pub fn new_child_path<P, H>(
+  program: P,
+  handler: H,
+) -> io::Result<(Neovim, IoHandle, Child)>
+where
+  P: AsRef<Path>,
+  H: Handler,
+{
+  new_child_cmd(Command::new(program.as_ref()).arg("--embed"), handler)
+}
+
+/// Connect to a neovim instance by spawning a new one
+///
+/// stdin/stdout will be rewritten to `Stdio::piped()`
+pub fn new_child_cmd<H>(
+  cmd: &mut Command,
+  handler: H,
+) -> io::Result<(Neovim, IoHandle, Child)>
+where
+  H: Handler,
+{
+  let mut child = cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()?;
+  let (stdout, stdin) = child_pipes(&mut child)?;
+  let (neovim, io_handle) = start(stdout, stdin, handler)?;
+
+  Ok((neovim, io_handle, child))
+}
+
+/// Connect to a neovim instance by spawning a new one and send a handshake
+/// message, see [`Neovim::handshake`](crate::neovim::Neovim::handshake).
+pub fn new_child_handshake_cmd<H>(
+  cmd: &mut Command,
+  handler: H,
+  message: &str,
+) -> Result<(Neovim, IoHandle, Child, HandshakeInfo), Box<HandshakeError>>
+where
+  H: Handler,
+{
+  let mut child = cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()?;
+  let (stdout, stdin) = child_pipes(&mut child)?;
+
+  let (spawn, tasks) = spawning();
+  let (inner, io, info) =
+    block_on(crate::Neovim::<Writer>::handshake_spawning(
+      stdout,
+      Box::new(stdin) as Writer,
+      SyncHandler(Arc::new(handler)),
+      spawn,
+      message,
+      &SessionOptions::default(),
+    ))?;
+  let io_handle = run(io, tasks)?;
+
+  Ok((Neovim { inner }, io_handle, child, info))
+}
+
+/// Take the pipes to talk to a child spawned with piped stdin and stdout.
+fn child_pipes(
+  child: &mut Child,
+) -> io::Result<(
+  Unblock<std::process::ChildStdout>,
+  Unblock<std::process::ChildStdin>,
+)> {
+  let stdout = child
+    .stdout
+    .take()
+    .ok_or_else(|| io::Error::other("Can't open stdout"))?;
+  let stdin = child
+    .stdin
+    .take()
+    .ok_or_else(|| io::Error::other("Can't open stdin"))?;
+
+  Ok((Unblock::new(stdout), Unblock::new(stdin)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::{
+    net::TcpListener,
+    sync::{
+      mpsc::{channel, Sender},
+      Mutex,
+    },
+    time::Duration,
+  };
+
+  use crate::testing::fake::FakeNeovim;
+
+  /// Serve `fake` on a tcp port, and return the address.
+  fn serve(fake: &FakeNeovim) -> std::net::SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let fake = fake.clone();
+    thread::spawn(move || {
+      let (stream, _) = listener.accept().unwrap();
+      let (reader, writer) = Async::new(stream).unwrap().split();
+      block_on(fake.serve(reader, writer)).unwrap();
+    });
+
+    addr
+  }
+
+  #[test]
+  fn test_calls() {
+    let fake = FakeNeovim::new();
+    let buffer = fake.current_buffer();
+    fake.set_lines(buffer, &["one", "two"]);
+
+    let (nvim, io) = new_tcp(serve(&fake), Dummy).unwrap();
+
+    nvim.set_var("answer", Value::from(42)).unwrap();
+    assert_eq!(Some(Value::from(42)), fake.var("answer"));
+
+    let buf = nvim.get_current_buf().unwrap();
+    assert!(nvim.list_bufs().unwrap().contains(&buf));
+    assert_eq!(vec!["one", "two"], buf.get_lines(0, -1, false).unwrap());
+    buf.set_lines(0, 1, false, vec!["zero".into()]).unwrap();
+    assert_eq!(Some(vec!["zero".into(), "two".into()]), fake.lines(buffer));
+
+    let win = nvim.get_current_win().unwrap();
+    assert!(buf == win.get_buf().unwrap());
+
+    nvim.close();
+    assert!(io.join().is_ok());
+  }
+
+  /// Calls neovim from inside the handler, and reports the result.
+  struct Calling(Mutex<Sender<Value>>);
+
+  impl Handler for Calling {
+    fn handle_notify(&self, _name: String, _args: Vec<Value>, neovim: Neovim) {
+      let res = neovim.get_var("answer").unwrap();
+      self.0.lock().unwrap().send(res).unwrap();
+    }
+  }
+
+  #[test]
+  fn test_handler() {
+    let fake = FakeNeovim::new();
+    fake.set_var("answer", Value::from(42));
+
+    let (sender, results) = channel();
+    let handler = Calling(Mutex::new(sender));
+    let (nvim, _io) = new_tcp(serve(&fake), handler).unwrap();
+
+    // Let the fake send us a notification by attaching to a buffer
+    let buf = nvim.get_current_buf().unwrap();
+    assert!(buf.attach(false, vec![]).unwrap());
+    buf.set_lines(0, -1, false, vec!["x".into()]).unwrap();
+
+    let res = results.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(Value::from(42), res);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_child_handshake() {
+    // Answer the handshake with the message "hi", and channel id 5 along
+    // with empty api metadata
+    let responses = r"\224\001\000\300\242hi\224\001\001\300\222\005\200";
+    let (nvim, io, mut child, info) = new_child_handshake_cmd(
+      Command::new("/bin/sh")
+        .args(["-c", &format!("echo noise; printf '{responses}'; sleep 1")]),
+      Dummy,
+      "hi",
+    )
+    .unwrap();
+
+    assert_eq!(5, info.channel_id);
+    assert_eq!(Value::Map(vec![]), info.api_info);
+
+    nvim.close();
+    assert!(io.join().is_ok());
+    child.wait().unwrap();
+  }
+}
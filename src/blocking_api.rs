@@ -0,0 +1,2185 @@
+//! The auto generated API for [`blocking::Neovim`](crate::blocking::Neovim)
+//!
+//! Every function blocks the calling thread until the corresponding function
+//! of [`neovim_api`](crate::neovim_api) has finished.
+//!
+//! Auto generated 2026-10-18 10:35:58.698653
+use futures::executor::block_on;
+
+use crate::{
+  blocking::{Buffer, Neovim, Tabpage, Window},
+  error::CallError,
+  rpc::*,
+};
+
+impl Buffer {
+  /// since: 1
+  pub fn line_count(&self) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.line_count())
+  }
+  /// since: 1
+  pub fn line_count_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.line_count_notify())
+  }
+  /// since: 4
+  pub fn attach(
+    &self,
+    send_buffer: bool,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<bool, Box<CallError>> {
+    block_on(self.inner.attach(send_buffer, opts))
+  }
+  /// since: 4
+  pub fn attach_notify(
+    &self,
+    send_buffer: bool,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.attach_notify(send_buffer, opts))
+  }
+  /// since: 4
+  pub fn detach(&self) -> Result<bool, Box<CallError>> {
+    block_on(self.inner.detach())
+  }
+  /// since: 4
+  pub fn detach_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.detach_notify())
+  }
+  /// since: 1
+  pub fn get_lines(
+    &self,
+    start: i64,
+    end: i64,
+    strict_indexing: bool,
+  ) -> Result<Vec<String>, Box<CallError>> {
+    block_on(self.inner.get_lines(start, end, strict_indexing))
+  }
+  /// since: 1
+  pub fn get_lines_notify(
+    &self,
+    start: i64,
+    end: i64,
+    strict_indexing: bool,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_lines_notify(start, end, strict_indexing))
+  }
+  /// since: 1
+  pub fn set_lines(
+    &self,
+    start: i64,
+    end: i64,
+    strict_indexing: bool,
+    replacement: Vec<String>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .set_lines(start, end, strict_indexing, replacement),
+    )
+  }
+  /// since: 1
+  pub fn set_lines_notify(
+    &self,
+    start: i64,
+    end: i64,
+    strict_indexing: bool,
+    replacement: Vec<String>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_lines_notify(
+      start,
+      end,
+      strict_indexing,
+      replacement,
+    ))
+  }
+  /// since: 7
+  pub fn set_text(
+    &self,
+    start_row: i64,
+    start_col: i64,
+    end_row: i64,
+    end_col: i64,
+    replacement: Vec<String>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_text(
+      start_row,
+      start_col,
+      end_row,
+      end_col,
+      replacement,
+    ))
+  }
+  /// since: 7
+  pub fn set_text_notify(
+    &self,
+    start_row: i64,
+    start_col: i64,
+    end_row: i64,
+    end_col: i64,
+    replacement: Vec<String>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_text_notify(
+      start_row,
+      start_col,
+      end_row,
+      end_col,
+      replacement,
+    ))
+  }
+  /// since: 9
+  pub fn get_text(
+    &self,
+    start_row: i64,
+    start_col: i64,
+    end_row: i64,
+    end_col: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<String>, Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .get_text(start_row, start_col, end_row, end_col, opts),
+    )
+  }
+  /// since: 9
+  pub fn get_text_notify(
+    &self,
+    start_row: i64,
+    start_col: i64,
+    end_row: i64,
+    end_col: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .get_text_notify(start_row, start_col, end_row, end_col, opts),
+    )
+  }
+  /// since: 5
+  pub fn get_offset(&self, index: i64) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.get_offset(index))
+  }
+  /// since: 5
+  pub fn get_offset_notify(&self, index: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_offset_notify(index))
+  }
+  /// since: 1
+  pub fn get_var(&self, name: &str) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.get_var(name))
+  }
+  /// since: 1
+  pub fn get_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_var_notify(name))
+  }
+  /// since: 2
+  pub fn get_changedtick(&self) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.get_changedtick())
+  }
+  /// since: 2
+  pub fn get_changedtick_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_changedtick_notify())
+  }
+  /// since: 3
+  pub fn get_keymap(
+    &self,
+    mode: &str,
+  ) -> Result<Vec<Vec<(Value, Value)>>, Box<CallError>> {
+    block_on(self.inner.get_keymap(mode))
+  }
+  /// since: 3
+  pub fn get_keymap_notify(&self, mode: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_keymap_notify(mode))
+  }
+  /// since: 6
+  pub fn set_keymap(
+    &self,
+    mode: &str,
+    lhs: &str,
+    rhs: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_keymap(mode, lhs, rhs, opts))
+  }
+  /// since: 6
+  pub fn set_keymap_notify(
+    &self,
+    mode: &str,
+    lhs: &str,
+    rhs: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_keymap_notify(mode, lhs, rhs, opts))
+  }
+  /// since: 6
+  pub fn del_keymap(
+    &self,
+    mode: &str,
+    lhs: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_keymap(mode, lhs))
+  }
+  /// since: 6
+  pub fn del_keymap_notify(
+    &self,
+    mode: &str,
+    lhs: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_keymap_notify(mode, lhs))
+  }
+  /// since: 1
+  pub fn set_var(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_var(name, value))
+  }
+  /// since: 1
+  pub fn set_var_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_var_notify(name, value))
+  }
+  /// since: 1
+  pub fn del_var(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_var(name))
+  }
+  /// since: 1
+  pub fn del_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_var_notify(name))
+  }
+  /// since: 1
+  pub fn get_name(&self) -> Result<String, Box<CallError>> {
+    block_on(self.inner.get_name())
+  }
+  /// since: 1
+  pub fn get_name_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_name_notify())
+  }
+  /// since: 1
+  pub fn set_name(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_name(name))
+  }
+  /// since: 1
+  pub fn set_name_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_name_notify(name))
+  }
+  /// since: 5
+  pub fn is_loaded(&self) -> Result<bool, Box<CallError>> {
+    block_on(self.inner.is_loaded())
+  }
+  /// since: 5
+  pub fn is_loaded_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.is_loaded_notify())
+  }
+  /// since: 7
+  pub fn delete(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.delete(opts))
+  }
+  /// since: 7
+  pub fn delete_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.delete_notify(opts))
+  }
+  /// since: 1
+  pub fn is_valid(&self) -> Result<bool, Box<CallError>> {
+    block_on(self.inner.is_valid())
+  }
+  /// since: 1
+  pub fn is_valid_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.is_valid_notify())
+  }
+  /// since: 8
+  pub fn del_mark(&self, name: &str) -> Result<bool, Box<CallError>> {
+    block_on(self.inner.del_mark(name))
+  }
+  /// since: 8
+  pub fn del_mark_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_mark_notify(name))
+  }
+  /// since: 8
+  pub fn set_mark(
+    &self,
+    name: &str,
+    line: i64,
+    col: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<bool, Box<CallError>> {
+    block_on(self.inner.set_mark(name, line, col, opts))
+  }
+  /// since: 8
+  pub fn set_mark_notify(
+    &self,
+    name: &str,
+    line: i64,
+    col: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_mark_notify(name, line, col, opts))
+  }
+  /// since: 1
+  pub fn get_mark(&self, name: &str) -> Result<(i64, i64), Box<CallError>> {
+    block_on(self.inner.get_mark(name))
+  }
+  /// since: 1
+  pub fn get_mark_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_mark_notify(name))
+  }
+  /// since: 9
+  pub fn create_user_command(
+    &self,
+    name: &str,
+    command: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.create_user_command(name, command, opts))
+  }
+  /// since: 9
+  pub fn create_user_command_notify(
+    &self,
+    name: &str,
+    command: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.create_user_command_notify(name, command, opts))
+  }
+  /// since: 9
+  pub fn del_user_command(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_user_command(name))
+  }
+  /// since: 9
+  pub fn del_user_command_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_user_command_notify(name))
+  }
+  /// since: 4
+  pub fn get_commands(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_commands(opts))
+  }
+  /// since: 4
+  pub fn get_commands_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_commands_notify(opts))
+  }
+  /// since: 1
+  pub fn get_number(&self) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.get_number())
+  }
+  /// since: 1
+  pub fn get_number_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_number_notify())
+  }
+  /// since: 1
+  pub fn clear_highlight(
+    &self,
+    ns_id: i64,
+    line_start: i64,
+    line_end: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.clear_highlight(ns_id, line_start, line_end))
+  }
+  /// since: 1
+  pub fn clear_highlight_notify(
+    &self,
+    ns_id: i64,
+    line_start: i64,
+    line_end: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .clear_highlight_notify(ns_id, line_start, line_end),
+    )
+  }
+  /// since: 1
+  pub fn add_highlight(
+    &self,
+    ns_id: i64,
+    hl_group: &str,
+    line: i64,
+    col_start: i64,
+    col_end: i64,
+  ) -> Result<i64, Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .add_highlight(ns_id, hl_group, line, col_start, col_end),
+    )
+  }
+  /// since: 1
+  pub fn add_highlight_notify(
+    &self,
+    ns_id: i64,
+    hl_group: &str,
+    line: i64,
+    col_start: i64,
+    col_end: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .add_highlight_notify(ns_id, hl_group, line, col_start, col_end),
+    )
+  }
+  /// since: 5
+  pub fn set_virtual_text(
+    &self,
+    src_id: i64,
+    line: i64,
+    chunks: Vec<Value>,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.set_virtual_text(src_id, line, chunks, opts))
+  }
+  /// since: 5
+  pub fn set_virtual_text_notify(
+    &self,
+    src_id: i64,
+    line: i64,
+    chunks: Vec<Value>,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .set_virtual_text_notify(src_id, line, chunks, opts),
+    )
+  }
+  /// since: 1
+  pub fn get_option(&self, name: &str) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.get_option(name))
+  }
+  /// since: 1
+  pub fn get_option_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_option_notify(name))
+  }
+  /// since: 1
+  pub fn set_option(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_option(name, value))
+  }
+  /// since: 1
+  pub fn set_option_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_option_notify(name, value))
+  }
+  /// since: 7
+  pub fn get_extmark_by_id(
+    &self,
+    ns_id: i64,
+    id: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<i64>, Box<CallError>> {
+    block_on(self.inner.get_extmark_by_id(ns_id, id, opts))
+  }
+  /// since: 7
+  pub fn get_extmark_by_id_notify(
+    &self,
+    ns_id: i64,
+    id: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_extmark_by_id_notify(ns_id, id, opts))
+  }
+  /// since: 7
+  pub fn get_extmarks(
+    &self,
+    ns_id: i64,
+    start: Value,
+    end: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<Value>, Box<CallError>> {
+    block_on(self.inner.get_extmarks(ns_id, start, end, opts))
+  }
+  /// since: 7
+  pub fn get_extmarks_notify(
+    &self,
+    ns_id: i64,
+    start: Value,
+    end: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_extmarks_notify(ns_id, start, end, opts))
+  }
+  /// since: 7
+  pub fn set_extmark(
+    &self,
+    ns_id: i64,
+    line: i64,
+    col: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.set_extmark(ns_id, line, col, opts))
+  }
+  /// since: 7
+  pub fn set_extmark_notify(
+    &self,
+    ns_id: i64,
+    line: i64,
+    col: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_extmark_notify(ns_id, line, col, opts))
+  }
+  /// since: 7
+  pub fn del_extmark(
+    &self,
+    ns_id: i64,
+    id: i64,
+  ) -> Result<bool, Box<CallError>> {
+    block_on(self.inner.del_extmark(ns_id, id))
+  }
+  /// since: 7
+  pub fn del_extmark_notify(
+    &self,
+    ns_id: i64,
+    id: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_extmark_notify(ns_id, id))
+  }
+  /// since: 5
+  pub fn clear_namespace(
+    &self,
+    ns_id: i64,
+    line_start: i64,
+    line_end: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.clear_namespace(ns_id, line_start, line_end))
+  }
+  /// since: 5
+  pub fn clear_namespace_notify(
+    &self,
+    ns_id: i64,
+    line_start: i64,
+    line_end: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .clear_namespace_notify(ns_id, line_start, line_end),
+    )
+  }
+}
+
+impl Window {
+  /// since: 1
+  pub fn get_option(&self, name: &str) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.get_option(name))
+  }
+  /// since: 1
+  pub fn get_option_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_option_notify(name))
+  }
+  /// since: 1
+  pub fn set_option(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_option(name, value))
+  }
+  /// since: 1
+  pub fn set_option_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_option_notify(name, value))
+  }
+  /// since: 6
+  pub fn set_config(
+    &self,
+    config: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_config(config))
+  }
+  /// since: 6
+  pub fn set_config_notify(
+    &self,
+    config: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_config_notify(config))
+  }
+  /// since: 6
+  pub fn get_config(&self) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_config())
+  }
+  /// since: 6
+  pub fn get_config_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_config_notify())
+  }
+  /// since: 5
+  pub fn set_buf(&self, buffer: &Buffer) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_buf(&buffer.inner))
+  }
+  /// since: 5
+  pub fn set_buf_notify(&self, buffer: &Buffer) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_buf_notify(&buffer.inner))
+  }
+  /// since: 1
+  pub fn get_cursor(&self) -> Result<(i64, i64), Box<CallError>> {
+    block_on(self.inner.get_cursor())
+  }
+  /// since: 1
+  pub fn get_cursor_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_cursor_notify())
+  }
+  /// since: 1
+  pub fn set_cursor(&self, pos: (i64, i64)) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_cursor(pos))
+  }
+  /// since: 1
+  pub fn set_cursor_notify(
+    &self,
+    pos: (i64, i64),
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_cursor_notify(pos))
+  }
+  /// since: 1
+  pub fn get_height(&self) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.get_height())
+  }
+  /// since: 1
+  pub fn get_height_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_height_notify())
+  }
+  /// since: 1
+  pub fn set_height(&self, height: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_height(height))
+  }
+  /// since: 1
+  pub fn set_height_notify(&self, height: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_height_notify(height))
+  }
+  /// since: 1
+  pub fn get_width(&self) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.get_width())
+  }
+  /// since: 1
+  pub fn get_width_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_width_notify())
+  }
+  /// since: 1
+  pub fn set_width(&self, width: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_width(width))
+  }
+  /// since: 1
+  pub fn set_width_notify(&self, width: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_width_notify(width))
+  }
+  /// since: 1
+  pub fn get_var(&self, name: &str) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.get_var(name))
+  }
+  /// since: 1
+  pub fn get_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_var_notify(name))
+  }
+  /// since: 1
+  pub fn set_var(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_var(name, value))
+  }
+  /// since: 1
+  pub fn set_var_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_var_notify(name, value))
+  }
+  /// since: 1
+  pub fn del_var(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_var(name))
+  }
+  /// since: 1
+  pub fn del_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_var_notify(name))
+  }
+  /// since: 1
+  pub fn get_position(&self) -> Result<(i64, i64), Box<CallError>> {
+    block_on(self.inner.get_position())
+  }
+  /// since: 1
+  pub fn get_position_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_position_notify())
+  }
+  /// since: 1
+  pub fn get_number(&self) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.get_number())
+  }
+  /// since: 1
+  pub fn get_number_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_number_notify())
+  }
+  /// since: 1
+  pub fn is_valid(&self) -> Result<bool, Box<CallError>> {
+    block_on(self.inner.is_valid())
+  }
+  /// since: 1
+  pub fn is_valid_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.is_valid_notify())
+  }
+  /// since: 7
+  pub fn hide(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.hide())
+  }
+  /// since: 7
+  pub fn hide_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.hide_notify())
+  }
+  /// since: 6
+  pub fn close(&self, force: bool) -> Result<(), Box<CallError>> {
+    block_on(self.inner.close(force))
+  }
+  /// since: 6
+  pub fn close_notify(&self, force: bool) -> Result<(), Box<CallError>> {
+    block_on(self.inner.close_notify(force))
+  }
+  /// since: 10
+  pub fn set_hl_ns(&self, ns_id: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_hl_ns(ns_id))
+  }
+  /// since: 10
+  pub fn set_hl_ns_notify(&self, ns_id: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_hl_ns_notify(ns_id))
+  }
+  /// since: 12
+  pub fn text_height(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.text_height(opts))
+  }
+  /// since: 12
+  pub fn text_height_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.text_height_notify(opts))
+  }
+}
+
+impl Tabpage {
+  /// since: 1
+  pub fn get_var(&self, name: &str) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.get_var(name))
+  }
+  /// since: 1
+  pub fn get_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_var_notify(name))
+  }
+  /// since: 1
+  pub fn set_var(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_var(name, value))
+  }
+  /// since: 1
+  pub fn set_var_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_var_notify(name, value))
+  }
+  /// since: 1
+  pub fn del_var(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_var(name))
+  }
+  /// since: 1
+  pub fn del_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_var_notify(name))
+  }
+  /// since: 12
+  pub fn set_win(&self, win: &Window) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_win(&win.inner))
+  }
+  /// since: 12
+  pub fn set_win_notify(&self, win: &Window) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_win_notify(&win.inner))
+  }
+  /// since: 1
+  pub fn get_number(&self) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.get_number())
+  }
+  /// since: 1
+  pub fn get_number_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_number_notify())
+  }
+  /// since: 1
+  pub fn is_valid(&self) -> Result<bool, Box<CallError>> {
+    block_on(self.inner.is_valid())
+  }
+  /// since: 1
+  pub fn is_valid_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.is_valid_notify())
+  }
+}
+
+impl Neovim {
+  pub fn get_autocmds(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<Value>, Box<CallError>> {
+    block_on(self.inner.get_autocmds(opts))
+  }
+
+  pub fn get_autocmds_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_autocmds_notify(opts))
+  }
+
+  pub fn create_autocmd(
+    &self,
+    event: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.create_autocmd(event, opts))
+  }
+
+  pub fn create_autocmd_notify(
+    &self,
+    event: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.create_autocmd_notify(event, opts))
+  }
+
+  pub fn del_autocmd(&self, id: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_autocmd(id))
+  }
+
+  pub fn del_autocmd_notify(&self, id: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_autocmd_notify(id))
+  }
+
+  pub fn clear_autocmds(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.clear_autocmds(opts))
+  }
+
+  pub fn clear_autocmds_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.clear_autocmds_notify(opts))
+  }
+
+  pub fn create_augroup(
+    &self,
+    name: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.create_augroup(name, opts))
+  }
+
+  pub fn create_augroup_notify(
+    &self,
+    name: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.create_augroup_notify(name, opts))
+  }
+
+  pub fn del_augroup_by_id(&self, id: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_augroup_by_id(id))
+  }
+
+  pub fn del_augroup_by_id_notify(
+    &self,
+    id: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_augroup_by_id_notify(id))
+  }
+
+  pub fn del_augroup_by_name(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_augroup_by_name(name))
+  }
+
+  pub fn del_augroup_by_name_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_augroup_by_name_notify(name))
+  }
+
+  pub fn exec_autocmds(
+    &self,
+    event: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.exec_autocmds(event, opts))
+  }
+
+  pub fn exec_autocmds_notify(
+    &self,
+    event: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.exec_autocmds_notify(event, opts))
+  }
+
+  pub fn parse_cmd(
+    &self,
+    str: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.parse_cmd(str, opts))
+  }
+
+  pub fn parse_cmd_notify(
+    &self,
+    str: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.parse_cmd_notify(str, opts))
+  }
+
+  pub fn cmd(
+    &self,
+    cmd: Vec<(Value, Value)>,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<String, Box<CallError>> {
+    block_on(self.inner.cmd(cmd, opts))
+  }
+
+  pub fn cmd_notify(
+    &self,
+    cmd: Vec<(Value, Value)>,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.cmd_notify(cmd, opts))
+  }
+
+  pub fn create_user_command(
+    &self,
+    name: &str,
+    command: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.create_user_command(name, command, opts))
+  }
+
+  pub fn create_user_command_notify(
+    &self,
+    name: &str,
+    command: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.create_user_command_notify(name, command, opts))
+  }
+
+  pub fn del_user_command(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_user_command(name))
+  }
+
+  pub fn del_user_command_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_user_command_notify(name))
+  }
+
+  pub fn get_commands(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_commands(opts))
+  }
+
+  pub fn get_commands_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_commands_notify(opts))
+  }
+
+  pub fn exec(
+    &self,
+    src: &str,
+    output: bool,
+  ) -> Result<String, Box<CallError>> {
+    block_on(self.inner.exec(src, output))
+  }
+
+  pub fn exec_notify(
+    &self,
+    src: &str,
+    output: bool,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.exec_notify(src, output))
+  }
+
+  pub fn command_output(
+    &self,
+    command: &str,
+  ) -> Result<String, Box<CallError>> {
+    block_on(self.inner.command_output(command))
+  }
+
+  pub fn command_output_notify(
+    &self,
+    command: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.command_output_notify(command))
+  }
+
+  pub fn execute_lua(
+    &self,
+    code: &str,
+    args: Vec<Value>,
+  ) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.execute_lua(code, args))
+  }
+
+  pub fn execute_lua_notify(
+    &self,
+    code: &str,
+    args: Vec<Value>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.execute_lua_notify(code, args))
+  }
+
+  pub fn get_hl_by_id(
+    &self,
+    hl_id: i64,
+    rgb: bool,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_hl_by_id(hl_id, rgb))
+  }
+
+  pub fn get_hl_by_id_notify(
+    &self,
+    hl_id: i64,
+    rgb: bool,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_hl_by_id_notify(hl_id, rgb))
+  }
+
+  pub fn get_hl_by_name(
+    &self,
+    name: &str,
+    rgb: bool,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_hl_by_name(name, rgb))
+  }
+
+  pub fn get_hl_by_name_notify(
+    &self,
+    name: &str,
+    rgb: bool,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_hl_by_name_notify(name, rgb))
+  }
+
+  pub fn get_option_info(
+    &self,
+    name: &str,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_option_info(name))
+  }
+
+  pub fn get_option_info_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_option_info_notify(name))
+  }
+
+  pub fn set_option(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_option(name, value))
+  }
+
+  pub fn set_option_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_option_notify(name, value))
+  }
+
+  pub fn get_option(&self, name: &str) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.get_option(name))
+  }
+
+  pub fn get_option_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_option_notify(name))
+  }
+
+  pub fn call_atomic(
+    &self,
+    calls: Vec<Value>,
+  ) -> Result<Vec<Value>, Box<CallError>> {
+    block_on(self.inner.call_atomic(calls))
+  }
+
+  pub fn call_atomic_notify(
+    &self,
+    calls: Vec<Value>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.call_atomic_notify(calls))
+  }
+
+  pub fn subscribe(&self, event: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.subscribe(event))
+  }
+
+  pub fn subscribe_notify(&self, event: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.subscribe_notify(event))
+  }
+
+  pub fn unsubscribe(&self, event: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.unsubscribe(event))
+  }
+
+  pub fn unsubscribe_notify(&self, event: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.unsubscribe_notify(event))
+  }
+
+  pub fn out_write(&self, str: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.out_write(str))
+  }
+
+  pub fn out_write_notify(&self, str: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.out_write_notify(str))
+  }
+
+  pub fn err_write(&self, str: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.err_write(str))
+  }
+
+  pub fn err_write_notify(&self, str: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.err_write_notify(str))
+  }
+
+  pub fn err_writeln(&self, str: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.err_writeln(str))
+  }
+
+  pub fn err_writeln_notify(&self, str: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.err_writeln_notify(str))
+  }
+
+  pub fn notify(
+    &self,
+    msg: &str,
+    log_level: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.notify(msg, log_level, opts))
+  }
+
+  pub fn notify_notify(
+    &self,
+    msg: &str,
+    log_level: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.notify_notify(msg, log_level, opts))
+  }
+
+  pub fn create_namespace(&self, name: &str) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.create_namespace(name))
+  }
+
+  pub fn create_namespace_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.create_namespace_notify(name))
+  }
+
+  pub fn get_namespaces(&self) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_namespaces())
+  }
+
+  pub fn get_namespaces_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_namespaces_notify())
+  }
+
+  pub fn set_decoration_provider(
+    &self,
+    ns_id: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_decoration_provider(ns_id, opts))
+  }
+
+  pub fn set_decoration_provider_notify(
+    &self,
+    ns_id: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_decoration_provider_notify(ns_id, opts))
+  }
+
+  pub fn get_option_value(
+    &self,
+    name: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.get_option_value(name, opts))
+  }
+
+  pub fn get_option_value_notify(
+    &self,
+    name: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_option_value_notify(name, opts))
+  }
+
+  pub fn set_option_value(
+    &self,
+    name: &str,
+    value: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_option_value(name, value, opts))
+  }
+
+  pub fn set_option_value_notify(
+    &self,
+    name: &str,
+    value: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_option_value_notify(name, value, opts))
+  }
+
+  pub fn get_all_options_info(
+    &self,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_all_options_info())
+  }
+
+  pub fn get_all_options_info_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_all_options_info_notify())
+  }
+
+  pub fn get_option_info2(
+    &self,
+    name: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_option_info2(name, opts))
+  }
+
+  pub fn get_option_info2_notify(
+    &self,
+    name: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_option_info2_notify(name, opts))
+  }
+
+  pub fn ui_set_focus(&self, gained: bool) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_set_focus(gained))
+  }
+
+  pub fn ui_set_focus_notify(
+    &self,
+    gained: bool,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_set_focus_notify(gained))
+  }
+
+  pub fn ui_detach(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_detach())
+  }
+
+  pub fn ui_detach_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_detach_notify())
+  }
+
+  pub fn ui_try_resize(
+    &self,
+    width: i64,
+    height: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_try_resize(width, height))
+  }
+
+  pub fn ui_try_resize_notify(
+    &self,
+    width: i64,
+    height: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_try_resize_notify(width, height))
+  }
+
+  pub fn ui_set_option(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_set_option(name, value))
+  }
+
+  pub fn ui_set_option_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_set_option_notify(name, value))
+  }
+
+  pub fn ui_try_resize_grid(
+    &self,
+    grid: i64,
+    width: i64,
+    height: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_try_resize_grid(grid, width, height))
+  }
+
+  pub fn ui_try_resize_grid_notify(
+    &self,
+    grid: i64,
+    width: i64,
+    height: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_try_resize_grid_notify(grid, width, height))
+  }
+
+  pub fn ui_pum_set_height(&self, height: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_pum_set_height(height))
+  }
+
+  pub fn ui_pum_set_height_notify(
+    &self,
+    height: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_pum_set_height_notify(height))
+  }
+
+  pub fn ui_pum_set_bounds(
+    &self,
+    width: f64,
+    height: f64,
+    row: f64,
+    col: f64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_pum_set_bounds(width, height, row, col))
+  }
+
+  pub fn ui_pum_set_bounds_notify(
+    &self,
+    width: f64,
+    height: f64,
+    row: f64,
+    col: f64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_pum_set_bounds_notify(width, height, row, col))
+  }
+
+  pub fn ui_term_event(
+    &self,
+    event: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_term_event(event, value))
+  }
+
+  pub fn ui_term_event_notify(
+    &self,
+    event: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.ui_term_event_notify(event, value))
+  }
+
+  pub fn get_hl_id_by_name(&self, name: &str) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.get_hl_id_by_name(name))
+  }
+
+  pub fn get_hl_id_by_name_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_hl_id_by_name_notify(name))
+  }
+
+  pub fn get_hl(
+    &self,
+    ns_id: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_hl(ns_id, opts))
+  }
+
+  pub fn get_hl_notify(
+    &self,
+    ns_id: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_hl_notify(ns_id, opts))
+  }
+
+  pub fn set_hl(
+    &self,
+    ns_id: i64,
+    name: &str,
+    val: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_hl(ns_id, name, val))
+  }
+
+  pub fn set_hl_notify(
+    &self,
+    ns_id: i64,
+    name: &str,
+    val: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_hl_notify(ns_id, name, val))
+  }
+
+  pub fn get_hl_ns(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.get_hl_ns(opts))
+  }
+
+  pub fn get_hl_ns_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_hl_ns_notify(opts))
+  }
+
+  pub fn set_hl_ns(&self, ns_id: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_hl_ns(ns_id))
+  }
+
+  pub fn set_hl_ns_notify(&self, ns_id: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_hl_ns_notify(ns_id))
+  }
+
+  pub fn set_hl_ns_fast(&self, ns_id: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_hl_ns_fast(ns_id))
+  }
+
+  pub fn set_hl_ns_fast_notify(
+    &self,
+    ns_id: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_hl_ns_fast_notify(ns_id))
+  }
+
+  pub fn feedkeys(
+    &self,
+    keys: &str,
+    mode: &str,
+    escape_ks: bool,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.feedkeys(keys, mode, escape_ks))
+  }
+
+  pub fn feedkeys_notify(
+    &self,
+    keys: &str,
+    mode: &str,
+    escape_ks: bool,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.feedkeys_notify(keys, mode, escape_ks))
+  }
+
+  pub fn input(&self, keys: &str) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.input(keys))
+  }
+
+  pub fn input_notify(&self, keys: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.input_notify(keys))
+  }
+
+  pub fn input_mouse(
+    &self,
+    button: &str,
+    action: &str,
+    modifier: &str,
+    grid: i64,
+    row: i64,
+    col: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .input_mouse(button, action, modifier, grid, row, col),
+    )
+  }
+
+  pub fn input_mouse_notify(
+    &self,
+    button: &str,
+    action: &str,
+    modifier: &str,
+    grid: i64,
+    row: i64,
+    col: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .input_mouse_notify(button, action, modifier, grid, row, col),
+    )
+  }
+
+  pub fn replace_termcodes(
+    &self,
+    str: &str,
+    from_part: bool,
+    do_lt: bool,
+    special: bool,
+  ) -> Result<String, Box<CallError>> {
+    block_on(self.inner.replace_termcodes(str, from_part, do_lt, special))
+  }
+
+  pub fn replace_termcodes_notify(
+    &self,
+    str: &str,
+    from_part: bool,
+    do_lt: bool,
+    special: bool,
+  ) -> Result<(), Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .replace_termcodes_notify(str, from_part, do_lt, special),
+    )
+  }
+
+  pub fn exec_lua(
+    &self,
+    code: &str,
+    args: Vec<Value>,
+  ) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.exec_lua(code, args))
+  }
+
+  pub fn exec_lua_notify(
+    &self,
+    code: &str,
+    args: Vec<Value>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.exec_lua_notify(code, args))
+  }
+
+  pub fn strwidth(&self, text: &str) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.strwidth(text))
+  }
+
+  pub fn strwidth_notify(&self, text: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.strwidth_notify(text))
+  }
+
+  pub fn list_runtime_paths(&self) -> Result<Vec<String>, Box<CallError>> {
+    block_on(self.inner.list_runtime_paths())
+  }
+
+  pub fn list_runtime_paths_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.list_runtime_paths_notify())
+  }
+
+  pub fn get_runtime_file(
+    &self,
+    name: &str,
+    all: bool,
+  ) -> Result<Vec<String>, Box<CallError>> {
+    block_on(self.inner.get_runtime_file(name, all))
+  }
+
+  pub fn get_runtime_file_notify(
+    &self,
+    name: &str,
+    all: bool,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_runtime_file_notify(name, all))
+  }
+
+  pub fn set_current_dir(&self, dir: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_current_dir(dir))
+  }
+
+  pub fn set_current_dir_notify(
+    &self,
+    dir: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_current_dir_notify(dir))
+  }
+
+  pub fn get_current_line(&self) -> Result<String, Box<CallError>> {
+    block_on(self.inner.get_current_line())
+  }
+
+  pub fn get_current_line_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_current_line_notify())
+  }
+
+  pub fn set_current_line(&self, line: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_current_line(line))
+  }
+
+  pub fn set_current_line_notify(
+    &self,
+    line: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_current_line_notify(line))
+  }
+
+  pub fn del_current_line(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_current_line())
+  }
+
+  pub fn del_current_line_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_current_line_notify())
+  }
+
+  pub fn get_var(&self, name: &str) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.get_var(name))
+  }
+
+  pub fn get_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_var_notify(name))
+  }
+
+  pub fn set_var(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_var(name, value))
+  }
+
+  pub fn set_var_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_var_notify(name, value))
+  }
+
+  pub fn del_var(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_var(name))
+  }
+
+  pub fn del_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_var_notify(name))
+  }
+
+  pub fn get_vvar(&self, name: &str) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.get_vvar(name))
+  }
+
+  pub fn get_vvar_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_vvar_notify(name))
+  }
+
+  pub fn set_vvar(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_vvar(name, value))
+  }
+
+  pub fn set_vvar_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_vvar_notify(name, value))
+  }
+
+  pub fn echo(
+    &self,
+    chunks: Vec<Value>,
+    history: bool,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.echo(chunks, history, opts))
+  }
+
+  pub fn echo_notify(
+    &self,
+    chunks: Vec<Value>,
+    history: bool,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.echo_notify(chunks, history, opts))
+  }
+
+  pub fn set_current_buf(&self, buffer: &Buffer) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_current_buf(&buffer.inner))
+  }
+
+  pub fn set_current_buf_notify(
+    &self,
+    buffer: &Buffer,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_current_buf_notify(&buffer.inner))
+  }
+
+  pub fn set_current_win(&self, window: &Window) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_current_win(&window.inner))
+  }
+
+  pub fn set_current_win_notify(
+    &self,
+    window: &Window,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_current_win_notify(&window.inner))
+  }
+
+  pub fn open_term(
+    &self,
+    buffer: &Buffer,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.open_term(&buffer.inner, opts))
+  }
+
+  pub fn open_term_notify(
+    &self,
+    buffer: &Buffer,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.open_term_notify(&buffer.inner, opts))
+  }
+
+  pub fn chan_send(&self, chan: i64, data: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.chan_send(chan, data))
+  }
+
+  pub fn chan_send_notify(
+    &self,
+    chan: i64,
+    data: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.chan_send_notify(chan, data))
+  }
+
+  pub fn set_current_tabpage(
+    &self,
+    tabpage: &Tabpage,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_current_tabpage(&tabpage.inner))
+  }
+
+  pub fn set_current_tabpage_notify(
+    &self,
+    tabpage: &Tabpage,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_current_tabpage_notify(&tabpage.inner))
+  }
+
+  pub fn paste(
+    &self,
+    data: &str,
+    crlf: bool,
+    phase: i64,
+  ) -> Result<bool, Box<CallError>> {
+    block_on(self.inner.paste(data, crlf, phase))
+  }
+
+  pub fn paste_notify(
+    &self,
+    data: &str,
+    crlf: bool,
+    phase: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.paste_notify(data, crlf, phase))
+  }
+
+  pub fn put(
+    &self,
+    lines: Vec<String>,
+    typ: &str,
+    after: bool,
+    follow: bool,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.put(lines, typ, after, follow))
+  }
+
+  pub fn put_notify(
+    &self,
+    lines: Vec<String>,
+    typ: &str,
+    after: bool,
+    follow: bool,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.put_notify(lines, typ, after, follow))
+  }
+
+  pub fn get_color_by_name(&self, name: &str) -> Result<i64, Box<CallError>> {
+    block_on(self.inner.get_color_by_name(name))
+  }
+
+  pub fn get_color_by_name_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_color_by_name_notify(name))
+  }
+
+  pub fn get_color_map(&self) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_color_map())
+  }
+
+  pub fn get_color_map_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_color_map_notify())
+  }
+
+  pub fn get_context(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_context(opts))
+  }
+
+  pub fn get_context_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_context_notify(opts))
+  }
+
+  pub fn load_context(
+    &self,
+    dict: Vec<(Value, Value)>,
+  ) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.load_context(dict))
+  }
+
+  pub fn load_context_notify(
+    &self,
+    dict: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.load_context_notify(dict))
+  }
+
+  pub fn get_mode(&self) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_mode())
+  }
+
+  pub fn get_mode_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_mode_notify())
+  }
+
+  pub fn get_keymap(
+    &self,
+    mode: &str,
+  ) -> Result<Vec<Vec<(Value, Value)>>, Box<CallError>> {
+    block_on(self.inner.get_keymap(mode))
+  }
+
+  pub fn get_keymap_notify(&self, mode: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_keymap_notify(mode))
+  }
+
+  pub fn set_keymap(
+    &self,
+    mode: &str,
+    lhs: &str,
+    rhs: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_keymap(mode, lhs, rhs, opts))
+  }
+
+  pub fn set_keymap_notify(
+    &self,
+    mode: &str,
+    lhs: &str,
+    rhs: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.set_keymap_notify(mode, lhs, rhs, opts))
+  }
+
+  pub fn del_keymap(
+    &self,
+    mode: &str,
+    lhs: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_keymap(mode, lhs))
+  }
+
+  pub fn del_keymap_notify(
+    &self,
+    mode: &str,
+    lhs: &str,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_keymap_notify(mode, lhs))
+  }
+
+  pub fn get_api_info(&self) -> Result<Vec<Value>, Box<CallError>> {
+    block_on(self.inner.get_api_info())
+  }
+
+  pub fn get_api_info_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_api_info_notify())
+  }
+
+  pub fn set_client_info(
+    &self,
+    name: &str,
+    version: Vec<(Value, Value)>,
+    typ: &str,
+    methods: Vec<(Value, Value)>,
+    attributes: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .set_client_info(name, version, typ, methods, attributes),
+    )
+  }
+
+  pub fn set_client_info_notify(
+    &self,
+    name: &str,
+    version: Vec<(Value, Value)>,
+    typ: &str,
+    methods: Vec<(Value, Value)>,
+    attributes: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .set_client_info_notify(name, version, typ, methods, attributes),
+    )
+  }
+
+  pub fn get_chan_info(
+    &self,
+    chan: i64,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.get_chan_info(chan))
+  }
+
+  pub fn get_chan_info_notify(&self, chan: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_chan_info_notify(chan))
+  }
+
+  pub fn list_chans(&self) -> Result<Vec<Value>, Box<CallError>> {
+    block_on(self.inner.list_chans())
+  }
+
+  pub fn list_chans_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.list_chans_notify())
+  }
+
+  pub fn list_uis(&self) -> Result<Vec<Value>, Box<CallError>> {
+    block_on(self.inner.list_uis())
+  }
+
+  pub fn list_uis_notify(&self) -> Result<(), Box<CallError>> {
+    block_on(self.inner.list_uis_notify())
+  }
+
+  pub fn get_proc_children(
+    &self,
+    pid: i64,
+  ) -> Result<Vec<Value>, Box<CallError>> {
+    block_on(self.inner.get_proc_children(pid))
+  }
+
+  pub fn get_proc_children_notify(
+    &self,
+    pid: i64,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_proc_children_notify(pid))
+  }
+
+  pub fn get_proc(&self, pid: i64) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.get_proc(pid))
+  }
+
+  pub fn get_proc_notify(&self, pid: i64) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_proc_notify(pid))
+  }
+
+  pub fn select_popupmenu_item(
+    &self,
+    item: i64,
+    insert: bool,
+    finish: bool,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.select_popupmenu_item(item, insert, finish, opts))
+  }
+
+  pub fn select_popupmenu_item_notify(
+    &self,
+    item: i64,
+    insert: bool,
+    finish: bool,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(
+      self
+        .inner
+        .select_popupmenu_item_notify(item, insert, finish, opts),
+    )
+  }
+
+  pub fn del_mark(&self, name: &str) -> Result<bool, Box<CallError>> {
+    block_on(self.inner.del_mark(name))
+  }
+
+  pub fn del_mark_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.del_mark_notify(name))
+  }
+
+  pub fn get_mark(
+    &self,
+    name: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<Value>, Box<CallError>> {
+    block_on(self.inner.get_mark(name, opts))
+  }
+
+  pub fn get_mark_notify(
+    &self,
+    name: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.get_mark_notify(name, opts))
+  }
+
+  pub fn eval_statusline(
+    &self,
+    str: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.eval_statusline(str, opts))
+  }
+
+  pub fn eval_statusline_notify(
+    &self,
+    str: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.eval_statusline_notify(str, opts))
+  }
+
+  pub fn exec2(
+    &self,
+    src: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.exec2(src, opts))
+  }
+
+  pub fn exec2_notify(
+    &self,
+    src: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.exec2_notify(src, opts))
+  }
+
+  pub fn command(&self, command: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.command(command))
+  }
+
+  pub fn command_notify(&self, command: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.command_notify(command))
+  }
+
+  pub fn eval(&self, expr: &str) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.eval(expr))
+  }
+
+  pub fn eval_notify(&self, expr: &str) -> Result<(), Box<CallError>> {
+    block_on(self.inner.eval_notify(expr))
+  }
+
+  pub fn call_function(
+    &self,
+    fname: &str,
+    args: Vec<Value>,
+  ) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.call_function(fname, args))
+  }
+
+  pub fn call_function_notify(
+    &self,
+    fname: &str,
+    args: Vec<Value>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.call_function_notify(fname, args))
+  }
+
+  pub fn call_dict_function(
+    &self,
+    dict: Value,
+    fname: &str,
+    args: Vec<Value>,
+  ) -> Result<Value, Box<CallError>> {
+    block_on(self.inner.call_dict_function(dict, fname, args))
+  }
+
+  pub fn call_dict_function_notify(
+    &self,
+    dict: Value,
+    fname: &str,
+    args: Vec<Value>,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.call_dict_function_notify(dict, fname, args))
+  }
+
+  pub fn parse_expression(
+    &self,
+    expr: &str,
+    flags: &str,
+    highlight: bool,
+  ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
+    block_on(self.inner.parse_expression(expr, flags, highlight))
+  }
+
+  pub fn parse_expression_notify(
+    &self,
+    expr: &str,
+    flags: &str,
+    highlight: bool,
+  ) -> Result<(), Box<CallError>> {
+    block_on(self.inner.parse_expression_notify(expr, flags, highlight))
+  }
+}
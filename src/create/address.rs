@@ -0,0 +1,217 @@
+//! Addresses of running neovim instances.
+//!
+//! Neovim listens on the address given by `--listen` or `serverstart()`. By
+//! default, that's a socket named `nvim.<pid>.0` in `$XDG_RUNTIME_DIR` (or a
+//! named pipe of that name on Windows). Processes started by neovim, e.g. in
+//! a terminal, find the address in `$NVIM`.
+//!
+//! To connect to an address, use `connect` of the module for your runtime,
+//! e.g. [`create::tokio::connect`](crate::create::tokio::connect). To find
+//! the instances that are actually running, use `list_servers`.
+use std::{
+  env,
+  fmt::{self, Display},
+  fs::{self, DirEntry},
+  io,
+  path::{Path, PathBuf},
+  str::FromStr,
+};
+
+// Only needed for probing, which needs a runtime
+#[cfg(any(feature = "use_tokio", feature = "use_smol"))]
+use {
+  crate::neovim::Neovim, futures::io::AsyncWrite, rmpv::Value,
+  std::time::Duration,
+};
+
+/// How long to wait for a server to answer when probing it.
+#[cfg(any(feature = "use_tokio", feature = "use_smol"))]
+pub(crate) const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The address of a neovim server.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ServerAddress {
+  /// A TCP address like `localhost:6666`.
+  Tcp(String),
+  /// A unix socket, or a named pipe on Windows.
+  Path(PathBuf),
+}
+
+impl FromStr for ServerAddress {
+  type Err = io::Error;
+
+  /// Parse an address the way `nvim --server` does: if it contains a colon
+  /// after the first character, it's a TCP address and the part after the
+  /// last colon is the port. Otherwise, it's a path.
+  fn from_str(s: &str) -> io::Result<Self> {
+    match s.rfind(':') {
+      Some(i) if i > 0 => {
+        if s[i + 1..].parse::<u16>().is_err() {
+          return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid port in address '{s}'"),
+          ));
+        }
+        Ok(ServerAddress::Tcp(s.to_owned()))
+      }
+      _ => Ok(ServerAddress::Path(PathBuf::from(s))),
+    }
+  }
+}
+
+impl Display for ServerAddress {
+  fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match *self {
+      ServerAddress::Tcp(ref addr) => write!(fmt, "{addr}"),
+      ServerAddress::Path(ref path) => write!(fmt, "{}", path.display()),
+    }
+  }
+}
+
+impl ServerAddress {
+  /// The address of the neovim instance that started this process, read from
+  /// `$NVIM`, or from `$NVIM_LISTEN_ADDRESS` as set by older versions.
+  #[must_use]
+  pub fn from_env() -> Option<ServerAddress> {
+    ["NVIM", "NVIM_LISTEN_ADDRESS"].iter().find_map(|var| {
+      let addr = env::var(var).ok().filter(|addr| !addr.is_empty())?;
+      addr
+        .parse()
+        .inspect_err(|e| warn!("Ignoring ${}: {}", var, e))
+        .ok()
+    })
+  }
+}
+
+/// The addresses where neovim servers might be listening: the one from
+/// [`ServerAddress::from_env`], followed by the sockets in `$XDG_RUNTIME_DIR`
+/// (the named pipes on Windows) whose name starts with `nvim.`.
+///
+/// This doesn't check whether a server is actually listening, see
+/// `list_servers` of the module for your runtime for that.
+#[must_use]
+pub fn candidates() -> Vec<ServerAddress> {
+  let mut addresses: Vec<_> = ServerAddress::from_env().into_iter().collect();
+
+  #[cfg(unix)]
+  let dir = env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from);
+  #[cfg(windows)]
+  let dir = Some(PathBuf::from(r"\\.\pipe\"));
+
+  if let Some(dir) = dir {
+    for addr in sockets_in(&dir) {
+      if !addresses.contains(&addr) {
+        addresses.push(addr);
+      }
+    }
+  }
+
+  addresses
+}
+
+/// The sockets (or named pipes) in `dir` whose name starts with `nvim.`,
+/// sorted by name.
+fn sockets_in(dir: &Path) -> Vec<ServerAddress> {
+  let entries = match fs::read_dir(dir) {
+    Ok(entries) => entries,
+    Err(e) => {
+      debug!("Cannot read {}: {}", dir.display(), e);
+      return Vec::new();
+    }
+  };
+
+  let mut paths: Vec<_> = entries
+    .filter_map(Result::ok)
+    .filter(|entry| entry.file_name().to_string_lossy().starts_with("nvim."))
+    .filter(is_socket)
+    .map(|entry| entry.path())
+    .collect();
+  paths.sort();
+
+  paths.into_iter().map(ServerAddress::Path).collect()
+}
+
+#[cfg(unix)]
+fn is_socket(entry: &DirEntry) -> bool {
+  use std::os::unix::fs::FileTypeExt;
+
+  entry.file_type().is_ok_and(|t| t.is_socket())
+}
+/// Everything in `\\.\pipe\` is a named pipe.
+#[cfg(windows)]
+fn is_socket(_entry: &DirEntry) -> bool {
+  true
+}
+
+/// Ask a freshly connected server for its api info, and close the session.
+/// Returns `None` if it doesn't answer like neovim would.
+#[cfg(any(feature = "use_tokio", feature = "use_smol"))]
+pub(crate) async fn probe<W>(neovim: Neovim<W>) -> Option<Vec<Value>>
+where
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  let res = neovim
+    .call_with_timeout("nvim_get_api_info", vec![], PROBE_TIMEOUT)
+    .await;
+  neovim.close().await;
+
+  match res {
+    Ok(Ok(Value::Array(info))) => Some(info),
+    Ok(Ok(_)) | Ok(Err(_)) => None,
+    Err(e) => {
+      debug!("Probing failed: {}", e);
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse() {
+    let parse = |s: &str| s.parse::<ServerAddress>().unwrap();
+
+    assert_eq!(
+      ServerAddress::Tcp("localhost:6666".into()),
+      parse("localhost:6666")
+    );
+    assert_eq!(ServerAddress::Tcp("[::1]:6666".into()), parse("[::1]:6666"));
+    assert_eq!(
+      ServerAddress::Path("/run/user/1000/nvim.1234.0".into()),
+      parse("/run/user/1000/nvim.1234.0")
+    );
+    assert_eq!(
+      ServerAddress::Path(r"\\.\pipe\nvim.1234.0".into()),
+      parse(r"\\.\pipe\nvim.1234.0")
+    );
+    assert_eq!(ServerAddress::Path(":6666".into()), parse(":6666"));
+
+    for addr in ["localhost:", "localhost:port", "localhost:70000"] {
+      let err = addr.parse::<ServerAddress>().unwrap_err();
+      assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+    }
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_sockets_in() {
+    use std::os::unix::net::UnixListener;
+
+    let dir = tempfile::tempdir().unwrap();
+    let _two = UnixListener::bind(dir.path().join("nvim.2.0")).unwrap();
+    let _one = UnixListener::bind(dir.path().join("nvim.1.0")).unwrap();
+    let _other = UnixListener::bind(dir.path().join("other.1.0")).unwrap();
+    fs::write(dir.path().join("nvim.log"), "").unwrap();
+
+    assert_eq!(
+      vec![
+        ServerAddress::Path(dir.path().join("nvim.1.0")),
+        ServerAddress::Path(dir.path().join("nvim.2.0")),
+      ],
+      sockets_in(dir.path())
+    );
+    assert!(sockets_in(&dir.path().join("missing")).is_empty());
+  }
+}
@@ -0,0 +1,334 @@
+//! Configuration for spawning neovim as a child process.
+//!
+//! [`new_child_cmd`](crate::create::tokio::new_child_cmd) leaves setting up
+//! the command to the caller. A [`ChildBuilder`] covers the usual needs of
+//! embedding neovim instead, and is run by `spawn_child` of the module for
+//! your runtime, e.g.
+//! [`create::tokio::spawn_child`](crate::create::tokio::spawn_child).
+use std::{
+  ffi::{OsStr, OsString},
+  path::{Path, PathBuf},
+};
+
+// Only needed for spawning, which needs a runtime
+#[cfg(any(feature = "use_tokio", feature = "use_smol"))]
+use std::{
+  env, fs, io,
+  process::{self, Command, Stdio},
+  sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Which configuration the child loads on startup.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ChildConfig {
+  /// The user's configuration, as if neovim was started by the user.
+  #[default]
+  User,
+  /// Start with `--clean`: no user configuration and no shada file, but the
+  /// builtin plugins are loaded.
+  Clean,
+  /// Start with `-u NONE`: no configuration and no plugins at all.
+  None,
+  /// Start with `-u <file>`.
+  File(PathBuf),
+}
+
+/// How to spawn a neovim child. The defaults match the behavior of
+/// [`new_child`](crate::create::tokio::new_child).
+#[derive(Debug, Clone)]
+pub struct ChildBuilder {
+  pub(crate) program: PathBuf,
+  pub(crate) config: ChildConfig,
+  pub(crate) headless: bool,
+  pub(crate) isolated: bool,
+  pub(crate) args: Vec<OsString>,
+  pub(crate) env: Vec<(OsString, OsString)>,
+  pub(crate) cwd: Option<PathBuf>,
+  pub(crate) files: Vec<PathBuf>,
+  pub(crate) kill_on_drop: bool,
+  pub(crate) capture_stderr: bool,
+  pub(crate) handshake: Option<String>,
+}
+
+impl Default for ChildBuilder {
+  fn default() -> Self {
+    let program = if cfg!(target_os = "windows") {
+      "nvim.exe"
+    } else {
+      "nvim"
+    };
+
+    ChildBuilder {
+      program: program.into(),
+      config: ChildConfig::default(),
+      headless: false,
+      isolated: false,
+      args: Vec::new(),
+      env: Vec::new(),
+      cwd: None,
+      files: Vec::new(),
+      kill_on_drop: false,
+      capture_stderr: false,
+      handshake: None,
+    }
+  }
+}
+
+impl ChildBuilder {
+  #[must_use]
+  pub fn new() -> ChildBuilder {
+    ChildBuilder::default()
+  }
+
+  /// Set the neovim binary to run. By default, `nvim` is looked up in
+  /// `$PATH`.
+  pub fn set_program<P: AsRef<Path>>(&mut self, val: P) -> &mut Self {
+    self.program = val.as_ref().to_owned();
+    self
+  }
+
+  /// Set which configuration neovim loads. By default, that's the user's.
+  pub fn set_config(&mut self, val: ChildConfig) -> &mut Self {
+    self.config = val;
+    self
+  }
+
+  /// Pass `--headless` in addition to `--embed`. Without it, neovim waits for
+  /// [`ui_attach`](crate::neovim::Neovim::ui_attach) before finishing its
+  /// startup, e.g. before sourcing the configuration and opening files.
+  pub fn set_headless(&mut self, val: bool) -> &mut Self {
+    self.headless = val;
+    self
+  }
+
+  /// Point `$XDG_CONFIG_HOME`, `$XDG_DATA_HOME`, `$XDG_STATE_HOME` and
+  /// `$XDG_CACHE_HOME` to a fresh temporary directory, so that neovim neither
+  /// sees nor touches the user's files. The directory is removed when the
+  /// child is dropped.
+  pub fn set_isolated(&mut self, val: bool) -> &mut Self {
+    self.isolated = val;
+    self
+  }
+
+  /// Pass an additional argument to neovim.
+  pub fn arg<S: AsRef<OsStr>>(&mut self, val: S) -> &mut Self {
+    self.args.push(val.as_ref().to_owned());
+    self
+  }
+
+  /// Set an environment variable for neovim. This takes precedence over the
+  /// variables set by [`set_isolated`](ChildBuilder::set_isolated).
+  pub fn env<K, V>(&mut self, key: K, val: V) -> &mut Self
+  where
+    K: AsRef<OsStr>,
+    V: AsRef<OsStr>,
+  {
+    self
+      .env
+      .push((key.as_ref().to_owned(), val.as_ref().to_owned()));
+    self
+  }
+
+  /// Set the working directory of neovim. By default, it's inherited.
+  pub fn set_cwd<P: AsRef<Path>>(&mut self, val: P) -> &mut Self {
+    self.cwd = Some(val.as_ref().to_owned());
+    self
+  }
+
+  /// Open a file on startup.
+  pub fn file<P: AsRef<Path>>(&mut self, val: P) -> &mut Self {
+    self.files.push(val.as_ref().to_owned());
+    self
+  }
+
+  /// Kill neovim when the child is dropped. By default, it keeps running.
+  pub fn set_kill_on_drop(&mut self, val: bool) -> &mut Self {
+    self.kill_on_drop = val;
+    self
+  }
+
+  /// Log everything neovim writes to stderr as warnings. By default, stderr
+  /// is inherited.
+  pub fn set_capture_stderr(&mut self, val: bool) -> &mut Self {
+    self.capture_stderr = val;
+    self
+  }
+
+  /// Connect with [`Neovim::handshake`](crate::neovim::Neovim::handshake)
+  /// instead of assuming that neovim speaks msgpack-rpc on stdout right away.
+  /// This is useful if the configuration might print something, see the
+  /// documentation there.
+  pub fn set_handshake<S: Into<String>>(&mut self, message: S) -> &mut Self {
+    self.handshake = Some(message.into());
+    self
+  }
+
+  /// The command to run, with stdin and stdout piped. `isolated` is the
+  /// directory to put the XDG directories in.
+  #[cfg(any(feature = "use_tokio", feature = "use_smol"))]
+  pub(crate) fn command(&self, isolated: Option<&Path>) -> Command {
+    let mut cmd = Command::new(&self.program);
+
+    cmd.arg("--embed");
+    if self.headless {
+      cmd.arg("--headless");
+    }
+    match self.config {
+      ChildConfig::User => {}
+      ChildConfig::Clean => {
+        cmd.arg("--clean");
+      }
+      ChildConfig::None => {
+        cmd.args(["-u", "NONE"]);
+      }
+      ChildConfig::File(ref file) => {
+        cmd.arg("-u").arg(file);
+      }
+    }
+    cmd.args(&self.args);
+    if !self.files.is_empty() {
+      cmd.arg("--").args(&self.files);
+    }
+
+    if let Some(dir) = isolated {
+      for (var, name) in [
+        ("XDG_CONFIG_HOME", "config"),
+        ("XDG_DATA_HOME", "data"),
+        ("XDG_STATE_HOME", "state"),
+        ("XDG_CACHE_HOME", "cache"),
+      ] {
+        cmd.env(var, dir.join(name));
+      }
+    }
+    cmd.envs(self.env.iter().map(|(k, v)| (k, v)));
+    if let Some(ref cwd) = self.cwd {
+      cmd.current_dir(cwd);
+    }
+
+    cmd.stdin(Stdio::piped()).stdout(Stdio::piped());
+    if self.capture_stderr {
+      cmd.stderr(Stdio::piped());
+    }
+
+    cmd
+  }
+}
+
+/// A temporary directory for the XDG directories of an isolated child,
+/// removed when dropped.
+#[cfg(any(feature = "use_tokio", feature = "use_smol"))]
+#[derive(Debug)]
+pub(crate) struct IsolatedDir(PathBuf);
+
+#[cfg(any(feature = "use_tokio", feature = "use_smol"))]
+impl IsolatedDir {
+  pub(crate) fn new() -> io::Result<IsolatedDir> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    loop {
+      let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+      let name = format!("nvim-rs.{}.{n}", process::id());
+      let path = env::temp_dir().join(name);
+      match fs::create_dir(&path) {
+        Ok(()) => return Ok(IsolatedDir(path)),
+        // Left over from an earlier process with the same pid
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(e),
+      }
+    }
+  }
+
+  pub(crate) fn path(&self) -> &Path {
+    &self.0
+  }
+}
+
+#[cfg(any(feature = "use_tokio", feature = "use_smol"))]
+impl Drop for IsolatedDir {
+  fn drop(&mut self) {
+    if let Err(e) = fs::remove_dir_all(&self.0) {
+      warn!("Cannot remove {}: {}", self.0.display(), e);
+    }
+  }
+}
+
+#[cfg(all(test, any(feature = "use_tokio", feature = "use_smol")))]
+mod tests {
+  use super::*;
+
+  fn args(cmd: &Command) -> Vec<&OsStr> {
+    cmd.get_args().collect()
+  }
+
+  #[test]
+  fn test_command() {
+    let cmd = ChildBuilder::new().command(None);
+    assert_eq!(vec!["--embed"], args(&cmd));
+    assert_eq!(0, cmd.get_envs().count());
+    assert_eq!(None, cmd.get_current_dir());
+
+    let cmd = ChildBuilder::new()
+      .set_program("/opt/nvim/bin/nvim")
+      .set_headless(true)
+      .set_config(ChildConfig::None)
+      .arg("-n")
+      .file("a.txt")
+      .file("-b.txt")
+      .set_cwd("/tmp")
+      .command(None);
+    assert_eq!(Path::new("/opt/nvim/bin/nvim"), cmd.get_program());
+    assert_eq!(
+      vec![
+        "--embed",
+        "--headless",
+        "-u",
+        "NONE",
+        "-n",
+        "--",
+        "a.txt",
+        "-b.txt"
+      ],
+      args(&cmd)
+    );
+    assert_eq!(Some(Path::new("/tmp")), cmd.get_current_dir());
+
+    let cmd = ChildBuilder::new()
+      .set_config(ChildConfig::Clean)
+      .command(None);
+    assert_eq!(vec!["--embed", "--clean"], args(&cmd));
+
+    let cmd = ChildBuilder::new()
+      .set_config(ChildConfig::File("init.lua".into()))
+      .command(None);
+    assert_eq!(vec!["--embed", "-u", "init.lua"], args(&cmd));
+  }
+
+  #[test]
+  fn test_isolated_env() {
+    let dir = IsolatedDir::new().unwrap();
+    let path = dir.path().to_owned();
+    assert!(path.is_dir());
+
+    let cmd = ChildBuilder::new()
+      .env("XDG_DATA_HOME", "/data")
+      .env("FOO", "bar")
+      .command(Some(&path));
+    let envs: Vec<_> = cmd.get_envs().collect();
+
+    let config = path.join("config");
+    assert!(
+      envs.contains(&(OsStr::new("XDG_CONFIG_HOME"), Some(config.as_os_str())))
+    );
+    assert!(envs.contains(&(OsStr::new("FOO"), Some(OsStr::new("bar")))));
+    // Explicitly set variables win
+    let data = envs
+      .iter()
+      .rev()
+      .find(|(k, _)| *k == "XDG_DATA_HOME")
+      .unwrap();
+    assert_eq!(Some(OsStr::new("/data")), data.1);
+
+    drop(dir);
+    assert!(!path.exists());
+  }
+}
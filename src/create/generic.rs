@@ -0,0 +1,410 @@
+//! Functions to spawn a [`neovim`](crate::neovim::Neovim) session on any
+//! executor
+//!
+//! Sockets, pipes and processes are driven by [`async_io`],
+//! [`async_process`] and [`blocking`], which don't depend on a particular
+//! executor. Tasks are spawned by a function you pass in, which gets each
+//! task as a [`BoxFuture`], e.g.
+//! `move |task: BoxFuture<'static, ()>| pool.spawn_ok(task)` for a
+//! [`futures::executor::ThreadPool`].
+//!
+//! Unlike the other runtimes, the constructors return the io future instead
+//! of spawning it, so you can run it however you like. The tasks are always
+//! passed to the function, even if `use_tokio` or `use_smol` is enabled as
+//! well.
+//!
+//! ```no_run
+//! # fn main() -> std::io::Result<()> {
+//! use futures::{
+//!   executor::{block_on, ThreadPool},
+//!   future::BoxFuture,
+//! };
+//! use nvim_rs::{create::generic as create, rpc::handler::Dummy};
+//!
+//! let pool = ThreadPool::new()?;
+//! let spawner = pool.clone();
+//! let spawn = move |task: BoxFuture<'static, ()>| spawner.spawn_ok(task);
+//!
+//! block_on(async {
+//!   let (nvim, io) =
+//!     create::new_tcp("127.0.0.1:6666", Dummy::new(), spawn).await?;
+//!   pool.spawn_ok(async move {
+//!     let _ = io.await;
+//!   });
+//!
+//!   nvim.command("echo 'Hello'").await.unwrap();
+//!   Ok(())
+//! })
+//! # }
+//! ```
+use std::{
+  fs::File,
+  future::Future,
+  io,
+  net::{SocketAddr, TcpStream, ToSocketAddrs},
+  path::Path,
+  process::Stdio,
+  sync::Arc,
+};
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+
+use async_io::Async;
+use blocking::{unblock, Unblock};
+use futures::{
+  future::BoxFuture,
+  io::{AsyncRead, AsyncReadExt, AsyncWrite, WriteHalf},
+};
+
+pub use async_process::{Child, ChildStdin, Command};
+
+use crate::{
+  create::{unbuffered_stdout, TaskSpawner},
+  error::{HandshakeError, LoopError},
+  neovim::{HandshakeInfo, Neovim, ShutdownReason},
+  sessionoptions::SessionOptions,
+  Handler,
+};
+
+/// The writer of a session created by [`new_tcp`].
+pub type TcpWriter = WriteHalf<Async<TcpStream>>;
+/// The writer of a session created by [`new_path`].
+#[cfg(unix)]
+pub type PathWriter = WriteHalf<Async<UnixStream>>;
+/// The writer of a session created by [`new_parent`].
+pub type ParentWriter = Unblock<File>;
+
+/// The result of the io future of a session.
+type IoResult = Result<ShutdownReason, Box<LoopError>>;
+
+/// A function spawning a task on some executor.
+pub type SpawnFn = Arc<dyn Fn(BoxFuture<'static, ()>) + Send + Sync>;
+
+impl TaskSpawner for SpawnFn {
+  fn spawn_task<Fut>(&self, future: Fut)
+  where
+    Fut: Future<Output = ()> + Send + 'static,
+  {
+    self(Box::pin(future));
+  }
+}
+
+/// Like [`Neovim::new`](crate::neovim::Neovim::new), but spawn the tasks of
+/// the session by calling `spawn`. This works for any reader and writer, the
+/// other functions of this module are built on it.
+pub fn new<R, W, H, S>(
+  reader: R,
+  writer: W,
+  handler: H,
+  spawn: S,
+) -> (Neovim<W>, impl Future<Output = IoResult> + use<R, W, H, S>)
+where
+  R: AsyncRead + Send + Unpin + 'static,
+  W: AsyncWrite + Send + Unpin + 'static,
+  H: Handler<Writer = W>,
+  S: Fn(BoxFuture<'static, ()>) + Send + Sync + 'static,
+{
+  new_with_options(reader, writer, handler, spawn, &SessionOptions::default())
+}
+
+/// Like [`new`], but configure the session with `options`.
+pub fn new_with_options<R, W, H, S>(
+  reader: R,
+  writer: W,
+  handler: H,
+  spawn: S,
+  options: &SessionOptions,
+) -> (Neovim<W>, impl Future<Output = IoResult> + use<R, W, H, S>)
+where
+  R: AsyncRead + Send + Unpin + 'static,
+  W: AsyncWrite + Send + Unpin + 'static,
+  H: Handler<Writer = W>,
+  S: Fn(BoxFuture<'static, ()>) + Send + Sync + 'static,
+{
+  let spawn: SpawnFn = Arc::new(spawn);
+  Neovim::new_spawning(reader, writer, handler, spawn, options)
+}
+
+/// Connect to a neovim instance via tcp
+pub async fn new_tcp<A, H, S>(
+  addr: A,
+  handler: H,
+  spawn: S,
+) -> io::Result<(
+  Neovim<TcpWriter>,
+  impl Future<Output = IoResult> + use<A, H, S>,
+)>
+where
+  A: ToSocketAddrs + Send + 'static,
+  A::Iter: Send,
+  H: Handler<Writer = TcpWriter>,
+  S: Fn(BoxFuture<'static, ()>) + Send + Sync + 'static,
+{
+  // Resolving might block
+  let addrs = unblock(move || addr.to_socket_addrs()).await?;
+  let stream = connect_tcp(addrs).await?;
+  let (reader, writer) = stream.split();
+
+  Ok(new(reader, writer, handler, spawn))
+}
+
+/// Try each address in turn, and return the first connection made, or the
+/// last error.
+async fn connect_tcp<I>(addrs: I) -> io::Result<Async<TcpStream>>
+where
+  I: Iterator<Item = SocketAddr>,
+{
+  let mut last_err = None;
+  for addr in addrs {
+    match Async::<TcpStream>::connect(addr).await {
+      Ok(stream) => return Ok(stream),
+      Err(e) => last_err = Some(e),
+    }
+  }
+
+  Err(last_err.unwrap_or_else(|| {
+    io::Error::new(io::ErrorKind::InvalidInput, "No address to connect to")
+  }))
+}
+
+#[cfg(unix)]
+/// Connect to a neovim instance via unix socket by path. This is currently
+/// only available on Unix.
+pub async fn new_path<P, H, S>(
+  path: P,
+  handler: H,
+  spawn: S,
+) -> io::Result<(
+  Neovim<PathWriter>,
+  impl Future<Output = IoResult> + use<P, H, S>,
+)>
+where
+  P: AsRef<Path>,
+  H: Handler<Writer = PathWriter>,
+  S: Fn(BoxFuture<'static, ()>) + Send + Sync + 'static,
+{
+  let stream = Async::<UnixStream>::connect(path).await?;
+  let (reader, writer) = stream.split();
+
+  Ok(new(reader, writer, handler, spawn))
+}
+
+/// Connect to the neovim instance that spawned this process over stdin/stdout
+pub fn new_parent<H, S>(
+  handler: H,
+  spawn: S,
+) -> io::Result<(
+  Neovim<ParentWriter>,
+  impl Future<Output = IoResult> + use<H, S>,
+)>
+where
+  H: Handler<Writer = ParentWriter>,
+  S: Fn(BoxFuture<'static, ()>) + Send + Sync + 'static,
+{
+  let sout = Unblock::new(unbuffered_stdout()?);
+  let sin = Unblock::new(io::stdin());
+
+  Ok(new(sin, sout, handler, spawn))
+}
+
+/// Connect to a neovim instance by spawning a new one
+pub async fn new_child<H, S>(
+  handler: H,
+  spawn: S,
+) -> io::Result<(
+  Neovim<ChildStdin>,
+  impl Future<Output = IoResult> + use<H, S>,
+  Child,
+)>
+where
+  H: Handler<Writer = ChildStdin>,
+  S: Fn(BoxFuture<'static, ()>) + Send + Sync + 'static,
+{
+  if cfg!(target_os = "windows") {
+    new_child_path("nvim.exe", handler, spawn).await
+  } else {
+    new_child_path("nvim", handler, spawn).await
+  }
+}
+
+/// Connect to a neovim instance by spawning a new one
+pub async fn new_child_path<P, H, S>(
+  program: P,
+  handler: H,
+  spawn: S,
+) -> io::Result<(
+  Neovim<ChildStdin>,
+  impl Future<Output = IoResult> + use<P, H, S>,
+  Child,
+)>
+where
+  P: AsRef<Path>,
+  H: Handler<Writer = ChildStdin>,
+  S: Fn(BoxFuture<'static, ()>) + Send + Sync + 'static,
+{
+  new_child_cmd(
+    Command::new(program.as_ref()).arg("--embed"),
+    handler,
+    spawn,
+  )
+  .await
+}
+
+/// Connect to a neovim instance by spawning a new one
+///
+/// stdin/stdout will be rewritten to `Stdio::piped()`
+pub async fn new_child_cmd<H, S>(
+  cmd: &mut Command,
+  handler: H,
+  spawn: S,
+) -> io::Result<(
+  Neovim<ChildStdin>,
+  impl Future<Output = IoResult> + use<H, S>,
+  Child,
+)>
+where
+  H: Handler<Writer = ChildStdin>,
+  S: Fn(BoxFuture<'static, ()>) + Send + Sync + 'static,
+{
+  let mut child = cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()?;
+  let (stdout, stdin) = child_pipes(&mut child)?;
+
+  let (neovim, io) = new(stdout, stdin, handler, spawn);
+
+  Ok((neovim, io, child))
+}
+
+/// Connect to a neovim instance by spawning a new one and send a handshake
+/// message. Unlike `new_child_cmd`, this function is tolerant to extra
+/// data in the reader before the handshake response is received.
+///
+/// `message` should be a unique string that is normally not found in the
+/// stdout, see [`Neovim::handshake`](crate::neovim::Neovim::handshake).
+pub async fn new_child_handshake_cmd<H, S>(
+  cmd: &mut Command,
+  handler: H,
+  spawn: S,
+  message: &str,
+) -> Result<
+  (
+    Neovim<ChildStdin>,
+    impl Future<Output = IoResult> + use<H, S>,
+    Child,
+    HandshakeInfo,
+  ),
+  Box<HandshakeError>,
+>
+where
+  H: Handler<Writer = ChildStdin>,
+  S: Fn(BoxFuture<'static, ()>) + Send + Sync + 'static,
+{
+  let mut child = cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()?;
+  let (stdout, stdin) = child_pipes(&mut child)?;
+
+  let spawn: SpawnFn = Arc::new(spawn);
+  let (neovim, io, info) = Neovim::handshake_spawning(
+    stdout,
+    stdin,
+    handler,
+    spawn,
+    message,
+    &SessionOptions::default(),
+  )
+  .await?;
+
+  Ok((neovim, io, child, info))
+}
+
+/// Take the pipes to talk to a child spawned with piped stdin and stdout.
+fn child_pipes(
+  child: &mut Child,
+) -> io::Result<(async_process::ChildStdout, ChildStdin)> {
+  let stdout = child
+    .stdout
+    .take()
+    .ok_or_else(|| io::Error::other("Can't open stdout"))?;
+  let stdin = child
+    .stdin
+    .take()
+    .ok_or_else(|| io::Error::other("Can't open stdin"))?;
+
+  Ok((stdout, stdin))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::net::TcpListener;
+
+  use futures::executor::{block_on, ThreadPool};
+
+  use crate::{rpc::handler::Dummy, testing::fake::FakeNeovim, Value};
+
+  fn spawner(
+    pool: &ThreadPool,
+  ) -> impl Fn(BoxFuture<'static, ()>) + Send + Sync + 'static {
+    let pool = pool.clone();
+    move |task| pool.spawn_ok(task)
+  }
+
+  #[test]
+  fn test_tcp() {
+    let pool = ThreadPool::new().unwrap();
+    let fake = FakeNeovim::new();
+    fake.set_var("answer", Value::from(42));
+
+    let listener = Async::<TcpListener>::bind(([127, 0, 0, 1], 0)).unwrap();
+    let addr = listener.get_ref().local_addr().unwrap();
+    let serve = fake.clone();
+    pool.spawn_ok(async move {
+      let (stream, _) = listener.accept().await.unwrap();
+      let (reader, writer) = stream.split();
+      serve.serve(reader, writer).await.unwrap();
+    });
+
+    block_on(async {
+      let (nvim, io) =
+        new_tcp(addr, Dummy::new(), spawner(&pool)).await.unwrap();
+      pool.spawn_ok(async move {
+        io.await.unwrap();
+      });
+
+      assert_eq!(Value::from(42), nvim.get_var("answer").await.unwrap());
+      nvim.set_var("question", Value::from("?")).await.unwrap();
+    });
+    assert_eq!(Some(Value::from("?")), fake.var("question"));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_path() {
+    use std::os::unix::net::UnixListener;
+
+    let pool = ThreadPool::new().unwrap();
+    let fake = FakeNeovim::new();
+    fake.set_var("answer", Value::from(42));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nvim.sock");
+    let listener = Async::<UnixListener>::bind(&path).unwrap();
+    let serve = fake.clone();
+    pool.spawn_ok(async move {
+      let (stream, _) = listener.accept().await.unwrap();
+      let (reader, writer) = stream.split();
+      serve.serve(reader, writer).await.unwrap();
+    });
+
+    block_on(async {
+      let (nvim, io) = new_path(path.clone(), Dummy::new(), spawner(&pool))
+        .await
+        .unwrap();
+      pool.spawn_ok(async move {
+        io.await.unwrap();
+      });
+
+      assert_eq!(Value::from(42), nvim.get_var("answer").await.unwrap());
+    });
+  }
+}
@@ -4,22 +4,28 @@
 //! spawning an own child process. Available capabilities might depend on your
 //! OS and choice of features.
 //!
-//! Supported features: `use_tokio` and `use_async-std`.
+//! Supported features: `use_tokio`, `use_smol` and `use_generic`.
 //!
 //! **IMPORTANT**: Due to incompatibilities of the rust async ecosystem,  you
 //! might not be able to use types from one lib with the runtime of another lib.
 //! E.g. when using the features `use_tokio`, you will need to run all the
 //! API functions from inside the tokio runtime.
+pub mod address;
+pub mod child;
+
 #[cfg(feature = "use_tokio")]
 pub mod tokio;
 
 #[cfg(feature = "use_smol")]
 pub mod smol;
 
+#[cfg(feature = "use_generic")]
+pub mod generic;
+
 use core::future::Future;
 use std::{fs::File, io};
 
-use crate::rpc::handler::Handler;
+use crate::rpc::handler::{Handler, LocalHandler};
 
 /// A task to generalize spawning a future that returns `()`.
 ///
@@ -38,6 +44,52 @@ pub trait Spawner: Handler {
     Fut: Future<Output = ()> + Send + 'static;
 }
 
+/// Spawns the tasks of a session. The constructors on
+/// [`Neovim`](crate::neovim::Neovim) use the [`Spawner`] implementation of the
+/// handler, via [`HandlerSpawner`]. The `generic` backend passes a function
+/// instead, which can't be a [`Spawner`] alongside the implementations of
+/// `use_tokio` and `use_smol` for every handler.
+pub(crate) trait TaskSpawner: Clone + Send + Sync + 'static {
+  fn spawn_task<Fut>(&self, future: Fut)
+  where
+    Fut: Future<Output = ()> + Send + 'static;
+}
+
+/// Spawns tasks with the [`Spawner`] implementation of a handler.
+#[derive(Clone)]
+pub(crate) struct HandlerSpawner<H>(pub(crate) H);
+
+impl<H> TaskSpawner for HandlerSpawner<H>
+where
+  H: Spawner,
+{
+  fn spawn_task<Fut>(&self, future: Fut)
+  where
+    Fut: Future<Output = ()> + Send + 'static,
+  {
+    // Tasks are never waited on, so the handle isn't needed
+    let _ = self.0.spawn(future);
+  }
+}
+
+/// Like [`Spawner`], but for a
+/// [`LocalHandler`](crate::rpc::handler::LocalHandler) on a single-threaded
+/// executor, so the futures don't need to be `Send`.
+///
+/// With the feature `use_tokio`, this is implemented on every
+/// [`LocalHandler`](crate::rpc::handler::LocalHandler) using
+/// `tokio::task::spawn_local`, so the session needs to run on a `LocalSet`.
+/// With `use_smol`, wrap the handler in `create::smol::LocalSpawning` to run
+/// it on a `LocalExecutor`. Otherwise, implement this for your executor and use
+/// [`Neovim::new_local`](crate::neovim::Neovim::new_local).
+pub trait LocalSpawner: LocalHandler {
+  type Handle;
+
+  fn spawn_local<Fut>(&self, future: Fut) -> Self::Handle
+  where
+    Fut: Future<Output = ()> + 'static;
+}
+
 /// Create a std::io::File for stdout, which is not line-buffered, as
 /// opposed to std::io::Stdout.
 #[cfg(unix)]
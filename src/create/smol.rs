@@ -1,34 +1,177 @@
 //! Functions to spawn a [`neovim`](crate::neovim::Neovim) session using
 //! [`smol`](smol)
-use std::{future::Future, io, fs::File, path::Path};
+use std::{
+  fs::File, future::Future, io, net::SocketAddr, path::Path,
+  process::ExitStatus, rc::Rc,
+};
 
 #[cfg(unix)]
-use smol::net::unix::UnixStream;
+use smol::net::unix::{UnixListener, UnixStream};
 
 use smol::{
-  Unblock,
-  net::{TcpStream, AsyncToSocketAddrs},
-  spawn, Task,
+  io::{AsyncBufReadExt, BufReader},
+  net::{AsyncToSocketAddrs, TcpListener, TcpStream},
+  process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+  spawn,
+  stream::StreamExt,
+  LocalExecutor, Task, Unblock,
 };
 
+#[cfg(unix)]
+use futures::future::{join_all, Either};
+
+#[cfg(unix)]
+use crate::{
+  create::address::{candidates, probe, ServerAddress},
+  rpc::handler::Dummy,
+  Value,
+};
 use crate::{
-  create::{unbuffered_stdout, Spawner},
-  error::LoopError,
-  neovim::Neovim,
-  Handler,
+  create::{
+    child::{ChildBuilder, IsolatedDir},
+    unbuffered_stdout, LocalSpawner, Spawner,
+  },
+  error::{HandshakeError, LoopError, SessionError},
+  neovim::{HandshakeInfo, Neovim, ShutdownReason},
+  sessionoptions::{Backoff, SessionOptions},
+  Handler, LocalHandler,
 };
 
 impl<H> Spawner for H
 where
   H: Handler,
 {
-  type Handle = Task<()>;
+  type Handle = ();
 
   fn spawn<Fut>(&self, future: Fut) -> Self::Handle
   where
     Fut: Future<Output = ()> + Send + 'static,
   {
-    spawn(future)
+    // Dropping a smol task cancels it, and the handle isn't kept around
+    spawn(future).detach();
+  }
+}
+
+/// A [`LocalHandler`] that spawns its tasks on a [`LocalExecutor`]. Use it
+/// with [`Neovim::new_local`], and run the io future on the same executor.
+///
+/// ```
+/// use std::{cell::Cell, rc::Rc};
+///
+/// use nvim_rs::{
+///   create::smol::LocalSpawning,
+///   testing::{script::Script, PipeWriter},
+///   LocalHandler, Neovim, Value,
+/// };
+/// use smol::LocalExecutor;
+///
+/// #[derive(Clone, Default)]
+/// struct Counter(Rc<Cell<u64>>);
+///
+/// impl LocalHandler for Counter {
+///   type Writer = PipeWriter;
+///
+///   async fn handle_notify(
+///     &self,
+///     _name: String,
+///     _args: Vec<Value>,
+///     _neovim: Neovim<PipeWriter>,
+///   ) {
+///     self.0.set(self.0.get() + 1);
+///   }
+/// }
+///
+/// let mut script = Script::new();
+/// script.notify("tick", vec![]).notify("tick", vec![]);
+/// let (reader, writer, serve) = script.start();
+///
+/// let executor = Rc::new(LocalExecutor::new());
+/// let counter = Counter::default();
+/// let handler = LocalSpawning::new(counter.clone(), executor.clone());
+/// let (_nvim, io) = Neovim::new_local(reader, writer, handler);
+///
+/// smol::block_on(executor.run(async {
+///   let io = executor.spawn(io);
+///   serve.await.unwrap();
+///   io.await.unwrap();
+/// }));
+/// assert_eq!(2, counter.0.get());
+/// ```
+pub struct LocalSpawning<H> {
+  handler: H,
+  executor: Rc<LocalExecutor<'static>>,
+}
+
+impl<H> Clone for LocalSpawning<H>
+where
+  H: Clone,
+{
+  fn clone(&self) -> Self {
+    LocalSpawning {
+      handler: self.handler.clone(),
+      executor: self.executor.clone(),
+    }
+  }
+}
+
+impl<H> LocalSpawning<H>
+where
+  H: LocalHandler,
+{
+  pub fn new(handler: H, executor: Rc<LocalExecutor<'static>>) -> Self {
+    LocalSpawning { handler, executor }
+  }
+
+  /// The wrapped handler.
+  pub fn handler(&self) -> &H {
+    &self.handler
+  }
+}
+
+impl<H> LocalHandler for LocalSpawning<H>
+where
+  H: LocalHandler,
+{
+  type Writer = H::Writer;
+
+  fn handle_request(
+    &self,
+    name: String,
+    args: Vec<Value>,
+    neovim: Neovim<Self::Writer>,
+  ) -> impl Future<Output = Result<Value, Value>> {
+    self.handler.handle_request(name, args, neovim)
+  }
+
+  fn handle_notify(
+    &self,
+    name: String,
+    args: Vec<Value>,
+    neovim: Neovim<Self::Writer>,
+  ) -> impl Future<Output = ()> {
+    self.handler.handle_notify(name, args, neovim)
+  }
+
+  fn handle_error(
+    &self,
+    error: SessionError,
+    neovim: Neovim<Self::Writer>,
+  ) -> impl Future<Output = ()> {
+    self.handler.handle_error(error, neovim)
+  }
+}
+
+impl<H> LocalSpawner for LocalSpawning<H>
+where
+  H: LocalHandler,
+{
+  type Handle = ();
+
+  fn spawn_local<Fut>(&self, future: Fut) -> Self::Handle
+  where
+    Fut: Future<Output = ()> + 'static,
+  {
+    self.executor.spawn(future).detach();
   }
 }
 
@@ -38,7 +181,24 @@ pub async fn new_tcp<A, H>(
   handler: H,
 ) -> io::Result<(
   Neovim<TcpStream>,
-  Task<Result<(), Box<LoopError>>>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = TcpStream>,
+  A: AsyncToSocketAddrs,
+{
+  new_tcp_with_options(addr, handler, &SessionOptions::default()).await
+}
+
+/// Like [`new_tcp`](crate::create::smol::new_tcp), but configure the session
+/// with `options`.
+pub async fn new_tcp_with_options<A, H>(
+  addr: A,
+  handler: H,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<TcpStream>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
 )>
 where
   H: Handler<Writer = TcpStream>,
@@ -47,7 +207,7 @@ where
   let stream = TcpStream::connect(addr).await?;
   let (reader, writer) = (stream.clone(), stream);
   let (neovim, io) =
-    Neovim::<TcpStream>::new(reader, writer, handler);
+    Neovim::<TcpStream>::new_with_options(reader, writer, handler, options);
   let io_handle = spawn(io);
 
   Ok((neovim, io_handle))
@@ -61,7 +221,24 @@ pub async fn new_path<H, P: AsRef<Path> + Clone>(
   handler: H,
 ) -> io::Result<(
   Neovim<UnixStream>,
-  Task<Result<(), Box<LoopError>>>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = UnixStream> + Send + 'static,
+{
+  new_path_with_options(path, handler, &SessionOptions::default()).await
+}
+
+#[cfg(unix)]
+/// Like [`new_path`](crate::create::smol::new_path), but configure the session
+/// with `options`.
+pub async fn new_path_with_options<H, P: AsRef<Path> + Clone>(
+  path: P,
+  handler: H,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<UnixStream>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
 )>
 where
   H: Handler<Writer = UnixStream> + Send + 'static,
@@ -69,23 +246,756 @@ where
   let stream = UnixStream::connect(path).await?;
   let (reader, writer) = (stream.clone(), stream);
   let (neovim, io) =
-    Neovim::<UnixStream>::new(reader, writer, handler);
+    Neovim::<UnixStream>::new_with_options(reader, writer, handler, options);
   let io_handle = spawn(io);
 
   Ok((neovim, io_handle))
 }
 
+/// Like [`new_tcp`](crate::create::smol::new_tcp), but reconnect whenever
+/// neovim disconnects, see
+/// [`Neovim::new_reconnecting`](crate::neovim::Neovim::new_reconnecting).
+pub async fn new_tcp_reconnecting<A, H>(
+  addr: A,
+  handler: H,
+  backoff: Backoff,
+) -> io::Result<(
+  Neovim<TcpStream>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = TcpStream>,
+  A: AsyncToSocketAddrs + Clone + Send + 'static,
+  A::Iter: Send,
+{
+  new_tcp_reconnecting_with_options(
+    addr,
+    handler,
+    backoff,
+    &SessionOptions::default(),
+  )
+  .await
+}
+
+/// Like [`new_tcp_reconnecting`](crate::create::smol::new_tcp_reconnecting),
+/// but configure the session with `options`.
+pub async fn new_tcp_reconnecting_with_options<A, H>(
+  addr: A,
+  handler: H,
+  backoff: Backoff,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<TcpStream>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = TcpStream>,
+  A: AsyncToSocketAddrs + Clone + Send + 'static,
+  A::Iter: Send,
+{
+  let connect = move || {
+    let addr = addr.clone();
+    async move {
+      let stream = TcpStream::connect(addr).await?;
+      Ok((stream.clone(), stream))
+    }
+  };
+  let (neovim, io) =
+    Neovim::<TcpStream>::new_reconnecting(connect, handler, backoff, options)
+      .await?;
+  let io_handle = spawn(io);
+
+  Ok((neovim, io_handle))
+}
+
+#[cfg(unix)]
+/// Like [`new_path`](crate::create::smol::new_path), but reconnect whenever
+/// neovim disconnects, see
+/// [`Neovim::new_reconnecting`](crate::neovim::Neovim::new_reconnecting).
+pub async fn new_path_reconnecting<H, P>(
+  path: P,
+  handler: H,
+  backoff: Backoff,
+) -> io::Result<(
+  Neovim<UnixStream>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = UnixStream>,
+  P: AsRef<Path> + Clone + Send + 'static,
+{
+  new_path_reconnecting_with_options(
+    path,
+    handler,
+    backoff,
+    &SessionOptions::default(),
+  )
+  .await
+}
+
+#[cfg(unix)]
+/// Like [`new_path_reconnecting`](crate::create::smol::new_path_reconnecting),
+/// but configure the session with `options`.
+pub async fn new_path_reconnecting_with_options<H, P>(
+  path: P,
+  handler: H,
+  backoff: Backoff,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<UnixStream>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = UnixStream>,
+  P: AsRef<Path> + Clone + Send + 'static,
+{
+  let connect = move || {
+    let path = path.clone();
+    async move {
+      let stream = UnixStream::connect(path).await?;
+      Ok((stream.clone(), stream))
+    }
+  };
+  let (neovim, io) =
+    Neovim::<UnixStream>::new_reconnecting(connect, handler, backoff, options)
+      .await?;
+  let io_handle = spawn(io);
+
+  Ok((neovim, io_handle))
+}
+
+/// A connection made by [`connect`], either via tcp or via unix socket.
+#[cfg(unix)]
+pub type AnyConnection = Either<TcpStream, UnixStream>;
+
+#[cfg(unix)]
+/// Connect to a neovim instance at `addr`, via tcp or via unix socket
+/// depending on the address. E.g. use
+/// [`ServerAddress::from_env`](crate::create::address::ServerAddress::from_env)
+/// to connect to the instance that started this process. This is currently
+/// only available on Unix for smol.
+pub async fn connect<H>(
+  addr: &ServerAddress,
+  handler: H,
+) -> io::Result<(
+  Neovim<AnyConnection>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = AnyConnection>,
+{
+  connect_with_options(addr, handler, &SessionOptions::default()).await
+}
+
+#[cfg(unix)]
+/// Like [`connect`](crate::create::smol::connect), but configure the session
+/// with `options`.
+pub async fn connect_with_options<H>(
+  addr: &ServerAddress,
+  handler: H,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<AnyConnection>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = AnyConnection>,
+{
+  let stream = match *addr {
+    ServerAddress::Tcp(ref addr) => {
+      Either::Left(TcpStream::connect(addr.as_str()).await?)
+    }
+    ServerAddress::Path(ref path) => {
+      Either::Right(UnixStream::connect(path).await?)
+    }
+  };
+  let (reader, writer) = (stream.clone(), stream);
+  let (neovim, io) =
+    Neovim::<AnyConnection>::new_with_options(reader, writer, handler, options);
+  let io_handle = spawn(io);
+
+  Ok((neovim, io_handle))
+}
+
+#[cfg(unix)]
+/// The neovim instances that are running, along with the result of
+/// `nvim_get_api_info` for each. The addresses are taken from
+/// [`candidates`](crate::create::address::candidates), and every one of
+/// them is probed. This is currently only available on Unix for smol.
+pub async fn list_servers() -> Vec<(ServerAddress, Vec<Value>)> {
+  let probes = candidates().into_iter().map(|addr| async move {
+    let (neovim, _io) = connect(&addr, Dummy::new())
+      .await
+      .inspect_err(|e| debug!("Cannot connect to {}: {}", addr, e))
+      .ok()?;
+    let info = probe(neovim).await?;
+    Some((addr, info))
+  });
+
+  join_all(probes).await.into_iter().flatten().collect()
+}
+
+/// Connect to a neovim instance by spawning a new one
+pub async fn new_child<H>(
+  handler: H,
+) -> io::Result<(
+  Neovim<ChildStdin>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+  Child,
+)>
+where
+  H: Handler<Writer = ChildStdin> + Send + 'static,
+{
+  new_child_with_options(handler, &SessionOptions::default()).await
+}
+
+/// Like [`new_child`](crate::create::smol::new_child), but configure the
+/// session with `options`.
+pub async fn new_child_with_options<H>(
+  handler: H,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<ChildStdin>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+  Child,
+)>
+where
+  H: Handler<Writer = ChildStdin> + Send + 'static,
+{
+  if cfg!(target_os = "windows") {
+    new_child_path_with_options("nvim.exe", handler, options).await
+  } else {
+    new_child_path_with_options("nvim", handler, options).await
+  }
+}
+
+/// Connect to a neovim instance by spawning a new one
+pub async fn new_child_path<H, S: AsRef<Path>>(
+  program: S,
+  handler: H,
+) -> io::Result<(
+  Neovim<ChildStdin>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+  Child,
+)>
+where
+  H: Handler<Writer = ChildStdin> + Send + 'static,
+{
+  new_child_path_with_options(program, handler, &SessionOptions::default())
+    .await
+}
+
+/// Like [`new_child_path`](crate::create::smol::new_child_path), but configure
+/// the session with `options`.
+pub async fn new_child_path_with_options<H, S: AsRef<Path>>(
+  program: S,
+  handler: H,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<ChildStdin>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+  Child,
+)>
+where
+  H: Handler<Writer = ChildStdin> + Send + 'static,
+{
+  new_child_cmd_with_options(
+    Command::new(program.as_ref()).arg("--embed"),
+    handler,
+    options,
+  )
+  .await
+}
+
+/// Connect to a neovim instance by spawning a new one
+///
+/// stdin/stdout will be rewritten to `Stdio::piped()`
+pub async fn new_child_cmd<H>(
+  cmd: &mut Command,
+  handler: H,
+) -> io::Result<(
+  Neovim<ChildStdin>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+  Child,
+)>
+where
+  H: Handler<Writer = ChildStdin> + Send + 'static,
+{
+  new_child_cmd_with_options(cmd, handler, &SessionOptions::default()).await
+}
+
+/// Like [`new_child_cmd`](crate::create::smol::new_child_cmd), but configure
+/// the session with `options`.
+pub async fn new_child_cmd_with_options<H>(
+  cmd: &mut Command,
+  handler: H,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<ChildStdin>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+  Child,
+)>
+where
+  H: Handler<Writer = ChildStdin> + Send + 'static,
+{
+  let mut child = cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()?;
+  let (stdout, stdin) = child_pipes(&mut child)?;
+
+  let (neovim, io) =
+    Neovim::<ChildStdin>::new_with_options(stdout, stdin, handler, options);
+  let io_handle = spawn(io);
+
+  Ok((neovim, io_handle, child))
+}
+
+/// Connect to a neovim instance by spawning a new one and send a handshake
+/// message. Unlike `new_child_cmd`, this function is tolerant to extra
+/// data in the reader before the handshake response is received.
+///
+/// `message` should be a unique string that is normally not found in the
+/// stdout, see [`Neovim::handshake`](crate::neovim::Neovim::handshake).
+pub async fn new_child_handshake_cmd<H>(
+  cmd: &mut Command,
+  handler: H,
+  message: &str,
+) -> Result<
+  (
+    Neovim<ChildStdin>,
+    Task<Result<ShutdownReason, Box<LoopError>>>,
+    Child,
+    HandshakeInfo,
+  ),
+  Box<HandshakeError>,
+>
+where
+  H: Handler<Writer = ChildStdin> + Send + 'static,
+{
+  new_child_handshake_cmd_with_options(
+    cmd,
+    handler,
+    message,
+    &SessionOptions::default(),
+  )
+  .await
+}
+
+/// Like
+/// [`new_child_handshake_cmd`](crate::create::smol::new_child_handshake_cmd),
+/// but configure the session with `options`.
+pub async fn new_child_handshake_cmd_with_options<H>(
+  cmd: &mut Command,
+  handler: H,
+  message: &str,
+  options: &SessionOptions,
+) -> Result<
+  (
+    Neovim<ChildStdin>,
+    Task<Result<ShutdownReason, Box<LoopError>>>,
+    Child,
+    HandshakeInfo,
+  ),
+  Box<HandshakeError>,
+>
+where
+  H: Handler<Writer = ChildStdin> + Send + 'static,
+{
+  let mut child = cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()?;
+  let (stdout, stdin) = child_pipes(&mut child)?;
+
+  let (neovim, io, info) = Neovim::<ChildStdin>::handshake_with_options(
+    stdout, stdin, handler, message, options,
+  )
+  .await?;
+  let io_handle = spawn(io);
+
+  Ok((neovim, io_handle, child, info))
+}
+
+/// A neovim child spawned by [`spawn_child`].
+///
+/// Whether dropping this kills neovim depends on
+/// [`ChildBuilder::set_kill_on_drop`]. The isolated directory of the child,
+/// if any, is removed when this is dropped.
+#[derive(Debug)]
+pub struct NeovimChild {
+  child: Child,
+  handshake: Option<HandshakeInfo>,
+  // Only kept around to be removed on drop. Declared after `child`, so that
+  // neovim is killed before its directory is removed.
+  isolated: Option<IsolatedDir>,
+}
+
+impl NeovimChild {
+  /// The underlying process.
+  pub fn child(&mut self) -> &mut Child {
+    &mut self.child
+  }
+
+  /// What neovim reported in response to the handshake, if one was requested
+  /// via [`ChildBuilder::set_handshake`].
+  #[must_use]
+  pub fn handshake_info(&self) -> Option<&HandshakeInfo> {
+    self.handshake.as_ref()
+  }
+
+  /// The directory containing the XDG directories of an isolated child, see
+  /// [`ChildBuilder::set_isolated`].
+  #[must_use]
+  pub fn isolated_dir(&self) -> Option<&Path> {
+    self.isolated.as_ref().map(IsolatedDir::path)
+  }
+
+  /// Wait for neovim to exit, and return its exit status.
+  pub async fn wait(&mut self) -> io::Result<ExitStatus> {
+    self.child.status().await
+  }
+}
+
+/// Connect to a neovim instance by spawning a new one as configured by
+/// `builder`.
+///
+/// Errors while spawning neovim are returned as
+/// [`HandshakeError::LaunchError`], whether or not a handshake was
+/// requested.
+pub async fn spawn_child<H>(
+  builder: &ChildBuilder,
+  handler: H,
+) -> Result<
+  (
+    Neovim<ChildStdin>,
+    Task<Result<ShutdownReason, Box<LoopError>>>,
+    NeovimChild,
+  ),
+  Box<HandshakeError>,
+>
+where
+  H: Handler<Writer = ChildStdin> + Send + 'static,
+{
+  spawn_child_with_options(builder, handler, &SessionOptions::default()).await
+}
+
+/// Like [`spawn_child`](crate::create::smol::spawn_child), but configure the
+/// session with `options`.
+pub async fn spawn_child_with_options<H>(
+  builder: &ChildBuilder,
+  handler: H,
+  options: &SessionOptions,
+) -> Result<
+  (
+    Neovim<ChildStdin>,
+    Task<Result<ShutdownReason, Box<LoopError>>>,
+    NeovimChild,
+  ),
+  Box<HandshakeError>,
+>
+where
+  H: Handler<Writer = ChildStdin> + Send + 'static,
+{
+  let isolated = if builder.isolated {
+    Some(IsolatedDir::new()?)
+  } else {
+    None
+  };
+  let mut cmd =
+    Command::from(builder.command(isolated.as_ref().map(IsolatedDir::path)));
+  let mut child = cmd.kill_on_drop(builder.kill_on_drop).spawn()?;
+
+  if let Some(stderr) = child.stderr.take() {
+    spawn(async move {
+      let mut lines = BufReader::new(stderr).lines();
+      while let Some(line) = lines.next().await {
+        match line {
+          Ok(line) => warn!("nvim: {}", line),
+          Err(e) => {
+            debug!("Cannot read stderr of nvim: {}", e);
+            break;
+          }
+        }
+      }
+    })
+    .detach();
+  }
+
+  let (stdout, stdin) = child_pipes(&mut child)?;
+  let (neovim, io, handshake) = match builder.handshake {
+    Some(ref message) => {
+      let (neovim, io, info) = Neovim::<ChildStdin>::handshake_with_options(
+        stdout, stdin, handler, message, options,
+      )
+      .await?;
+      (neovim, spawn(io), Some(info))
+    }
+    None => {
+      let (neovim, io) =
+        Neovim::<ChildStdin>::new_with_options(stdout, stdin, handler, options);
+      (neovim, spawn(io), None)
+    }
+  };
+
+  Ok((
+    neovim,
+    io,
+    NeovimChild {
+      child,
+      handshake,
+      isolated,
+    },
+  ))
+}
+
+/// Take the pipes to talk to a child spawned with piped stdin and stdout.
+fn child_pipes(child: &mut Child) -> io::Result<(ChildStdout, ChildStdin)> {
+  let stdout = child
+    .stdout
+    .take()
+    .ok_or_else(|| io::Error::other("Can't open stdout"))?;
+  let stdin = child
+    .stdin
+    .take()
+    .ok_or_else(|| io::Error::other("Can't open stdin"))?;
+
+  Ok((stdout, stdin))
+}
+
 /// Connect to the neovim instance that spawned this process over stdin/stdout
 pub async fn new_parent<H>(
   handler: H,
-) -> io::Result<(Neovim<Unblock<File>>, Task<Result<(), Box<LoopError>>>)>
+) -> io::Result<(
+  Neovim<Unblock<File>>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = Unblock<File>>,
+{
+  new_parent_with_options(handler, &SessionOptions::default()).await
+}
+
+/// Like [`new_parent`](crate::create::smol::new_parent), but configure the
+/// session with `options`.
+pub async fn new_parent_with_options<H>(
+  handler: H,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<Unblock<File>>,
+  Task<Result<ShutdownReason, Box<LoopError>>>,
+)>
 where
   H: Handler<Writer = Unblock<File>>,
 {
   let sout = Unblock::new(unbuffered_stdout()?);
   let sin = Unblock::new(std::io::stdin());
-  let (neovim, io) = Neovim::<Unblock<File>>::new(sin, sout, handler);
+  let (neovim, io) =
+    Neovim::<Unblock<File>>::new_with_options(sin, sout, handler, options);
   let io_handle = spawn(io);
 
   Ok((neovim, io_handle))
 }
+
+/// Accepts connections neovim makes to us via tcp, e.g. with
+/// `sockconnect("tcp", addr, {"rpc": v:true})`. See
+/// [`listen_tcp`](crate::create::smol::listen_tcp).
+pub struct TcpServer<F> {
+  listener: TcpListener,
+  factory: F,
+  options: SessionOptions,
+}
+
+/// Listen for connections from neovim via tcp. For each connection, a handler
+/// is created by calling `factory`.
+pub async fn listen_tcp<A, F, H>(
+  addr: A,
+  factory: F,
+) -> io::Result<TcpServer<F>>
+where
+  A: AsyncToSocketAddrs,
+  F: FnMut() -> H,
+  H: Handler<Writer = TcpStream>,
+{
+  listen_tcp_with_options(addr, factory, &SessionOptions::default()).await
+}
+
+/// Like [`listen_tcp`](crate::create::smol::listen_tcp), but configure the
+/// session of every connection with `options`.
+///
+/// The options are cloned for each session, so a recorder set in them
+/// records all connections into the same transcript.
+pub async fn listen_tcp_with_options<A, F, H>(
+  addr: A,
+  factory: F,
+  options: &SessionOptions,
+) -> io::Result<TcpServer<F>>
+where
+  A: AsyncToSocketAddrs,
+  F: FnMut() -> H,
+  H: Handler<Writer = TcpStream>,
+{
+  Ok(TcpServer {
+    listener: TcpListener::bind(addr).await?,
+    factory,
+    options: options.clone(),
+  })
+}
+
+impl<F, H> TcpServer<F>
+where
+  F: FnMut() -> H,
+  H: Handler<Writer = TcpStream>,
+{
+  /// The address the server is listening on. Useful when binding to port 0.
+  pub fn local_addr(&self) -> io::Result<SocketAddr> {
+    self.listener.local_addr()
+  }
+
+  /// Wait for the next connection, and start a session on it.
+  pub async fn accept(
+    &mut self,
+  ) -> io::Result<(
+    Neovim<TcpStream>,
+    Task<Result<ShutdownReason, Box<LoopError>>>,
+  )> {
+    let (stream, addr) = self.listener.accept().await?;
+    debug!("Accepted connection from {}", addr);
+
+    let (reader, writer) = (stream.clone(), stream);
+    let (neovim, io) = Neovim::<TcpStream>::new_with_options(
+      reader,
+      writer,
+      (self.factory)(),
+      &self.options,
+    );
+    let io_handle = spawn(io);
+
+    Ok((neovim, io_handle))
+  }
+}
+
+#[cfg(unix)]
+/// Accepts connections neovim makes to us via unix socket, e.g. with
+/// `sockconnect("pipe", path, {"rpc": v:true})`. See
+/// [`listen_path`](crate::create::smol::listen_path).
+pub struct PathServer<F> {
+  listener: UnixListener,
+  factory: F,
+  options: SessionOptions,
+}
+
+#[cfg(unix)]
+/// Listen for connections from neovim via unix socket. For each connection, a
+/// handler is created by calling `factory`. This is currently only available
+/// on Unix for smol.
+///
+/// This fails if `path` already exists. The socket file is not removed when
+/// the server is dropped.
+pub async fn listen_path<P, F, H>(
+  path: P,
+  factory: F,
+) -> io::Result<PathServer<F>>
+where
+  P: AsRef<Path>,
+  F: FnMut() -> H,
+  H: Handler<Writer = UnixStream>,
+{
+  listen_path_with_options(path, factory, &SessionOptions::default()).await
+}
+
+#[cfg(unix)]
+/// Like [`listen_path`](crate::create::smol::listen_path), but configure the
+/// session of every connection with `options`.
+///
+/// The options are cloned for each session, so a recorder set in them
+/// records all connections into the same transcript.
+pub async fn listen_path_with_options<P, F, H>(
+  path: P,
+  factory: F,
+  options: &SessionOptions,
+) -> io::Result<PathServer<F>>
+where
+  P: AsRef<Path>,
+  F: FnMut() -> H,
+  H: Handler<Writer = UnixStream>,
+{
+  Ok(PathServer {
+    listener: UnixListener::bind(path)?,
+    factory,
+    options: options.clone(),
+  })
+}
+
+#[cfg(unix)]
+impl<F, H> PathServer<F>
+where
+  F: FnMut() -> H,
+  H: Handler<Writer = UnixStream>,
+{
+  /// Wait for the next connection, and start a session on it.
+  pub async fn accept(
+    &mut self,
+  ) -> io::Result<(
+    Neovim<UnixStream>,
+    Task<Result<ShutdownReason, Box<LoopError>>>,
+  )> {
+    let (stream, _) = self.listener.accept().await?;
+
+    let (reader, writer) = (stream.clone(), stream);
+    let (neovim, io) = Neovim::<UnixStream>::new_with_options(
+      reader,
+      writer,
+      (self.factory)(),
+      &self.options,
+    );
+    let io_handle = spawn(io);
+
+    Ok((neovim, io_handle))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::time::Duration;
+
+  use smol::{future, Timer};
+
+  use crate::testing::{script::Script, PipeWriter};
+
+  /// Answers requests only after yielding to the executor.
+  #[derive(Clone)]
+  struct Slow;
+
+  impl Handler for Slow {
+    type Writer = PipeWriter;
+
+    async fn handle_request(
+      &self,
+      name: String,
+      _args: Vec<Value>,
+      _neovim: Neovim<PipeWriter>,
+    ) -> Result<Value, Value> {
+      Timer::after(Duration::from_millis(10)).await;
+      Ok(Value::from(name))
+    }
+  }
+
+  #[test]
+  fn test_spawned_tasks_keep_running() {
+    let mut script = Script::new();
+    script
+      .request("slow", vec![])
+      .expect_response(Ok(Value::from("slow")));
+    let (reader, writer, serve) = script.start();
+
+    smol::block_on(async {
+      let (_nvim, io) = Neovim::new(reader, writer, Slow);
+      let io = spawn(io);
+
+      // A cancelled task never answers the request
+      let timeout = async {
+        Timer::after(Duration::from_secs(5)).await;
+        panic!("The request was not answered");
+      };
+      future::or(serve, timeout).await.unwrap();
+      assert_eq!(ShutdownReason::Disconnected, io.await.unwrap());
+    });
+  }
+}
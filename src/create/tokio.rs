@@ -3,17 +3,20 @@
 use std::{
   future::Future,
   io::{self, Error, ErrorKind},
+  net::SocketAddr,
   path::Path,
-  process::Stdio,
+  process::{ExitStatus, Stdio},
 };
 
 use tokio::{
   fs::File as TokioFile,
-  io::{split, stdin, WriteHalf},
-  net::{TcpStream, ToSocketAddrs},
+  io::{
+    split, stdin, AsyncBufReadExt, AsyncRead, AsyncWrite, BufReader, WriteHalf,
+  },
+  net::{TcpListener, TcpStream, ToSocketAddrs},
   process::{Child, ChildStdin, Command},
   spawn,
-  task::JoinHandle,
+  task::{spawn_local, JoinHandle},
 };
 
 #[cfg(unix)]
@@ -21,15 +24,34 @@ type Connection = tokio::net::UnixStream;
 #[cfg(windows)]
 type Connection = tokio::net::windows::named_pipe::NamedPipeClient;
 
-use tokio_util::compat::{
-  Compat, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
+/// A connection accepted by a [`PathServer`].
+#[cfg(unix)]
+pub type ServerConnection = tokio::net::UnixStream;
+/// A connection accepted by a [`PathServer`].
+#[cfg(windows)]
+pub type ServerConnection = tokio::net::windows::named_pipe::NamedPipeServer;
+
+/// A connection made by [`connect`], either via tcp or via
+/// unix socket (Unix) or named pipe (Windows).
+pub type AnyConnection = Either<TcpStream, Connection>;
+
+use futures::future::join_all;
+use tokio_util::{
+  compat::{Compat, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt},
+  either::Either,
 };
 
 use crate::{
-  create::{unbuffered_stdout, Spawner},
+  create::{
+    address::{candidates, probe, ServerAddress},
+    child::{ChildBuilder, IsolatedDir},
+    unbuffered_stdout, LocalSpawner, Spawner,
+  },
   error::{HandshakeError, LoopError},
-  neovim::Neovim,
-  Handler,
+  neovim::{HandshakeInfo, Neovim, ShutdownReason},
+  rpc::handler::Dummy,
+  sessionoptions::{Backoff, SessionOptions},
+  Handler, LocalHandler, Value,
 };
 
 impl<H> Spawner for H
@@ -46,13 +68,46 @@ where
   }
 }
 
+impl<H> LocalSpawner for H
+where
+  H: LocalHandler,
+{
+  type Handle = JoinHandle<()>;
+
+  /// Panics if not called from inside a
+  /// [`LocalSet`](tokio::task::LocalSet).
+  fn spawn_local<Fut>(&self, future: Fut) -> Self::Handle
+  where
+    Fut: Future<Output = ()> + 'static,
+  {
+    spawn_local(future)
+  }
+}
+
 /// Connect to a neovim instance via tcp
 pub async fn new_tcp<A, H>(
   addr: A,
   handler: H,
 ) -> io::Result<(
   Neovim<Compat<WriteHalf<TcpStream>>>,
-  JoinHandle<Result<(), Box<LoopError>>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = Compat<WriteHalf<TcpStream>>>,
+  A: ToSocketAddrs,
+{
+  new_tcp_with_options(addr, handler, &SessionOptions::default()).await
+}
+
+/// Like [`new_tcp`](crate::create::tokio::new_tcp), but configure the session
+/// with `options`.
+pub async fn new_tcp_with_options<A, H>(
+  addr: A,
+  handler: H,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<Compat<WriteHalf<TcpStream>>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
 )>
 where
   H: Handler<Writer = Compat<WriteHalf<TcpStream>>>,
@@ -60,10 +115,11 @@ where
 {
   let stream = TcpStream::connect(addr).await?;
   let (reader, writer) = split(stream);
-  let (neovim, io) = Neovim::<Compat<WriteHalf<TcpStream>>>::new(
+  let (neovim, io) = Neovim::<Compat<WriteHalf<TcpStream>>>::new_with_options(
     reader.compat(),
     writer.compat_write(),
     handler,
+    options,
   );
   let io_handle = spawn(io);
 
@@ -76,49 +132,245 @@ pub async fn new_path<H, P: AsRef<Path> + Clone>(
   handler: H,
 ) -> io::Result<(
   Neovim<Compat<WriteHalf<Connection>>>,
-  JoinHandle<Result<(), Box<LoopError>>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
 )>
 where
   H: Handler<Writer = Compat<WriteHalf<Connection>>> + Send + 'static,
 {
-  let stream = {
-    #[cfg(unix)]
-    {
-      use tokio::net::UnixStream;
+  new_path_with_options(path, handler, &SessionOptions::default()).await
+}
 
-      UnixStream::connect(path).await?
-    }
-    #[cfg(windows)]
-    {
-      use std::time::Duration;
-      use tokio::net::windows::named_pipe::ClientOptions;
-      use tokio::time;
-
-      // From windows-sys so we don't have to depend on that for just this constant
-      // https://docs.rs/windows-sys/latest/windows_sys/Win32/Foundation/constant.ERROR_PIPE_BUSY.html
-      pub const ERROR_PIPE_BUSY: i32 = 231i32;
-
-      // Based on the example in the tokio docs, see explanation there
-      // https://docs.rs/tokio/latest/tokio/net/windows/named_pipe/struct.NamedPipeClient.html
-      let client = loop {
-        match ClientOptions::new().open(path.as_ref()) {
-          Ok(client) => break client,
-          Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY) => (),
-          Err(e) => return Err(e),
-        }
+/// Like [`new_path`](crate::create::tokio::new_path), but configure the session
+/// with `options`.
+pub async fn new_path_with_options<H, P: AsRef<Path> + Clone>(
+  path: P,
+  handler: H,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<Compat<WriteHalf<Connection>>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = Compat<WriteHalf<Connection>>> + Send + 'static,
+{
+  let stream = connect_path(path).await?;
+  let (reader, writer) = split(stream);
+  let (neovim, io) = Neovim::<Compat<WriteHalf<Connection>>>::new_with_options(
+    reader.compat(),
+    writer.compat_write(),
+    handler,
+    options,
+  );
+  let io_handle = spawn(io);
 
-        time::sleep(Duration::from_millis(50)).await;
-      };
+  Ok((neovim, io_handle))
+}
 
-      client
+/// Connect to a neovim instance at `addr`, via tcp or via unix socket (Unix)
+/// or named pipe (Windows) depending on the address. E.g. use
+/// [`ServerAddress::from_env`](crate::create::address::ServerAddress::from_env)
+/// to connect to the instance that started this process.
+pub async fn connect<H>(
+  addr: &ServerAddress,
+  handler: H,
+) -> io::Result<(
+  Neovim<Compat<WriteHalf<AnyConnection>>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = Compat<WriteHalf<AnyConnection>>>,
+{
+  connect_with_options(addr, handler, &SessionOptions::default()).await
+}
+
+/// Like [`connect`](crate::create::tokio::connect), but configure the session
+/// with `options`.
+pub async fn connect_with_options<H>(
+  addr: &ServerAddress,
+  handler: H,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<Compat<WriteHalf<AnyConnection>>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = Compat<WriteHalf<AnyConnection>>>,
+{
+  let stream = match *addr {
+    ServerAddress::Tcp(ref addr) => {
+      Either::Left(TcpStream::connect(addr.as_str()).await?)
     }
+    ServerAddress::Path(ref path) => Either::Right(connect_path(path).await?),
   };
   let (reader, writer) = split(stream);
-  let (neovim, io) = Neovim::<Compat<WriteHalf<Connection>>>::new(
-    reader.compat(),
-    writer.compat_write(),
+  let (neovim, io) =
+    Neovim::<Compat<WriteHalf<AnyConnection>>>::new_with_options(
+      reader.compat(),
+      writer.compat_write(),
+      handler,
+      options,
+    );
+  let io_handle = spawn(io);
+
+  Ok((neovim, io_handle))
+}
+
+/// The neovim instances that are running, along with the result of
+/// `nvim_get_api_info` for each. The addresses are taken from
+/// [`candidates`](crate::create::address::candidates), and every one of
+/// them is probed.
+pub async fn list_servers() -> Vec<(ServerAddress, Vec<Value>)> {
+  let probes = candidates().into_iter().map(|addr| async move {
+    let (neovim, _io) = connect(&addr, Dummy::new())
+      .await
+      .inspect_err(|e| debug!("Cannot connect to {}: {}", addr, e))
+      .ok()?;
+    let info = probe(neovim).await?;
+    Some((addr, info))
+  });
+
+  join_all(probes).await.into_iter().flatten().collect()
+}
+
+/// Connect to a unix socket (Unix) or named pipe (Windows)
+async fn connect_path<P: AsRef<Path>>(path: P) -> io::Result<Connection> {
+  #[cfg(unix)]
+  {
+    use tokio::net::UnixStream;
+
+    UnixStream::connect(path).await
+  }
+  #[cfg(windows)]
+  {
+    use std::time::Duration;
+    use tokio::net::windows::named_pipe::ClientOptions;
+    use tokio::time;
+
+    // From windows-sys so we don't have to depend on that for just this constant
+    // https://docs.rs/windows-sys/latest/windows_sys/Win32/Foundation/constant.ERROR_PIPE_BUSY.html
+    pub const ERROR_PIPE_BUSY: i32 = 231i32;
+
+    // Based on the example in the tokio docs, see explanation there
+    // https://docs.rs/tokio/latest/tokio/net/windows/named_pipe/struct.NamedPipeClient.html
+    let client = loop {
+      match ClientOptions::new().open(path.as_ref()) {
+        Ok(client) => break client,
+        Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY) => (),
+        Err(e) => return Err(e),
+      }
+
+      time::sleep(Duration::from_millis(50)).await;
+    };
+
+    Ok(client)
+  }
+}
+
+/// Like [`new_tcp`](crate::create::tokio::new_tcp), but reconnect whenever
+/// neovim disconnects, see
+/// [`Neovim::new_reconnecting`](crate::neovim::Neovim::new_reconnecting).
+pub async fn new_tcp_reconnecting<A, H>(
+  addr: A,
+  handler: H,
+  backoff: Backoff,
+) -> io::Result<(
+  Neovim<Compat<WriteHalf<TcpStream>>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = Compat<WriteHalf<TcpStream>>>,
+  A: ToSocketAddrs + Clone + Send + 'static,
+{
+  new_tcp_reconnecting_with_options(
+    addr,
     handler,
-  );
+    backoff,
+    &SessionOptions::default(),
+  )
+  .await
+}
+
+/// Like [`new_tcp_reconnecting`](crate::create::tokio::new_tcp_reconnecting),
+/// but configure the session with `options`.
+pub async fn new_tcp_reconnecting_with_options<A, H>(
+  addr: A,
+  handler: H,
+  backoff: Backoff,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<Compat<WriteHalf<TcpStream>>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = Compat<WriteHalf<TcpStream>>>,
+  A: ToSocketAddrs + Clone + Send + 'static,
+{
+  let connect = move || {
+    let addr = addr.clone();
+    async move {
+      let (reader, writer) = split(TcpStream::connect(addr).await?);
+      Ok((reader.compat(), writer.compat_write()))
+    }
+  };
+  let (neovim, io) = Neovim::<Compat<WriteHalf<TcpStream>>>::new_reconnecting(
+    connect, handler, backoff, options,
+  )
+  .await?;
+  let io_handle = spawn(io);
+
+  Ok((neovim, io_handle))
+}
+
+/// Like [`new_path`](crate::create::tokio::new_path), but reconnect whenever
+/// neovim disconnects, see
+/// [`Neovim::new_reconnecting`](crate::neovim::Neovim::new_reconnecting).
+pub async fn new_path_reconnecting<H, P>(
+  path: P,
+  handler: H,
+  backoff: Backoff,
+) -> io::Result<(
+  Neovim<Compat<WriteHalf<Connection>>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = Compat<WriteHalf<Connection>>>,
+  P: AsRef<Path> + Clone + Send + 'static,
+{
+  new_path_reconnecting_with_options(
+    path,
+    handler,
+    backoff,
+    &SessionOptions::default(),
+  )
+  .await
+}
+
+/// Like [`new_path_reconnecting`](crate::create::tokio::new_path_reconnecting),
+/// but configure the session with `options`.
+pub async fn new_path_reconnecting_with_options<H, P>(
+  path: P,
+  handler: H,
+  backoff: Backoff,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<Compat<WriteHalf<Connection>>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+)>
+where
+  H: Handler<Writer = Compat<WriteHalf<Connection>>>,
+  P: AsRef<Path> + Clone + Send + 'static,
+{
+  let connect = move || {
+    let path = path.clone();
+    async move {
+      let (reader, writer) = split(connect_path(path).await?);
+      Ok((reader.compat(), writer.compat_write()))
+    }
+  };
+  let (neovim, io) = Neovim::<Compat<WriteHalf<Connection>>>::new_reconnecting(
+    connect, handler, backoff, options,
+  )
+  .await?;
   let io_handle = spawn(io);
 
   Ok((neovim, io_handle))
@@ -129,16 +381,32 @@ pub async fn new_child<H>(
   handler: H,
 ) -> io::Result<(
   Neovim<Compat<ChildStdin>>,
-  JoinHandle<Result<(), Box<LoopError>>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+  Child,
+)>
+where
+  H: Handler<Writer = Compat<ChildStdin>> + Send + 'static,
+{
+  new_child_with_options(handler, &SessionOptions::default()).await
+}
+
+/// Like [`new_child`](crate::create::tokio::new_child), but configure the
+/// session with `options`.
+pub async fn new_child_with_options<H>(
+  handler: H,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<Compat<ChildStdin>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
   Child,
 )>
 where
   H: Handler<Writer = Compat<ChildStdin>> + Send + 'static,
 {
   if cfg!(target_os = "windows") {
-    new_child_path("nvim.exe", handler).await
+    new_child_path_with_options("nvim.exe", handler, options).await
   } else {
-    new_child_path("nvim", handler).await
+    new_child_path_with_options("nvim", handler, options).await
   }
 }
 
@@ -148,13 +416,36 @@ pub async fn new_child_path<H, S: AsRef<Path>>(
   handler: H,
 ) -> io::Result<(
   Neovim<Compat<ChildStdin>>,
-  JoinHandle<Result<(), Box<LoopError>>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
   Child,
 )>
 where
   H: Handler<Writer = Compat<ChildStdin>> + Send + 'static,
 {
-  new_child_cmd(Command::new(program.as_ref()).arg("--embed"), handler).await
+  new_child_path_with_options(program, handler, &SessionOptions::default())
+    .await
+}
+
+/// Like [`new_child_path`](crate::create::tokio::new_child_path), but configure
+/// the session with `options`.
+pub async fn new_child_path_with_options<H, S: AsRef<Path>>(
+  program: S,
+  handler: H,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<Compat<ChildStdin>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+  Child,
+)>
+where
+  H: Handler<Writer = Compat<ChildStdin>> + Send + 'static,
+{
+  new_child_cmd_with_options(
+    Command::new(program.as_ref()).arg("--embed"),
+    handler,
+    options,
+  )
+  .await
 }
 
 /// Connect to a neovim instance by spawning a new one
@@ -165,7 +456,24 @@ pub async fn new_child_cmd<H>(
   handler: H,
 ) -> io::Result<(
   Neovim<Compat<ChildStdin>>,
-  JoinHandle<Result<(), Box<LoopError>>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+  Child,
+)>
+where
+  H: Handler<Writer = Compat<ChildStdin>> + Send + 'static,
+{
+  new_child_cmd_with_options(cmd, handler, &SessionOptions::default()).await
+}
+
+/// Like [`new_child_cmd`](crate::create::tokio::new_child_cmd), but configure
+/// the session with `options`.
+pub async fn new_child_cmd_with_options<H>(
+  cmd: &mut Command,
+  handler: H,
+  options: &SessionOptions,
+) -> io::Result<(
+  Neovim<Compat<ChildStdin>>,
+  JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
   Child,
 )>
 where
@@ -183,7 +491,9 @@ where
     .ok_or_else(|| Error::new(ErrorKind::Other, "Can't open stdin"))?
     .compat_write();
 
-  let (neovim, io) = Neovim::<Compat<ChildStdin>>::new(stdout, stdin, handler);
+  let (neovim, io) = Neovim::<Compat<ChildStdin>>::new_with_options(
+    stdout, stdin, handler, options,
+  );
   let io_handle = spawn(io);
 
   Ok((neovim, io_handle, child))
@@ -195,7 +505,25 @@ pub async fn new_parent<H>(
 ) -> Result<
   (
     Neovim<Compat<tokio::fs::File>>,
-    JoinHandle<Result<(), Box<LoopError>>>,
+    JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+  ),
+  Error,
+>
+where
+  H: Handler<Writer = Compat<tokio::fs::File>>,
+{
+  new_parent_with_options(handler, &SessionOptions::default()).await
+}
+
+/// Like [`new_parent`](crate::create::tokio::new_parent), but configure the
+/// session with `options`.
+pub async fn new_parent_with_options<H>(
+  handler: H,
+  options: &SessionOptions,
+) -> Result<
+  (
+    Neovim<Compat<tokio::fs::File>>,
+    JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
   ),
   Error,
 >
@@ -204,10 +532,11 @@ where
 {
   let sout = TokioFile::from_std(unbuffered_stdout()?);
 
-  let (neovim, io) = Neovim::<Compat<tokio::fs::File>>::new(
+  let (neovim, io) = Neovim::<Compat<tokio::fs::File>>::new_with_options(
     stdin().compat(),
     sout.compat(),
     handler,
+    options,
   );
   let io_handle = spawn(io);
 
@@ -219,9 +548,7 @@ where
 /// data in the reader before the handshake response is received.
 ///
 /// `message` should be a unique string that is normally not found in the
-/// stdout. Due to the way Neovim packs strings, the length has to be either
-/// less than 20 characters or more than 31 characters long.
-/// See https://github.com/neovim/neovim/issues/32784 for more information.
+/// stdout, see [`Neovim::handshake`](crate::neovim::Neovim::handshake).
 pub async fn new_child_handshake_cmd<H>(
   cmd: &mut Command,
   handler: H,
@@ -229,8 +556,38 @@ pub async fn new_child_handshake_cmd<H>(
 ) -> Result<
   (
     Neovim<Compat<ChildStdin>>,
-    JoinHandle<Result<(), Box<LoopError>>>,
+    JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+    Child,
+    HandshakeInfo,
+  ),
+  Box<HandshakeError>,
+>
+where
+  H: Handler<Writer = Compat<ChildStdin>> + Send + 'static,
+{
+  new_child_handshake_cmd_with_options(
+    cmd,
+    handler,
+    message,
+    &SessionOptions::default(),
+  )
+  .await
+}
+
+/// Like
+/// [`new_child_handshake_cmd`](crate::create::tokio::new_child_handshake_cmd),
+/// but configure the session with `options`.
+pub async fn new_child_handshake_cmd_with_options<H>(
+  cmd: &mut Command,
+  handler: H,
+  message: &str,
+  options: &SessionOptions,
+) -> Result<
+  (
+    Neovim<Compat<ChildStdin>>,
+    JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
     Child,
+    HandshakeInfo,
   ),
   Box<HandshakeError>,
 >
@@ -249,10 +606,341 @@ where
     .ok_or_else(|| Error::new(ErrorKind::Other, "Can't open stdin"))?
     .compat_write();
 
-  let (neovim, io) =
-    Neovim::<Compat<ChildStdin>>::handshake(stdout, stdin, handler, message)
-      .await?;
+  let (neovim, io, info) =
+    Neovim::<Compat<ChildStdin>>::handshake_with_options(
+      stdout, stdin, handler, message, options,
+    )
+    .await?;
   let io_handle = spawn(io);
 
-  Ok((neovim, io_handle, child))
+  Ok((neovim, io_handle, child, info))
+}
+
+/// A neovim child spawned by [`spawn_child`].
+///
+/// Whether dropping this kills neovim depends on
+/// [`ChildBuilder::set_kill_on_drop`]. The isolated directory of the child,
+/// if any, is removed when this is dropped.
+#[derive(Debug)]
+pub struct NeovimChild {
+  child: Child,
+  handshake: Option<HandshakeInfo>,
+  // Only kept around to be removed on drop. Declared after `child`, so that
+  // neovim is killed before its directory is removed.
+  isolated: Option<IsolatedDir>,
+}
+
+impl NeovimChild {
+  /// The underlying process.
+  pub fn child(&mut self) -> &mut Child {
+    &mut self.child
+  }
+
+  /// What neovim reported in response to the handshake, if one was requested
+  /// via [`ChildBuilder::set_handshake`].
+  #[must_use]
+  pub fn handshake_info(&self) -> Option<&HandshakeInfo> {
+    self.handshake.as_ref()
+  }
+
+  /// The directory containing the XDG directories of an isolated child, see
+  /// [`ChildBuilder::set_isolated`].
+  #[must_use]
+  pub fn isolated_dir(&self) -> Option<&Path> {
+    self.isolated.as_ref().map(IsolatedDir::path)
+  }
+
+  /// Wait for neovim to exit, and return its exit status.
+  pub async fn wait(&mut self) -> io::Result<ExitStatus> {
+    self.child.wait().await
+  }
+}
+
+/// Connect to a neovim instance by spawning a new one as configured by
+/// `builder`.
+///
+/// Errors while spawning neovim are returned as
+/// [`HandshakeError::LaunchError`], whether or not a handshake was
+/// requested.
+pub async fn spawn_child<H>(
+  builder: &ChildBuilder,
+  handler: H,
+) -> Result<
+  (Neovim<Compat<ChildStdin>>, IoHandle, NeovimChild),
+  Box<HandshakeError>,
+>
+where
+  H: Handler<Writer = Compat<ChildStdin>> + Send + 'static,
+{
+  spawn_child_with_options(builder, handler, &SessionOptions::default()).await
+}
+
+/// Like [`spawn_child`](crate::create::tokio::spawn_child), but configure the
+/// session with `options`.
+pub async fn spawn_child_with_options<H>(
+  builder: &ChildBuilder,
+  handler: H,
+  options: &SessionOptions,
+) -> Result<
+  (Neovim<Compat<ChildStdin>>, IoHandle, NeovimChild),
+  Box<HandshakeError>,
+>
+where
+  H: Handler<Writer = Compat<ChildStdin>> + Send + 'static,
+{
+  let isolated = if builder.isolated {
+    Some(IsolatedDir::new()?)
+  } else {
+    None
+  };
+  let mut cmd =
+    Command::from(builder.command(isolated.as_ref().map(IsolatedDir::path)));
+  let mut child = cmd.kill_on_drop(builder.kill_on_drop).spawn()?;
+
+  if let Some(stderr) = child.stderr.take() {
+    spawn(async move {
+      let mut lines = BufReader::new(stderr).lines();
+      loop {
+        match lines.next_line().await {
+          Ok(Some(line)) => warn!("nvim: {}", line),
+          Ok(None) => break,
+          Err(e) => {
+            debug!("Cannot read stderr of nvim: {}", e);
+            break;
+          }
+        }
+      }
+    });
+  }
+
+  let stdout = child
+    .stdout
+    .take()
+    .ok_or_else(|| Error::other("Can't open stdout"))?
+    .compat();
+  let stdin = child
+    .stdin
+    .take()
+    .ok_or_else(|| Error::other("Can't open stdin"))?
+    .compat_write();
+
+  let (neovim, io, handshake) = match builder.handshake {
+    Some(ref message) => {
+      let (neovim, io, info) =
+        Neovim::<Compat<ChildStdin>>::handshake_with_options(
+          stdout, stdin, handler, message, options,
+        )
+        .await?;
+      (neovim, spawn(io), Some(info))
+    }
+    None => {
+      let (neovim, io) = Neovim::<Compat<ChildStdin>>::new_with_options(
+        stdout, stdin, handler, options,
+      );
+      (neovim, spawn(io), None)
+    }
+  };
+
+  Ok((
+    neovim,
+    io,
+    NeovimChild {
+      child,
+      handshake,
+      isolated,
+    },
+  ))
+}
+
+/// The handle of a spawned io future.
+type IoHandle = JoinHandle<Result<ShutdownReason, Box<LoopError>>>;
+
+/// Start a session on `stream`, and spawn its io future.
+fn start_session<S, H>(
+  stream: S,
+  handler: H,
+  options: &SessionOptions,
+) -> (Neovim<Compat<WriteHalf<S>>>, IoHandle)
+where
+  S: AsyncRead + AsyncWrite + Send + 'static,
+  H: Handler<Writer = Compat<WriteHalf<S>>>,
+{
+  let (reader, writer) = split(stream);
+  let (neovim, io) = Neovim::<Compat<WriteHalf<S>>>::new_with_options(
+    reader.compat(),
+    writer.compat_write(),
+    handler,
+    options,
+  );
+
+  (neovim, spawn(io))
+}
+
+/// Accepts connections neovim makes to us via tcp, e.g. with
+/// `sockconnect("tcp", addr, {"rpc": v:true})`. See
+/// [`listen_tcp`](crate::create::tokio::listen_tcp).
+pub struct TcpServer<F> {
+  listener: TcpListener,
+  factory: F,
+  options: SessionOptions,
+}
+
+/// Listen for connections from neovim via tcp. For each connection, a handler
+/// is created by calling `factory`.
+pub async fn listen_tcp<A, F, H>(
+  addr: A,
+  factory: F,
+) -> io::Result<TcpServer<F>>
+where
+  A: ToSocketAddrs,
+  F: FnMut() -> H,
+  H: Handler<Writer = Compat<WriteHalf<TcpStream>>>,
+{
+  listen_tcp_with_options(addr, factory, &SessionOptions::default()).await
+}
+
+/// Like [`listen_tcp`](crate::create::tokio::listen_tcp), but configure the
+/// session of every connection with `options`.
+///
+/// The options are cloned for each session, so a recorder set in them
+/// records all connections into the same transcript.
+pub async fn listen_tcp_with_options<A, F, H>(
+  addr: A,
+  factory: F,
+  options: &SessionOptions,
+) -> io::Result<TcpServer<F>>
+where
+  A: ToSocketAddrs,
+  F: FnMut() -> H,
+  H: Handler<Writer = Compat<WriteHalf<TcpStream>>>,
+{
+  Ok(TcpServer {
+    listener: TcpListener::bind(addr).await?,
+    factory,
+    options: options.clone(),
+  })
+}
+
+impl<F, H> TcpServer<F>
+where
+  F: FnMut() -> H,
+  H: Handler<Writer = Compat<WriteHalf<TcpStream>>>,
+{
+  /// The address the server is listening on. Useful when binding to port 0.
+  pub fn local_addr(&self) -> io::Result<SocketAddr> {
+    self.listener.local_addr()
+  }
+
+  /// Wait for the next connection, and start a session on it.
+  pub async fn accept(
+    &mut self,
+  ) -> io::Result<(
+    Neovim<Compat<WriteHalf<TcpStream>>>,
+    JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+  )> {
+    let (stream, addr) = self.listener.accept().await?;
+    debug!("Accepted connection from {}", addr);
+
+    Ok(start_session(stream, (self.factory)(), &self.options))
+  }
+}
+
+/// Accepts connections neovim makes to us via unix socket (Unix) or named
+/// pipe (Windows), e.g. with `sockconnect("pipe", path, {"rpc": v:true})`.
+/// See [`listen_path`](crate::create::tokio::listen_path).
+pub struct PathServer<F> {
+  #[cfg(unix)]
+  listener: tokio::net::UnixListener,
+  /// The pipe instance waiting for the next client
+  #[cfg(windows)]
+  pipe: ServerConnection,
+  #[cfg(windows)]
+  path: std::path::PathBuf,
+  factory: F,
+  options: SessionOptions,
+}
+
+/// Listen for connections from neovim via unix socket (Unix) or named pipe
+/// (Windows). For each connection, a handler is created by calling `factory`.
+///
+/// On Unix, this fails if `path` already exists. The socket file is not
+/// removed when the server is dropped.
+pub async fn listen_path<P, F, H>(
+  path: P,
+  factory: F,
+) -> io::Result<PathServer<F>>
+where
+  P: AsRef<Path>,
+  F: FnMut() -> H,
+  H: Handler<Writer = Compat<WriteHalf<ServerConnection>>>,
+{
+  listen_path_with_options(path, factory, &SessionOptions::default()).await
+}
+
+/// Like [`listen_path`](crate::create::tokio::listen_path), but configure the
+/// session of every connection with `options`.
+///
+/// The options are cloned for each session, so a recorder set in them
+/// records all connections into the same transcript.
+pub async fn listen_path_with_options<P, F, H>(
+  path: P,
+  factory: F,
+  options: &SessionOptions,
+) -> io::Result<PathServer<F>>
+where
+  P: AsRef<Path>,
+  F: FnMut() -> H,
+  H: Handler<Writer = Compat<WriteHalf<ServerConnection>>>,
+{
+  #[cfg(unix)]
+  {
+    Ok(PathServer {
+      listener: tokio::net::UnixListener::bind(path)?,
+      factory,
+      options: options.clone(),
+    })
+  }
+  #[cfg(windows)]
+  {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let pipe = ServerOptions::new()
+      .first_pipe_instance(true)
+      .create(path.as_ref())?;
+    Ok(PathServer {
+      pipe,
+      path: path.as_ref().to_owned(),
+      factory,
+      options: options.clone(),
+    })
+  }
+}
+
+impl<F, H> PathServer<F>
+where
+  F: FnMut() -> H,
+  H: Handler<Writer = Compat<WriteHalf<ServerConnection>>>,
+{
+  /// Wait for the next connection, and start a session on it.
+  pub async fn accept(
+    &mut self,
+  ) -> io::Result<(
+    Neovim<Compat<WriteHalf<ServerConnection>>>,
+    JoinHandle<Result<ShutdownReason, Box<LoopError>>>,
+  )> {
+    #[cfg(unix)]
+    let stream = self.listener.accept().await?.0;
+    #[cfg(windows)]
+    let stream = {
+      use tokio::net::windows::named_pipe::ServerOptions;
+
+      self.pipe.connect().await?;
+      // Create the instance for the next client before handing out this one,
+      // so clients always find the pipe
+      let next = ServerOptions::new().create(&self.path)?;
+      std::mem::replace(&mut self.pipe, next)
+    };
+
+    Ok(start_session(stream, (self.factory)(), &self.options))
+  }
 }
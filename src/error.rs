@@ -7,7 +7,7 @@
 //!
 //! ### Overview
 //!
-//! Errors can originate in three ways:
+//! Errors can originate in four ways:
 //!
 //!   1. Failure of a request to neovim is communicated by a
 //!      [`CallError`](crate::error::CallError).
@@ -16,6 +16,10 @@
 //!   3. A failure to connect to neovim when starting up via one of the
 //!      [`new_*`](crate::create) functions  is communicated by an
 //!      [`io::Error`](std::io::Error).
+//!   4. A failure that can't be returned to a caller, like a response to
+//!      neovim that could not be sent, is communicated by a
+//!      [`SessionError`](crate::error::SessionError) passed to the
+//!      [`Handler`](crate::rpc::handler::Handler).
 //!
 //! Most errors should probably be treated as fatal, and the application should
 //! just exit.
@@ -29,12 +33,12 @@
 //!
 //! Use
 //! [`CallError::is_channel_closed`](crate::error::CallError::is_channel_closed)
-//! or
-//! [`LoopError::is_channel_closed`](crate::error::LoopError::is_channel_closed)
 //! to determine if the error originates from a closed channel. This means
 //! either neovim closed the channel actively, or neovim was closed. Often, this
-//! is not seen as a real error, but the signal for the plugin to quit. Again,
-//! see the [example](crate::examples::scorched_earth).
+//! is not seen as a real error, but the signal for the plugin to quit. The io
+//! loop itself does not return an error in that case, but a
+//! [`ShutdownReason`](crate::neovim::ShutdownReason). Again, see the
+//! [example](crate::examples::scorched_earth).
 use std::{
   error::Error, fmt, fmt::Display, io, io::ErrorKind, ops::RangeInclusive,
   sync::Arc,
@@ -45,6 +49,9 @@ use rmpv::{
   decode::Error as RmpvDecodeError, encode::Error as RmpvEncodeError, Value,
 };
 
+#[cfg(feature = "testing")]
+use crate::rpc::model::RpcMessage;
+
 /// A message from neovim had an invalid format
 ///
 /// This should be very basically non-existent, since it would indicate a bug in
@@ -69,6 +76,9 @@ pub enum InvalidMessage {
   InvalidRequestName(u64, Value),
   /// The msgid of a request or response was not decodable into a u64
   InvalidMsgid(Value),
+  /// TooLarge(needed, max) means that the message needs at least `needed`
+  /// bytes, which is more than the maximum size of `max` bytes
+  TooLarge(usize, usize),
 }
 
 impl Error for InvalidMessage {}
@@ -105,6 +115,10 @@ impl Display for InvalidMessage {
       InvalidMsgid(val) => {
         write!(fmt, "Msgid of message not decodable into u64: '{val}'")
       }
+      TooLarge(needed, max) => write!(
+        fmt,
+        "Message needs at least {needed} bytes, the maximum is {max}"
+      ),
     }
   }
 }
@@ -244,6 +258,22 @@ pub enum CallError {
   /// The response from neovim contained a [`Value`](rmpv::Value) of the wrong
   /// type
   WrongValueType(Value),
+  /// Neovim did not respond to the request in time, see
+  /// [`call_with_timeout`](crate::neovim::Neovim::call_with_timeout).
+  ///
+  /// Fields:
+  ///
+  /// 0. The name of the called method
+  Timeout(String),
+  /// The session has been shut down, see
+  /// [`shutdown`](crate::neovim::Neovim::shutdown). Either the request was
+  /// made afterwards, or it was still waiting for a response when the session
+  /// was closed.
+  ///
+  /// Fields:
+  ///
+  /// 0. The name of the called method
+  SessionClosed(String),
 }
 
 impl Error for CallError {
@@ -252,7 +282,10 @@ impl Error for CallError {
       CallError::SendError(ref e, _) => Some(e),
       CallError::InternalReceiveError(ref e, _) => Some(e),
       CallError::DecodeError(ref e, _) => Some(e.as_ref()),
-      CallError::NeovimError(_, _) | CallError::WrongValueType(_) => None,
+      CallError::NeovimError(_, _)
+      | CallError::WrongValueType(_)
+      | CallError::Timeout(_)
+      | CallError::SessionClosed(_) => None,
     }
   }
 }
@@ -261,6 +294,9 @@ impl CallError {
   /// Determine if the error originated from a closed channel. This is generally
   /// used to close a plugin from neovim's side, and so most of the time should
   /// not be treated as a real error, but a signal to finish the program.
+  ///
+  /// A session that was shut down on our side is not a closed channel, see
+  /// [`CallError::SessionClosed`](crate::error::CallError::SessionClosed).
   #[must_use]
   pub fn is_channel_closed(&self) -> bool {
     match *self {
@@ -303,6 +339,12 @@ impl Display for CallError {
       CallError::WrongValueType(ref val) => {
         write!(fmt, "Wrong value type: '{val}'")
       }
+      CallError::Timeout(ref s) => {
+        write!(fmt, "Timed out waiting for response to request '{s}'")
+      }
+      CallError::SessionClosed(ref s) => {
+        write!(fmt, "Session closed, no response to request '{s}'")
+      }
     }
   }
 }
@@ -349,19 +391,31 @@ pub enum LoopError {
   /// 0. The msgid of the request the response was sent for
   /// 1. The response from neovim
   InternalSendResponseError(u64, Result<Value, Value>),
+  /// The handler loop has finished, so a message from neovim could not be
+  /// passed on to the handler.
+  ///
+  /// Fields:
+  ///
+  /// 0. The method of the request or notification
+  HandlerLoopClosed(String),
 }
 
 impl Error for LoopError {
   fn source(&self) -> Option<&(dyn Error + 'static)> {
     match *self {
       LoopError::MsgidNotFound(_)
-      | LoopError::InternalSendResponseError(_, _) => None,
+      | LoopError::InternalSendResponseError(_, _)
+      | LoopError::HandlerLoopClosed(_) => None,
       LoopError::DecodeError(ref e, _) => Some(e.as_ref()),
     }
   }
 }
 
 impl LoopError {
+  /// Determine if the error originated from a closed channel. The io loop
+  /// reports a closed channel as a
+  /// [`ShutdownReason`](crate::neovim::ShutdownReason) instead, so this is
+  /// only true for errors constructed elsewhere.
   #[must_use]
   pub fn is_channel_closed(&self) -> bool {
     if let LoopError::DecodeError(ref err, _) = *self {
@@ -405,6 +459,11 @@ impl Display for LoopError {
         "Request {i}: Could not send response, which was {:?}",
         res
       ),
+      Self::HandlerLoopClosed(ref method) => write!(
+        fmt,
+        "Could not pass '{method}' to the handler, the handler loop has \
+         finished"
+      ),
     }
   }
 }
@@ -435,18 +494,21 @@ pub enum HandshakeError {
   ///
   /// 0. The underlying error
   SendError(EncodeError),
-  /// Sending the request to neovim has failed.
+  /// Receiving the response from neovim has failed.
   ///
   /// Fields:
   ///
   /// 0. The underlying error
-  /// 1. The data read so far
+  /// 1. The output before the response, or all output read so far if no
+  ///    response was found
   RecvError(io::Error, String),
-  /// Unexpected response received
+  /// No response was received before the output ended, or the response was
+  /// malformed
   ///
   /// Fields:
   ///
-  /// 0. The data read so far
+  /// 0. The output before the response, or all output read so far if no
+  ///    response was found
   UnexpectedResponse(String),
   /// The launch of Neovim failed
   ///
@@ -454,6 +516,13 @@ pub enum HandshakeError {
   ///
   /// 0. The underlying error
   LaunchError(io::Error),
+  /// Neovim answered the handshake with an error
+  ///
+  /// Fields:
+  ///
+  /// 0. The error
+  /// 1. The output before the response
+  ErrorResponse(Value, String),
 }
 
 impl From<Box<EncodeError>> for Box<HandshakeError> {
@@ -480,7 +549,7 @@ impl Error for HandshakeError {
       Self::SendError(ref s) => Some(s),
       Self::RecvError(ref s, _) => Some(s),
       Self::LaunchError(ref s) => Some(s),
-      Self::UnexpectedResponse(_) => None,
+      Self::UnexpectedResponse(_) | Self::ErrorResponse(..) => None,
     }
   }
 }
@@ -501,6 +570,108 @@ impl Display for HandshakeError {
         fmt,
         "Error receiving handshake response, unexpected output:\n{output}"
       ),
+      Self::ErrorResponse(ref error, ref output) => write!(
+        fmt,
+        "Error response to handshake '{error}', unexpected output:\n{output}"
+      ),
+    }
+  }
+}
+
+/// A failure in the session that can't be returned to a caller, passed to
+/// [`Handler::handle_error`](crate::rpc::handler::Handler::handle_error).
+#[derive(Debug)]
+pub enum SessionError {
+  /// Sending the response to a request from neovim failed. Neovim is still
+  /// waiting for the response.
+  ///
+  /// Fields:
+  ///
+  /// 0. The msgid of the request
+  /// 1. The underlying error
+  ResponseError(u64, EncodeError),
+  /// Neovim sent an `nvim_error_event` notification, because it could not
+  /// handle one of our messages (see `:h api-global-events`).
+  ///
+  /// Fields:
+  ///
+  /// 0. Neovim's error type (see `:h api`)
+  /// 1. Neovim's error message
+  ErrorEvent(i64, String),
+}
+
+impl Error for SessionError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match *self {
+      Self::ResponseError(_, ref e) => Some(e),
+      Self::ErrorEvent(_, _) => None,
+    }
+  }
+}
+
+impl Display for SessionError {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+    match *self {
+      Self::ResponseError(msgid, _) => {
+        write!(fmt, "Error sending response to request {msgid}")
+      }
+      Self::ErrorEvent(i, ref s) => {
+        write!(fmt, "Neovim reported an error: {i} - '{s}'")
+      }
+    }
+  }
+}
+
+/// A [`Script`](crate::testing::script::Script) found that the client did not
+/// behave as scripted, or the connection of a script or a
+/// [`FakeNeovim`](crate::testing::fake::FakeNeovim) to the client failed.
+#[cfg(feature = "testing")]
+#[derive(Debug)]
+pub enum ScriptError {
+  /// The client sent a message other than the one expected next.
+  ///
+  /// Fields:
+  ///
+  /// 0. The message that was expected
+  /// 1. The message the client sent
+  UnexpectedMessage(RpcMessage, RpcMessage),
+  /// The client closed the connection while a message was still expected.
+  ///
+  /// Fields:
+  ///
+  /// 0. The message that was expected
+  Disconnected(RpcMessage),
+  /// Reading a message from the client failed.
+  ReadError(DecodeError),
+  /// Sending a message to the client failed.
+  WriteError(EncodeError),
+}
+
+#[cfg(feature = "testing")]
+impl Error for ScriptError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match *self {
+      Self::UnexpectedMessage(_, _) | Self::Disconnected(_) => None,
+      Self::ReadError(ref e) => Some(e),
+      Self::WriteError(ref e) => Some(e),
+    }
+  }
+}
+
+#[cfg(feature = "testing")]
+impl Display for ScriptError {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+    match *self {
+      Self::UnexpectedMessage(ref expected, ref got) => write!(
+        fmt,
+        "Expected message {expected:?}, but the client sent {got:?}"
+      ),
+      Self::Disconnected(ref expected) => write!(
+        fmt,
+        "Client disconnected while expecting message {expected:?}"
+      ),
+      Self::ReadError(_) => write!(fmt, "Error reading message from client"),
+      Self::WriteError(_) => write!(fmt, "Error sending message to client"),
     }
   }
 }
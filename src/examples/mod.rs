@@ -12,8 +12,8 @@
 //!
 //! ### `quitting`
 //!
-//! An example showing how to handle quitting in a plugin by catching a [`closed
-//! channel`](crate::error::CallError::is_channel_closed).
+//! An example showing how to handle quitting in a plugin, which ends the io
+//! loop with a [`ShutdownReason`](crate::neovim::ShutdownReason).
 //!
 //!
 //! ## `scorched_earth`
@@ -31,7 +31,7 @@
 //! ```sh
 //! cargo build --examples --features use_tokio --release
 //! cargo build --examples --features use_async-std --release
-//! cargo build --examples --features use_neovim_lib --release
+//! cargo build --examples --features use_blocking --release
 //! ```
 //!
 //! (the features aren't all compatible, so you need to run those separately
@@ -42,10 +42,10 @@
 //! note that tokio's runtime takes parameters that influence performance.
 //! Tweaking those, I found the runtimes don't differ by much.
 //!
-//! The benchmark of `neovim_lib` (called `bench_sync`) can't be designed the
-//! way the others are, since they use nested requests. I tried to get around
-//! that somewhat sneakily, but it's not 100% clear those benchmarks are
-//! equivalent (but, if anything, they should favor neovim-lib a tad).
+//! The benchmark of the [`blocking`](crate::blocking) client (called
+//! `bench_sync`) can't be designed the way the others are, since they use
+//! nested requests. I tried to get around that somewhat sneakily, but it's not
+//! 100% clear those benchmarks are equivalent.
 pub mod handler_drop;
 pub mod quitting;
 pub mod scorched_earth;
@@ -8,7 +8,8 @@
 //! example. Note that this final request _will_ receive an error, since it will
 //! not get an answer from neovim.
 //!
-//! Also note that all other pending requests will receive an EOF error as well.
+//! In both cases, the io loop finishes with a
+//! [`ShutdownReason`](crate::neovim::ShutdownReason) instead of an error.
 //!
 //! ## Usage
 //!
@@ -40,14 +41,11 @@
 //! possible, by calling [`tokio::spawn`](tokio::spawn) that in turn calls out
 //! to the surrounding runtime.
 //!
-//! * Any shutdown logic should be handled after the channel was closed. We
-//! don't actually need to inspect the error, since the application will shut
-//! down no matter what. If we need access to our handler for that, we should
-//! implement [`Drop`](std::ops::Drop) for it, see
+//! * Any shutdown logic should be handled after the io loop has finished. A
+//! closed channel is not an error, so the io loop returns `Ok` with the
+//! [`ShutdownReason`](crate::neovim::ShutdownReason). If we need access to our
+//! handler for that, we should implement [`Drop`](std::ops::Drop) for it, see
 //! [`handler_drop`](crate::examples::handler_drop).
 //!
 //! * The last command (the one that instructs neovim to close the channel) will
-//! not receive an answer anymore, but an error. We just show the error and its
-//! source for demonstation purposes. We use the
-//! [`is_channel_closed`](crate::error::CallError::is_channel_closed) method
-//! to verify that the error originates from this.
+//! not receive an answer anymore, but an error. We simply ignore it.
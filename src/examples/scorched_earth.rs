@@ -60,15 +60,12 @@
 //!   [`Drop`](std::ops::Drop) for it, see the
 //!   [example](crate::examples::handler_drop).
 //!
-//! * After the IO task has finished, we're inspecting the errors to see why it
-//! went. A join error simply gets printed, then we inspect potential errors
-//! from the io loop itself. First, if we did not see a general reader error, we
-//! try to send some last notification to the neovim user. Secondly, we quietly
-//! ignore the channel being closed, because this usually means that it was
-//! closed by neovim, which isn't always an error.
-//!
-//!   *Note*: A closed channel could still mean an error, so the plugin has the
-//!   option to react to this.
+//! * After the IO task has finished, we're inspecting the result to see why it
+//! went. A join error simply gets printed. If neovim closed the channel, the io
+//! loop returns a [`ShutdownReason`](crate::neovim::ShutdownReason), and we
+//! have nothing to do. Otherwise, if we did not see a general reader error, we
+//! try to send some last notification to the neovim user, and print the error
+//! with its sources.
 //!
 //! * As with the other examples, we implement [`Spawn`](futures::task::Spawn)
 //! for our `NeovimHandler` most trivially.
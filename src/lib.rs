@@ -29,6 +29,10 @@ extern crate rmpv;
 extern crate log;
 
 pub mod rpc;
+#[cfg(feature = "use_blocking")]
+pub mod blocking;
+#[cfg(feature = "use_blocking")]
+pub mod blocking_api;
 #[macro_use]
 pub mod neovim;
 pub mod error;
@@ -36,14 +40,19 @@ pub mod examples;
 pub mod exttypes;
 pub mod neovim_api;
 pub mod neovim_api_manual;
+pub mod registry;
+pub mod sessionoptions;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod uioptions;
 
 pub mod create;
 
 pub use crate::{
   exttypes::{Buffer, Tabpage, Window},
-  neovim::Neovim,
-  rpc::handler::Handler,
+  neovim::{Neovim, ShutdownReason},
+  rpc::handler::{Handler, LocalHandler},
+  sessionoptions::{Backoff, OverflowPolicy, SessionOptions},
   uioptions::{UiAttachOptions, UiOption},
 };
 
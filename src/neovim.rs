@@ -1,35 +1,49 @@
 //! An active neovim session.
 use std::{
+  collections::HashMap,
   future::Future,
+  io,
+  num::NonZeroUsize,
+  pin::pin,
   sync::{
-    atomic::{AtomicU64, Ordering},
-    Arc,
+    atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    Arc, Mutex as StdMutex, MutexGuard, PoisonError,
   },
+  task::Poll,
+  time::{Duration, Instant},
 };
 
 use futures::{
   channel::{
-    mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    mpsc::{self, unbounded, TrySendError, UnboundedReceiver, UnboundedSender},
     oneshot,
   },
-  future,
-  io::{AsyncRead, AsyncReadExt, AsyncWrite},
+  future::{self, Either, FutureExt},
+  io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, Cursor},
   lock::Mutex,
-  sink::SinkExt,
-  stream::StreamExt,
+  stream::{Stream, StreamExt},
+  task::AtomicWaker,
   TryFutureExt,
 };
 
+#[cfg(feature = "recorder")]
+use crate::rpc::recorder::{Direction, Recorder};
 use crate::{
-  create::Spawner,
-  error::{CallError, DecodeError, EncodeError, HandshakeError, LoopError},
+  create::{HandlerSpawner, LocalSpawner, Spawner, TaskSpawner},
+  error::{
+    CallError, DecodeError, EncodeError, HandshakeError, InvalidMessage,
+    LoopError, SessionError,
+  },
   rpc::{
-    handler::Handler,
+    handler::{run_local, Handler, LocalHandler, LocalProxy},
+    metrics::{HandlerTimer, MessageType, Metrics, Stats},
     model,
-    model::{IntoVal, RpcMessage},
+    model::{Decoder, IntoVal, RpcMessage},
   },
+  sessionoptions::{Backoff, OverflowPolicy, SessionOptions},
   uioptions::UiAttachOptions,
 };
+use futures_timer::Delay;
 use rmpv::Value;
 
 /// Pack the given arguments into a `Vec<Value>`, suitable for using it for a
@@ -48,7 +62,166 @@ macro_rules! call_args {
 
 type ResponseResult = Result<Result<Value, Value>, Arc<DecodeError>>;
 
-type Queue = Arc<Mutex<Vec<(u64, oneshot::Sender<ResponseResult>)>>>;
+/// The requests sent to neovim that are still waiting for a response, keyed
+/// by their msgid.
+///
+/// The lock is only held for single map operations and never across an
+/// `.await`, so a blocking mutex is cheaper than an async one here.
+#[derive(Default)]
+pub(crate) struct Queue {
+  inner: StdMutex<QueueInner>,
+  /// Woken when the queue becomes empty
+  emptied: AtomicWaker,
+}
+
+#[derive(Default)]
+struct QueueInner {
+  senders: HashMap<u64, oneshot::Sender<ResponseResult>>,
+  /// Set when the session has been shut down, no new requests are accepted
+  closed: bool,
+}
+
+impl Queue {
+  fn lock(&self) -> MutexGuard<'_, QueueInner> {
+    // No code can panic while holding the lock, but don't propagate a
+    // poisoned lock anyways
+    self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  /// Insert the sender for a request. If the queue has been closed, the
+  /// sender is given back.
+  fn insert(
+    &self,
+    msgid: u64,
+    sender: oneshot::Sender<ResponseResult>,
+  ) -> Result<(), oneshot::Sender<ResponseResult>> {
+    let mut inner = self.lock();
+    if inner.closed {
+      return Err(sender);
+    }
+    inner.senders.insert(msgid, sender);
+    Ok(())
+  }
+
+  fn remove(&self, msgid: u64) -> Option<oneshot::Sender<ResponseResult>> {
+    let mut inner = self.lock();
+    let sender = inner.senders.remove(&msgid);
+    if inner.senders.is_empty() {
+      self.emptied.wake();
+    }
+    sender
+  }
+
+  fn drain(&self) -> Vec<(u64, oneshot::Sender<ResponseResult>)> {
+    let senders = self.lock().senders.drain().collect();
+    self.emptied.wake();
+    senders
+  }
+
+  /// Don't accept any new requests, and drop the senders of all pending ones.
+  fn close(&self) {
+    let mut inner = self.lock();
+    inner.closed = true;
+    inner.senders.clear();
+  }
+
+  fn is_empty(&self) -> bool {
+    self.lock().senders.is_empty()
+  }
+
+  fn len(&self) -> usize {
+    self.lock().senders.len()
+  }
+}
+
+/// A request to shut down the session, see
+/// [`shutdown`](crate::neovim::Neovim::shutdown).
+struct ShutdownRequest {
+  /// Wait for requests and handler tasks to finish
+  wait: bool,
+  /// Notified when the session has been shut down
+  done: oneshot::Sender<()>,
+}
+
+/// The state of a session shared by all clones of a
+/// [`Neovim`](crate::neovim::Neovim).
+pub(crate) struct Session {
+  /// Set when shutting down, no new requests or notifications are sent
+  closed: AtomicBool,
+  /// Set when the writer is about to be closed, nothing at all is sent
+  /// anymore, not even by handlers
+  stopped: AtomicBool,
+  /// The number of spawned handler tasks that have not finished yet
+  running_tasks: AtomicUsize,
+  /// Woken when all handler tasks have finished
+  tasks_finished: AtomicWaker,
+  shutdown_sender: UnboundedSender<ShutdownRequest>,
+  /// Records all messages sent and received, if set
+  #[cfg(feature = "recorder")]
+  recorder: Option<Recorder>,
+  /// Collects metrics, if enabled
+  metrics: Option<Arc<Metrics>>,
+}
+
+impl Session {
+  fn new(
+    #[cfg(feature = "recorder")] recorder: Option<Recorder>,
+    metrics: Option<Arc<Metrics>>,
+  ) -> (Self, UnboundedReceiver<ShutdownRequest>) {
+    let (shutdown_sender, shutdown_receiver) = unbounded();
+    let session = Session {
+      closed: AtomicBool::new(false),
+      stopped: AtomicBool::new(false),
+      running_tasks: AtomicUsize::new(0),
+      tasks_finished: AtomicWaker::new(),
+      shutdown_sender,
+      #[cfg(feature = "recorder")]
+      recorder,
+      metrics,
+    };
+
+    (session, shutdown_receiver)
+  }
+
+  fn is_closed(&self) -> bool {
+    self.closed.load(Ordering::SeqCst)
+  }
+
+  fn is_stopped(&self) -> bool {
+    self.stopped.load(Ordering::SeqCst)
+  }
+
+  /// Register a new handler task. It counts as running until the returned
+  /// value is dropped.
+  fn start_task(self: &Arc<Self>) -> RunningTask {
+    self.running_tasks.fetch_add(1, Ordering::SeqCst);
+    RunningTask(self.clone())
+  }
+}
+
+/// A running handler task, see
+/// [`Session::start_task`](crate::neovim::Session::start_task).
+struct RunningTask(Arc<Session>);
+
+impl Drop for RunningTask {
+  fn drop(&mut self) {
+    if self.0.running_tasks.fetch_sub(1, Ordering::SeqCst) == 1 {
+      self.0.tasks_finished.wake();
+    }
+  }
+}
+
+/// A request to add to the queue right before it is written, see
+/// [`write_locked`](crate::neovim::write_locked).
+type Registration = (u64, oneshot::Sender<ResponseResult>);
+
+/// An encoded message for the writer task, the request to add to the queue if
+/// it is one, and the channel to report the result of writing it.
+type PendingWrite = (
+  Vec<u8>,
+  Option<Registration>,
+  oneshot::Sender<Result<(), Box<EncodeError>>>,
+);
 
 /// An active Neovim session.
 pub struct Neovim<W>
@@ -56,8 +229,14 @@ where
   W: AsyncWrite + Send + Unpin + 'static,
 {
   pub(crate) writer: Arc<Mutex<W>>,
-  pub(crate) queue: Queue,
+  pub(crate) queue: Arc<Queue>,
   pub(crate) msgid_counter: Arc<AtomicU64>,
+  /// If set, messages are written by the writer task instead of directly
+  pub(crate) write_sender: Option<UnboundedSender<PendingWrite>>,
+  pub(crate) session: Arc<Session>,
+  /// Set on the instances passed to the handler, so running handlers can still
+  /// call neovim while the session waits for them to finish
+  pub(crate) in_handler: bool,
 }
 
 impl<W> Clone for Neovim<W>
@@ -69,6 +248,9 @@ where
       writer: self.writer.clone(),
       queue: self.queue.clone(),
       msgid_counter: self.msgid_counter.clone(),
+      write_sender: self.write_sender.clone(),
+      session: self.session.clone(),
+      in_handler: self.in_handler,
     }
   }
 }
@@ -94,45 +276,511 @@ where
     handler: H,
   ) -> (
     Neovim<<H as Handler>::Writer>,
-    impl Future<Output = Result<(), Box<LoopError>>>,
+    impl Future<Output = Result<ShutdownReason, Box<LoopError>>>,
+  )
+  where
+    R: AsyncRead + Send + Unpin + 'static,
+    H: Handler<Writer = W> + Spawner,
+  {
+    Self::new_with_options(reader, writer, handler, &SessionOptions::default())
+  }
+
+  /// Like [`new`](Neovim::new), but configure the session with `options`.
+  pub fn new_with_options<H, R>(
+    reader: R,
+    writer: W,
+    handler: H,
+    options: &SessionOptions,
+  ) -> (
+    Neovim<<H as Handler>::Writer>,
+    impl Future<Output = Result<ShutdownReason, Box<LoopError>>>
+      + use<H, R, W>,
+  )
+  where
+    R: AsyncRead + Send + Unpin + 'static,
+    H: Handler<Writer = W> + Spawner,
+  {
+    let spawner = HandlerSpawner(handler.clone());
+    Self::new_spawning(reader, writer, handler, spawner, options)
+  }
+
+  /// Like [`new_with_options`](Neovim::new_with_options), but spawn the tasks
+  /// of the session with `spawner` instead of the handler.
+  pub(crate) fn new_spawning<H, R, S>(
+    reader: R,
+    writer: W,
+    handler: H,
+    spawner: S,
+    options: &SessionOptions,
+  ) -> (
+    Neovim<<H as Handler>::Writer>,
+    impl Future<Output = Result<ShutdownReason, Box<LoopError>>>
+      + use<H, R, S, W>,
+  )
+  where
+    R: AsyncRead + Send + Unpin + 'static,
+    H: Handler<Writer = W>,
+    S: TaskSpawner,
+  {
+    let (req, receivers) = Self::from_writer(writer, options);
+    let fut =
+      req
+        .clone()
+        .run(reader, handler, spawner, receivers, options.clone());
+
+    (req, fut)
+  }
+
+  /// Like [`new`](Neovim::new), but for a handler that isn't `Send`. The
+  /// handler is run on a single-threaded executor, see
+  /// [`LocalSpawner`](crate::create::LocalSpawner).
+  ///
+  /// The io future isn't `Send` either, and needs to be spawned on that same
+  /// executor, e.g. with `spawn_local` on a tokio `LocalSet`.
+  pub fn new_local<H, R>(
+    reader: R,
+    writer: W,
+    handler: H,
+  ) -> (
+    Neovim<<H as LocalHandler>::Writer>,
+    impl Future<Output = Result<ShutdownReason, Box<LoopError>>>
+      + use<H, R, W>,
+  )
+  where
+    R: AsyncRead + Send + Unpin + 'static,
+    H: LocalHandler<Writer = W> + LocalSpawner,
+  {
+    Self::new_local_with_options(
+      reader,
+      writer,
+      handler,
+      &SessionOptions::default(),
+    )
+  }
+
+  /// Like [`new_local`](Neovim::new_local), but configure the session with
+  /// `options`.
+  pub fn new_local_with_options<H, R>(
+    reader: R,
+    writer: W,
+    handler: H,
+    options: &SessionOptions,
+  ) -> (
+    Neovim<<H as LocalHandler>::Writer>,
+    impl Future<Output = Result<ShutdownReason, Box<LoopError>>>
+      + use<H, R, W>,
   )
+  where
+    R: AsyncRead + Send + Unpin + 'static,
+    H: LocalHandler<Writer = W> + LocalSpawner,
+  {
+    let (proxy, messages) = LocalProxy::new();
+    let (req, io) = Self::new_with_options(reader, writer, proxy, options);
+    let fut = async move {
+      let io = pin!(io);
+      let local = pin!(run_local(handler, messages));
+      match future::select(io, local).await {
+        Either::Left((res, _)) => res,
+        // Only happens once the session doesn't pass on anything anymore
+        Either::Right(((), io)) => io.await,
+      }
+    };
+
+    (req, fut)
+  }
+
+  /// Connect to neovim by calling `connect`, and connect again the same way
+  /// whenever neovim disconnects, e.g. because the server restarted. Retries
+  /// are made according to `backoff`. Fails if the first connection can't be
+  /// made.
+  ///
+  /// All clones of the returned instance keep working across reconnections.
+  /// Calls still waiting for a response when neovim disconnects fail, like
+  /// they would without reconnecting. Calls made while reconnecting wait
+  /// until the new connection has been made. Afterwards,
+  /// [`Handler::handle_reconnect`](crate::rpc::handler::Handler::handle_reconnect)
+  /// is run, e.g. to subscribe to events again.
+  ///
+  /// The io future only resolves when the session is shut down, an error
+  /// occurs, or reconnecting has been given up.
+  pub async fn new_reconnecting<H, R, C, Fut>(
+    mut connect: C,
+    handler: H,
+    backoff: Backoff,
+    options: &SessionOptions,
+  ) -> io::Result<(
+    Neovim<<H as Handler>::Writer>,
+    impl Future<Output = Result<ShutdownReason, Box<LoopError>>>
+      + use<H, R, W, C, Fut>,
+  )>
   where
     R: AsyncRead + Send + Unpin + 'static,
     H: Handler<Writer = W> + Spawner,
+    C: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = io::Result<(R, W)>> + Send,
   {
-    let req = Neovim {
+    let (reader, writer) = connect().await?;
+    let (req, receivers) = Self::from_writer(writer, options);
+    let spawner = HandlerSpawner(handler.clone());
+    let fut = req.clone().run_reconnecting(
+      reader,
+      handler,
+      spawner,
+      receivers,
+      options.clone(),
+      connect,
+      backoff,
+    );
+
+    Ok((req, fut))
+  }
+
+  /// Create the instance, and the receiving ends of the writer task (if it is
+  /// enabled in `options`) and of the shutdown requests.
+  fn from_writer(
+    writer: W,
+    options: &SessionOptions,
+  ) -> (Self, SessionReceivers) {
+    let metrics = options.metrics.then(|| Arc::new(Metrics::new()));
+    let (session, shutdown_receiver) = Session::new(
+      #[cfg(feature = "recorder")]
+      options.recorder.clone(),
+      metrics,
+    );
+    let (write_sender, write_receiver) = if options.coalesce_writes {
+      let (sender, receiver) = unbounded();
+      (Some(sender), Some(receiver))
+    } else {
+      (None, None)
+    };
+
+    let instance = Neovim {
       writer: Arc::new(Mutex::new(writer)),
       msgid_counter: Arc::new(AtomicU64::new(0)),
-      queue: Arc::new(Mutex::new(Vec::new())),
+      queue: Arc::new(Queue::default()),
+      write_sender,
+      session: Arc::new(session),
+      in_handler: false,
     };
 
-    let (sender, receiver) = unbounded();
-    let fut = future::try_join(
-      req.clone().io_loop(reader, sender),
-      req.clone().handler_loop(handler, receiver),
+    (
+      instance,
+      SessionReceivers {
+        write: write_receiver,
+        shutdown: shutdown_receiver,
+      },
     )
-    .map_ok(|_| ());
+  }
 
-    (req, fut)
+  /// Run the io loop, the handler loop and the writer task (if enabled) until
+  /// neovim disconnects, one of them fails, or the session is shut down.
+  async fn run<H, R, S>(
+    self,
+    reader: R,
+    handler: H,
+    spawner: S,
+    receivers: SessionReceivers,
+    options: SessionOptions,
+  ) -> Result<ShutdownReason, Box<LoopError>>
+  where
+    R: AsyncRead + Send + Unpin + 'static,
+    H: Handler<Writer = W>,
+    S: TaskSpawner,
+  {
+    let loops = self
+      .clone()
+      .connection_loops(reader, handler, spawner, &options);
+    self.supervise(loops, receivers).await
+  }
+
+  /// Like [`run`](Neovim::run), but reconnect when neovim disconnects.
+  #[allow(clippy::too_many_arguments)]
+  async fn run_reconnecting<H, R, S, C, Fut>(
+    self,
+    reader: R,
+    handler: H,
+    spawner: S,
+    receivers: SessionReceivers,
+    options: SessionOptions,
+    mut connect: C,
+    backoff: Backoff,
+  ) -> Result<ShutdownReason, Box<LoopError>>
+  where
+    R: AsyncRead + Send + Unpin + 'static,
+    H: Handler<Writer = W>,
+    S: TaskSpawner,
+    C: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = io::Result<(R, W)>> + Send,
+  {
+    let neovim = self.clone();
+    let loops = async move {
+      let mut reader = reader;
+      let mut reconnected = false;
+
+      loop {
+        let loops = neovim.clone().connection_loops(
+          reader,
+          handler.clone(),
+          spawner.clone(),
+          &options,
+        );
+        // The hook needs the loops running to get responses from neovim
+        let hook = async {
+          if reconnected {
+            handler.handle_reconnect(neovim.for_handler()).await;
+          }
+          future::pending().await
+        };
+        let reason = future::select(Box::pin(loops), Box::pin(hook))
+          .await
+          .factor_first()
+          .0?;
+        if reason == ShutdownReason::Requested {
+          return Ok(reason);
+        }
+
+        info!("Neovim disconnected ({:?}), reconnecting", reason);
+        reader = match neovim.reconnect(&mut connect, &backoff).await {
+          Some(reader) => reader,
+          None => return Ok(reason),
+        };
+        reconnected = true;
+      }
+    };
+
+    self.supervise(loops, receivers).await
+  }
+
+  /// Connect again, waiting according to `backoff`. On success, the writer is
+  /// replaced and the new reader is returned.
+  ///
+  /// The writer stays locked in the meantime, so new messages wait for the
+  /// new connection instead of being written to the old one.
+  async fn reconnect<R, C, Fut>(
+    &self,
+    connect: &mut C,
+    backoff: &Backoff,
+  ) -> Option<R>
+  where
+    C: FnMut() -> Fut,
+    Fut: Future<Output = io::Result<(R, W)>>,
+  {
+    let mut writer = self.writer.lock().await;
+
+    for (attempt, delay) in backoff.delays().enumerate() {
+      Delay::new(delay).await;
+      match connect().await {
+        Ok((reader, new_writer)) => {
+          *writer = new_writer;
+          // Requests written to the old connection after the io loop ended
+          // won't be answered either
+          let err = DecodeError::ReaderError(io::Error::new(
+            io::ErrorKind::NotConnected,
+            "Reconnected to neovim",
+          ));
+          let _ = self.send_error_to_callers(&self.queue, err);
+          info!("Reconnected to neovim after {} attempts", attempt + 1);
+          return Some(reader);
+        }
+        Err(e) => warn!("Reconnecting to neovim failed: '{}'", e),
+      }
+    }
+
+    error!("Giving up reconnecting to neovim");
+    None
+  }
+
+  /// Run the io loop and the handler loop on one connection, until neovim
+  /// disconnects or one of them fails.
+  fn connection_loops<H, R, S>(
+    self,
+    reader: R,
+    handler: H,
+    spawner: S,
+    options: &SessionOptions,
+  ) -> impl Future<Output = Result<ShutdownReason, Box<LoopError>>>
+  + use<H, R, S, W>
+  where
+    R: AsyncRead + Send + Unpin + 'static,
+    H: Handler<Writer = W>,
+    S: TaskSpawner,
+  {
+    let (sender, receiver) = message_channel(options.channel_capacity);
+    let tokens = options.max_concurrent_requests.map(TaskTokens::new);
+    future::try_join(
+      self.clone().io_loop(
+        reader,
+        sender,
+        options.overflow_policy,
+        options.max_message_size,
+      ),
+      self.handler_loop(
+        handler,
+        spawner,
+        receiver,
+        tokens,
+        options.overflow_policy,
+      ),
+    )
+    .map_ok(|(reason, ())| reason)
+  }
+
+  /// Drive `loops` along with the writer task (if enabled), and shut down the
+  /// session when requested.
+  async fn supervise<L>(
+    self,
+    loops: L,
+    receivers: SessionReceivers,
+  ) -> Result<ShutdownReason, Box<LoopError>>
+  where
+    L: Future<Output = Result<ShutdownReason, Box<LoopError>>>,
+  {
+    let SessionReceivers {
+      write: write_receiver,
+      shutdown: mut shutdown_receiver,
+    } = receivers;
+    let writer = self.writer.clone();
+    let queue = self.queue.clone();
+    let metrics = self.session.metrics.clone();
+    // The writer task only finishes when all instances are dropped, so the
+    // session ends with the other loops
+    let write_loop = Box::pin(async move {
+      if let Some(receiver) = write_receiver {
+        write_loop(writer, queue, metrics, receiver).await;
+      }
+      future::pending().await
+    });
+
+    let mut loops = Box::pin(
+      future::select(Box::pin(loops), write_loop)
+        .map(|either| either.factor_first().0),
+    );
+
+    let mut request =
+      match future::select(loops.as_mut(), shutdown_receiver.next()).await {
+        Either::Left((res, _)) => return res,
+        Either::Right((Some(request), _)) => request,
+        // We're holding a sender in `self`, so this can't happen
+        Either::Right((None, _)) => return loops.await,
+      };
+
+    // Keep running the loops until all requests have been answered and all
+    // handler tasks have finished, or someone asks to close immediately
+    let mut waiting = Vec::new();
+    loop {
+      waiting.push(request.done);
+      if !request.wait {
+        break;
+      }
+
+      let next = future::select(shutdown_receiver.next(), self.drained());
+      match future::select(loops.as_mut(), next).await {
+        Either::Left((res, _)) => return res,
+        Either::Right((Either::Left((Some(next), _)), _)) => request = next,
+        Either::Right(_) => break,
+      }
+    }
+
+    drop(loops);
+    self.session.stopped.store(true, Ordering::SeqCst);
+    self.queue.close();
+    if let Err(e) = self.writer.lock().await.close().await {
+      error!("Error closing the writer: '{}'", e);
+    }
+    for done in waiting {
+      let _ = done.send(());
+    }
+
+    Ok(ShutdownReason::Requested)
+  }
+
+  /// Resolves when there are no more requests waiting for a response, and no
+  /// more handler tasks running.
+  fn drained(&self) -> impl Future<Output = ()> + '_ {
+    future::poll_fn(move |cx| {
+      self.queue.emptied.register(cx.waker());
+      self.session.tasks_finished.register(cx.waker());
+
+      if self.queue.is_empty()
+        && self.session.running_tasks.load(Ordering::SeqCst) == 0
+      {
+        Poll::Ready(())
+      } else {
+        Poll::Pending
+      }
+    })
+  }
+
+  /// Shut down the session gracefully. New calls fail with
+  /// [`CallError::SessionClosed`](crate::error::CallError::SessionClosed),
+  /// and new requests from neovim are answered with an error. Then this waits
+  /// until all pending calls have been answered and all spawned handler tasks
+  /// have finished, before closing the writer. Handlers that are still running
+  /// can call neovim until then. Afterwards, the io future resolves to
+  /// [`ShutdownReason::Requested`](crate::neovim::ShutdownReason::Requested).
+  ///
+  /// If the io future has already finished, this returns immediately. If it
+  /// hasn't been polled yet, this waits until it is polled or dropped.
+  pub async fn shutdown(&self) {
+    self.request_shutdown(true).await;
+  }
+
+  /// Like [`shutdown`](Neovim::shutdown), but don't wait for pending calls
+  /// and handler tasks. Pending calls fail with
+  /// [`CallError::SessionClosed`](crate::error::CallError::SessionClosed).
+  /// Spawned handler tasks keep running, but can't call neovim anymore.
+  pub async fn close(&self) {
+    self.request_shutdown(false).await;
+  }
+
+  async fn request_shutdown(&self, wait: bool) {
+    self.session.closed.store(true, Ordering::SeqCst);
+    if !wait {
+      self.session.stopped.store(true, Ordering::SeqCst);
+    }
+
+    let (done, finished) = oneshot::channel();
+    if self
+      .session
+      .shutdown_sender
+      .unbounded_send(ShutdownRequest { wait, done })
+      .is_ok()
+    {
+      // If the io future is dropped, the sender is dropped as well
+      let _ = finished.await;
+    }
+  }
+
+  /// Whether this instance may not send anything anymore, because the session
+  /// has been shut down. Handlers may keep calling neovim while a
+  /// [`shutdown`](Neovim::shutdown) waits for them.
+  fn is_closed(&self) -> bool {
+    self.session.is_stopped() || (self.session.is_closed() && !self.in_handler)
   }
 
   /// Create a new instance, immediately send a handshake message and
   /// wait for the response. Unlike `new`, this function is tolerant to extra
-  /// data in the reader before the handshake response is received.
+  /// data in the reader before the handshake response is received, e.g. if
+  /// the configuration of an embedded neovim prints something.
   ///
   /// `message` should be a unique string that is normally not found in the
-  /// stdout. Due to the way Neovim packs strings, the length has to be either
-  /// less than 20 characters or more than 31 characters long.
-  /// See https://github.com/neovim/neovim/issues/32784 for more information.
+  /// stdout. Neovim echoes it back, and the first response with the right id
+  /// and the message as its result is taken as the handshake response.
+  /// Anything before it is logged, and reported in the error if no response
+  /// is found.
+  ///
+  /// The channel id and api info are requested along with the handshake.
   pub async fn handshake<H, R>(
-    mut reader: R,
+    reader: R,
     writer: W,
     handler: H,
     message: &str,
   ) -> Result<
     (
       Neovim<<H as Handler>::Writer>,
-      impl Future<Output = Result<(), Box<LoopError>>> + use<H, R, W>,
+      impl Future<Output = Result<ShutdownReason, Box<LoopError>>>
+        + use<H, R, W>,
+      HandshakeInfo,
     ),
     Box<HandshakeError>,
   >
@@ -140,84 +788,115 @@ where
     R: AsyncRead + Send + Unpin + 'static,
     H: Handler<Writer = W> + Spawner,
   {
-    let instance = Neovim {
-      writer: Arc::new(Mutex::new(writer)),
-      msgid_counter: Arc::new(AtomicU64::new(0)),
-      queue: Arc::new(Mutex::new(Vec::new())),
-    };
+    Self::handshake_with_options(
+      reader,
+      writer,
+      handler,
+      message,
+      &SessionOptions::default(),
+    )
+    .await
+  }
 
-    let msgid = instance.msgid_counter.fetch_add(1, Ordering::SeqCst);
-    // Nvim encodes fixed size strings with a length of 20-31 bytes wrong, so
-    // avoid that
-    let msg_len = message.len();
-    assert!(
-      !(20..=31).contains(&msg_len),
-      "The message should be less than 20 characters or more than 31 characters
-      long, but the length is {msg_len}."
-    );
+  /// Like [`handshake`](Neovim::handshake), but configure the session with
+  /// `options`.
+  pub async fn handshake_with_options<H, R>(
+    reader: R,
+    writer: W,
+    handler: H,
+    message: &str,
+    options: &SessionOptions,
+  ) -> Result<
+    (
+      Neovim<<H as Handler>::Writer>,
+      impl Future<Output = Result<ShutdownReason, Box<LoopError>>>
+        + use<H, R, W>,
+      HandshakeInfo,
+    ),
+    Box<HandshakeError>,
+  >
+  where
+    R: AsyncRead + Send + Unpin + 'static,
+    H: Handler<Writer = W> + Spawner,
+  {
+    let spawner = HandlerSpawner(handler.clone());
+    Self::handshake_spawning(reader, writer, handler, spawner, message, options)
+      .await
+  }
 
-    let req = RpcMessage::RpcRequest {
+  /// Like [`handshake_with_options`](Neovim::handshake_with_options), but
+  /// spawn the tasks of the session with `spawner` instead of the handler.
+  pub(crate) async fn handshake_spawning<H, R, S>(
+    mut reader: R,
+    writer: W,
+    handler: H,
+    spawner: S,
+    message: &str,
+    options: &SessionOptions,
+  ) -> Result<
+    (
+      Neovim<<H as Handler>::Writer>,
+      impl Future<Output = Result<ShutdownReason, Box<LoopError>>>
+        + use<H, R, S, W>,
+      HandshakeInfo,
+    ),
+    Box<HandshakeError>,
+  >
+  where
+    R: AsyncRead + Send + Unpin + 'static,
+    H: Handler<Writer = W>,
+    S: TaskSpawner,
+  {
+    let (instance, receivers) = Self::from_writer(writer, options);
+
+    let msgid = instance.msgid_counter.fetch_add(2, Ordering::SeqCst);
+    let echo = RpcMessage::RpcRequest {
       msgid,
       method: "nvim_exec_lua".to_owned(),
-      params: call_args![format!("return '{message}'"), Vec::<Value>::new()],
+      params: call_args!["return ...", call_args![message]],
     };
-    model::encode(instance.writer.clone(), req).await?;
-
-    let expected_resp = RpcMessage::RpcResponse {
-      msgid,
-      error: rmpv::Value::Nil,
-      result: rmpv::Value::String(message.into()),
+    let api_info = RpcMessage::RpcRequest {
+      msgid: msgid + 1,
+      method: "nvim_get_api_info".to_owned(),
+      params: call_args![],
     };
-    let mut expected_data = Vec::new();
-    model::encode_sync(&mut expected_data, expected_resp)
-      .expect("Encoding static data can't fail");
-    let mut actual_data = Vec::new();
-    let mut start = 0;
-    let mut end = 0;
-    while end - start != expected_data.len() {
-      actual_data.resize(start + expected_data.len(), 0);
-
-      let bytes_read =
-        reader
-          .read(&mut actual_data[start..])
-          .await
-          .map_err(|err| {
-            (
-              err,
-              String::from_utf8_lossy(&actual_data[..end]).to_string(),
-            )
-          })?;
-      if bytes_read == 0 {
-        // The end of the stream has been reached when the reader returns Ok(0).
-        // Since we haven't detected a suitable response yet, return an error.
-        return Err(Box::new(HandshakeError::UnexpectedResponse(
-          String::from_utf8_lossy(&actual_data[..end]).to_string(),
-        )));
-      }
-      end += bytes_read;
-      while end - start > 0 {
-        if actual_data[start..end] == expected_data[..end - start] {
-          break;
-        }
-        start += 1;
-      }
+    // The writer task isn't running yet, so write directly. Neovim answers
+    // both requests in order.
+    for req in [echo, api_info] {
+      #[cfg(feature = "recorder")]
+      instance.record(Direction::Outgoing, &req);
+      model::encode(instance.writer.clone(), req).await?;
     }
 
-    let (sender, receiver) = unbounded();
-    let fut = future::try_join(
-      instance.clone().io_loop(reader, sender),
-      instance.clone().handler_loop(handler, receiver),
-    )
-    .map_ok(|_| ());
+    let (info, rest, bytes_read) =
+      read_handshake(&mut reader, msgid, message).await?;
+    // The io loop counts the bytes it gets from `rest`
+    if let Some(ref metrics) = instance.session.metrics {
+      metrics.bytes_received(bytes_read - rest.len() as u64);
+    }
+    // Whatever neovim sent after the responses belongs to the session
+    let reader = Cursor::new(rest).chain(reader);
+    let fut = instance.clone().run(
+      reader,
+      handler,
+      spawner,
+      receivers,
+      options.clone(),
+    );
 
-    Ok((instance, fut))
+    Ok((instance, fut, info))
   }
 
   async fn send_msg(
     &self,
     method: &str,
     args: Vec<Value>,
-  ) -> Result<oneshot::Receiver<ResponseResult>, Box<EncodeError>> {
+  ) -> Result<(QueueGuard, oneshot::Receiver<ResponseResult>), Box<CallError>>
+  {
+    if self.is_closed() {
+      return Err(Box::new(CallError::SessionClosed(method.to_string())));
+    }
+
     let msgid = self.msgid_counter.fetch_add(1, Ordering::SeqCst);
 
     let req = RpcMessage::RpcRequest {
@@ -227,32 +906,203 @@ where
     };
 
     let (sender, receiver) = oneshot::channel();
+    let guard = QueueGuard::new(self.queue.clone(), msgid);
 
-    self.queue.lock().await.push((msgid, sender));
-
-    let writer = self.writer.clone();
-    model::encode(writer, req).await?;
+    self
+      .write_registered(req, Some((msgid, sender)))
+      .await
+      .map_err(|e| CallError::SendError(*e, method.to_string()))?;
 
-    Ok(receiver)
+    Ok((guard, receiver))
   }
 
+  /// Send a request to neovim and wait for the response.
+  ///
+  /// If the returned future is dropped before the response arrives, the
+  /// request is removed from the queue and the response will be discarded.
   pub async fn call(
     &self,
     method: &str,
     args: Vec<Value>,
   ) -> Result<Result<Value, Value>, Box<CallError>> {
-    let receiver = self
-      .send_msg(method, args)
+    let start = Instant::now();
+    let (guard, receiver) = self.send_msg(method, args).await?;
+
+    let response = receiver.await;
+    guard.disarm();
+
+    self.unpack_response(response, method, start)
+  }
+
+  /// Like [`call`](Neovim::call), but fail with
+  /// [`CallError::Timeout`](crate::error::CallError::Timeout) if neovim did
+  /// not respond within `timeout`. This is useful if neovim might be blocked,
+  /// e.g. by a "Press ENTER" prompt.
+  ///
+  /// The timeout only applies to waiting for the response, writing the request
+  /// to neovim is not interrupted so the stream can't be left with a partial
+  /// message.
+  pub async fn call_with_timeout(
+    &self,
+    method: &str,
+    args: Vec<Value>,
+    timeout: Duration,
+  ) -> Result<Result<Value, Value>, Box<CallError>> {
+    self
+      .call_with_deadline(method, args, Instant::now() + timeout)
       .await
-      .map_err(|e| CallError::SendError(*e, method.to_string()))?;
+  }
+
+  /// Like [`call_with_timeout`](Neovim::call_with_timeout), but fail if
+  /// neovim did not respond before `deadline`.
+  pub async fn call_with_deadline(
+    &self,
+    method: &str,
+    args: Vec<Value>,
+    deadline: Instant,
+  ) -> Result<Result<Value, Value>, Box<CallError>> {
+    let start = Instant::now();
+    let (guard, receiver) = self.send_msg(method, args).await?;
+
+    let delay = Delay::new(deadline.saturating_duration_since(Instant::now()));
+
+    match future::select(receiver, delay).await {
+      Either::Left((response, _)) => {
+        guard.disarm();
+        self.unpack_response(response, method, start)
+      }
+      Either::Right(_) => {
+        // Dropping the guard removes the request from the queue
+        drop(guard);
+        Err(Box::new(CallError::Timeout(method.to_string())))
+      }
+    }
+  }
+
+  /// Send a notification to neovim. Unlike [`call`](Neovim::call), this does
+  /// not register the message in the request queue and returns as soon as the
+  /// message has been written. Neovim does not send a response, errors while
+  /// executing the method are reported by neovim via an `nvim_error_event`
+  /// notification.
+  pub async fn send_notification(
+    &self,
+    method: &str,
+    args: Vec<Value>,
+  ) -> Result<(), Box<CallError>> {
+    if self.is_closed() {
+      return Err(Box::new(CallError::SessionClosed(method.to_string())));
+    }
+
+    let notification = RpcMessage::RpcNotification {
+      method: method.to_owned(),
+      params: args,
+    };
+
+    self
+      .write(notification)
+      .await
+      .map_err(|e| Box::new(CallError::SendError(*e, method.to_string())))
+  }
+
+  /// A snapshot of the metrics of the session, or `None` if collecting
+  /// metrics hasn't been enabled via
+  /// [`SessionOptions::set_metrics`](crate::sessionoptions::SessionOptions::set_metrics).
+  #[must_use]
+  pub fn stats(&self) -> Option<Stats> {
+    let metrics = self.session.metrics.as_ref()?;
+    Some(metrics.snapshot(self.queue.len()))
+  }
+
+  /// Pass a message to the recorder, if there is one.
+  #[cfg(feature = "recorder")]
+  fn record(&self, direction: Direction, msg: &RpcMessage) {
+    if let Some(ref recorder) = self.session.recorder {
+      recorder.record(direction, msg);
+    }
+  }
+
+  /// Write a message to neovim, either directly or through the writer task.
+  async fn write(&self, msg: RpcMessage) -> Result<(), Box<EncodeError>> {
+    self.write_registered(msg, None).await
+  }
+
+  /// Like [`write`](Neovim::write), but add a request to the queue right
+  /// before writing it.
+  async fn write_registered(
+    &self,
+    msg: RpcMessage,
+    registration: Option<Registration>,
+  ) -> Result<(), Box<EncodeError>> {
+    if self.session.is_stopped() {
+      let err = io::Error::new(io::ErrorKind::NotConnected, "Session closed");
+      return Err(Box::new(EncodeError::WriterError(err)));
+    }
 
-    match receiver.await {
-      // Result<Result<Result<Value, Value>, Arc<DecodeError>>, RecvError>
-      Ok(Ok(r)) => Ok(r), // r is Result<Value, Value>, i.e. we got an answer
+    #[cfg(feature = "recorder")]
+    self.record(Direction::Outgoing, &msg);
+    let typ = MessageType::of(&msg);
+    let mut data = Vec::new();
+    model::encode_sync(&mut data, msg)?;
+    let len = data.len();
+
+    self.write_encoded(data, registration).await?;
+    if let Some(ref metrics) = self.session.metrics {
+      metrics.sent(typ, len);
+    }
+    Ok(())
+  }
+
+  /// Write an encoded message, either directly or through the writer task.
+  async fn write_encoded(
+    &self,
+    data: Vec<u8>,
+    registration: Option<Registration>,
+  ) -> Result<(), Box<EncodeError>> {
+    let Some(ref write_sender) = self.write_sender else {
+      let metrics = self.session.metrics.as_deref();
+      return Ok(
+        write_locked(&self.writer, &data, || {
+          if let Some(registration) = registration {
+            register(&self.queue, metrics, registration);
+          }
+        })
+        .await?,
+      );
+    };
+
+    let (sender, receiver) = oneshot::channel();
+    write_sender
+      .unbounded_send((data, registration, sender))
+      .map_err(|_| writer_task_closed())?;
+
+    receiver.await.map_err(|_| writer_task_closed())?
+  }
+
+  /// Unpack the response to a request, as received through the oneshot channel
+  /// from the queue.
+  fn unpack_response(
+    &self,
+    response: Result<ResponseResult, oneshot::Canceled>,
+    method: &str,
+    start: Instant,
+  ) -> Result<Result<Value, Value>, Box<CallError>> {
+    match response {
+      // Result<Result<Result<Value, Value>, Arc<DecodeError>>, Canceled>
+      Ok(Ok(r)) => {
+        // r is Result<Value, Value>, i.e. we got an answer
+        if let Some(ref metrics) = self.session.metrics {
+          metrics.call_finished(method, start);
+        }
+        Ok(r)
+      }
       Ok(Err(err)) => {
         // err is a Decode Error, i.e. the answer wasn't decodable
         Err(Box::new(CallError::DecodeError(err, method.to_string())))
       }
+      Err(_) if self.session.is_closed() => {
+        // The queue was closed while we were waiting
+        Err(Box::new(CallError::SessionClosed(method.to_string())))
+      }
       Err(err) => {
         // err is RecvError
         Err(Box::new(CallError::InternalReceiveError(
@@ -263,7 +1113,7 @@ where
     }
   }
 
-  async fn send_error_to_callers(
+  fn send_error_to_callers(
     &self,
     queue: &Queue,
     err: DecodeError,
@@ -271,11 +1121,8 @@ where
     let err = Arc::new(err);
     let mut v: Vec<u64> = vec![];
 
-    let mut queue = queue.lock().await;
-    queue.drain(0..).for_each(|sender| {
-      let msgid = sender.0;
+    queue.drain().into_iter().for_each(|(msgid, sender)| {
       sender
-        .1
         .send(Err(err.clone()))
         .unwrap_or_else(|_| v.push(msgid));
     });
@@ -287,14 +1134,50 @@ where
     }
   }
 
-  async fn handler_loop<H>(
+  /// Answer a request with an error, because we're overloaded or shutting
+  /// down.
+  async fn reject_request(&self, msgid: u64, reason: &str) {
+    warn!("Rejecting request {}: {}", msgid, reason);
+
+    let response = RpcMessage::RpcResponse {
+      msgid,
+      result: Value::Nil,
+      error: Value::from(reason),
+    };
+    self.write(response).await.unwrap_or_else(|e| {
+      error!("Error sending response to request {}: '{}'", msgid, e);
+    });
+  }
+
+  /// Start timing the handler for `method`, if metrics are enabled.
+  fn time_handler(&self, method: &str) -> Option<HandlerTimer> {
+    let metrics = self.session.metrics.as_ref()?;
+    Some(metrics.time_handler(method))
+  }
+
+  /// A clone to pass to the handler.
+  fn for_handler(&self) -> Self {
+    Neovim {
+      in_handler: true,
+      ..self.clone()
+    }
+  }
+
+  async fn handler_loop<H, S, M>(
     self,
     handler: H,
-    mut receiver: UnboundedReceiver<RpcMessage>,
+    spawner: S,
+    mut receiver: M,
+    mut tokens: Option<TaskTokens>,
+    overflow_policy: OverflowPolicy,
   ) -> Result<(), Box<LoopError>>
   where
-    H: Handler<Writer = W> + Spawner,
+    H: Handler<Writer = W>,
+    S: TaskSpawner,
+    M: Stream<Item = RpcMessage> + Unpin,
   {
+    let mut notification_order = NotificationOrder::default();
+
     loop {
       let msg = match receiver.next().await {
         Some(msg) => msg,
@@ -306,44 +1189,107 @@ where
       };
 
       match msg {
+        RpcMessage::RpcRequest { msgid, .. } if self.session.is_closed() => {
+          self.reject_request(msgid, SESSION_CLOSED).await;
+        }
+        RpcMessage::RpcNotification { method, .. }
+          if self.session.is_closed() =>
+        {
+          debug!("Dropping notification '{}', session is closed", method);
+        }
         RpcMessage::RpcRequest {
           msgid,
           method,
           params,
         } => {
+          let token = match tokens {
+            None => None,
+            Some(ref mut tokens)
+              if overflow_policy == OverflowPolicy::Reject =>
+            {
+              match tokens.try_acquire() {
+                Some(token) => Some(token),
+                None => {
+                  self.reject_request(msgid, TOO_MANY_REQUESTS).await;
+                  continue;
+                }
+              }
+            }
+            Some(ref mut tokens) => Some(tokens.acquire().await),
+          };
+
           let handler_c = handler.clone();
-          let neovim = self.clone();
-          let writer = self.writer.clone();
+          let neovim = self.for_handler();
+          let writer = self.for_handler();
+          let task = self.session.start_task();
+          let timer = self.time_handler(&method);
 
-          handler.spawn(async move {
-            let response = match handler_c
-              .handle_request(method, params, neovim)
-              .await
-              {
-                Ok(result) => RpcMessage::RpcResponse {
-                  msgid,
-                  result,
-                  error: Value::Nil,
-                },
-                Err(error) => RpcMessage::RpcResponse {
-                  msgid,
-                  result: Value::Nil,
-                  error,
-                },
-              };
-
-            model::encode(writer, response)
-              .await
-              .unwrap_or_else(|e| {
-                error!("Error sending response to request {}: '{}'", msgid, e);
-              });
+          spawner.spawn_task(async move {
+            // Keep the token until the request has been handled
+            let _token = token;
+            let _task = task;
+            let result = handler_c.handle_request(method, params, neovim).await;
+            drop(timer);
+            let response = match result {
+              Ok(result) => RpcMessage::RpcResponse {
+                msgid,
+                result,
+                error: Value::Nil,
+              },
+              Err(error) => RpcMessage::RpcResponse {
+                msgid,
+                result: Value::Nil,
+                error,
+              },
+            };
+
+            if let Err(e) = writer.write(response).await {
+              error!("Error sending response to request {}: '{}'", msgid, e);
+              let error = SessionError::ResponseError(msgid, *e);
+              handler_c.handle_error(error, writer).await;
+            }
           });
         },
-        RpcMessage::RpcNotification {
-          method,
-          params
-        } => handler.handle_notify(method, params, self.clone()).await,
-        RpcMessage::RpcResponse { .. } => unreachable!(),
+        RpcMessage::RpcNotification { method, params } => {
+          // Error events are passed on as notifications as well, handlers may
+          // still look for them there
+          if let Some(error) = error_event(&method, &params) {
+            warn!("{}", error);
+            handler.handle_error(error, self.for_handler()).await;
+          }
+
+          match handler.notification_key(&method, &params) {
+            None => {
+              let _timer = self.time_handler(&method);
+              handler
+                .handle_notify(method, params, self.for_handler())
+                .await;
+            }
+            Some(key) => {
+              let (previous, done) = notification_order.next(key);
+              let handler_c = handler.clone();
+              let neovim = self.for_handler();
+              let task = self.session.start_task();
+              let metrics = self.session.metrics.clone();
+
+              spawner.spawn_task(async move {
+                let _task = task;
+                // Wait for the previous notification with the same key. If
+                // its task was killed, the sender is dropped and we continue.
+                if let Some(previous) = previous {
+                  let _ = previous.await;
+                }
+                let _timer = metrics.map(|m| m.time_handler(&method));
+                handler_c.handle_notify(method, params, neovim).await;
+                let _ = done.send(());
+              });
+            }
+          }
+        }
+        RpcMessage::RpcResponse { msgid, .. } => {
+          // The io loop handles all responses itself
+          error!("Handler loop got the response to {}, ignoring it", msgid);
+        }
       }
     }
   }
@@ -351,37 +1297,108 @@ where
   async fn io_loop<R>(
     self,
     mut reader: R,
-    mut sender: UnboundedSender<RpcMessage>,
-  ) -> Result<(), Box<LoopError>>
+    mut sender: MessageSender,
+    overflow_policy: OverflowPolicy,
+    max_message_size: Option<usize>,
+  ) -> Result<ShutdownReason, Box<LoopError>>
   where
     R: AsyncRead + Send + Unpin + 'static,
   {
-    let mut rest: Vec<u8> = vec![];
+    let mut decoder = Decoder::new();
+    if let Some(max) = max_message_size {
+      decoder.set_max_message_size(max);
+    }
+    // The bytes read by the decoder that have been passed to the metrics
+    let mut counted = 0;
 
     loop {
-      let msg = match model::decode(&mut reader, &mut rest).await {
+      let msg = match decoder.decode(&mut reader).await {
         Ok(msg) => msg,
         Err(err) => {
-          let e = self.send_error_to_callers(&self.queue, *err).await?;
-          return Err(Box::new(LoopError::DecodeError(e, None)));
+          if let DecodeError::InvalidMessage(
+            InvalidMessage::InvalidRequestName(msgid, _),
+          ) = *err
+          {
+            // The request can still be answered, so the session goes on
+            self.reject_request(msgid, INVALID_METHOD_NAME).await;
+            continue;
+          }
+          let reason = disconnect_reason(&err);
+          let res = self.send_error_to_callers(&self.queue, *err);
+          return match (reason, res) {
+            // Callers that stopped waiting don't matter if neovim is gone
+            (Some(reason), _) => Ok(reason),
+            (None, Ok(e)) => Err(Box::new(LoopError::DecodeError(e, None))),
+            (None, Err(e)) => Err(e),
+          };
         }
       };
 
       debug!("Get message {:?}", msg);
+      #[cfg(feature = "recorder")]
+      self.record(Direction::Incoming, &msg);
+      if let Some(ref metrics) = self.session.metrics {
+        metrics.received(&msg);
+        metrics.bytes_received(decoder.bytes_read() - counted);
+      }
+      counted = decoder.bytes_read();
       if let RpcMessage::RpcResponse { msgid, result, error, } = msg {
-        let sender = find_sender(&self.queue, msgid).await?;
-        if error == Value::Nil {
-          sender
-            .send(Ok(Ok(result)))
-            .map_err(|r| (msgid, r.expect("This was an OK(_)")))?;
+        /* If the caller stopped waiting for the response (e.g. because of a
+         * timeout), the request has been removed from the queue or its
+         * receiver has been dropped. This is not an error, so just discard the
+         * response.
+         */
+        let sender = match find_sender(&self.queue, msgid) {
+          Ok(sender) => sender,
+          Err(_) if msgid < self.msgid_counter.load(Ordering::SeqCst) => {
+            debug!("Discarding response to cancelled request {}", msgid);
+            continue;
+          }
+          Err(err) => return Err(err),
+        };
+        let response = if error == Value::Nil {
+          Ok(result)
         } else {
-          sender
-            .send(Ok(Err(error)))
-            .map_err(|r| (msgid, r.expect("This was an OK(_)")))?;
+          Err(error)
+        };
+        if sender.send(Ok(response)).is_err() {
+          debug!("Discarding response to cancelled request {}", msgid);
         }
       } else {
+        let msg = if overflow_policy == OverflowPolicy::Wait {
+          msg
+        } else {
+          match sender.try_send(msg) {
+            Ok(()) => continue,
+            Err(err) if err.is_full() => match err.into_inner() {
+              RpcMessage::RpcNotification { method, .. } => {
+                warn!(
+                  "Dropping notification '{}', handler is overloaded",
+                  method
+                );
+                continue;
+              }
+              RpcMessage::RpcRequest { msgid, .. }
+                if overflow_policy == OverflowPolicy::Reject =>
+              {
+                self.reject_request(msgid, TOO_MANY_REQUESTS).await;
+                continue;
+              }
+              msg => msg,
+            },
+            Err(err) => err.into_inner(),
+          }
+        };
+
         // Send message to handler_loop()
-        sender.send(msg).await.unwrap();
+        if let Err(msg) = sender.send(msg).await {
+          let method = match msg {
+            RpcMessage::RpcRequest { method, .. }
+            | RpcMessage::RpcNotification { method, .. } => method,
+            RpcMessage::RpcResponse { .. } => String::new(),
+          };
+          return Err(Box::new(LoopError::HandlerLoopClosed(method)));
+        }
       }
     }
   }
@@ -412,56 +1429,1465 @@ where
   }
 }
 
-/* The idea to use Vec here instead of HashMap
- * is that Vec is faster on small queue sizes
- * in most cases Vec.len = 1 so we just take first item in iteration.
- */
-async fn find_sender(
+/// What neovim reported along with the response to a
+/// [`handshake`](Neovim::handshake).
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandshakeInfo {
+  /// The id of the channel for this connection on neovim's side
+  pub channel_id: u64,
+  /// The api metadata, as returned by `nvim_get_api_info`
+  pub api_info: Value,
+}
+
+/// How a session ended without an error, as returned by the io future.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownReason {
+  /// Neovim closed the stream. This happens when neovim quits, or when it
+  /// closes the channel, e.g. via `chanclose()`.
+  Disconnected,
+  /// The connection was reset or aborted by neovim's side.
+  ConnectionReset,
+  /// The session was shut down via [`shutdown`](Neovim::shutdown) or
+  /// [`close`](Neovim::close).
+  Requested,
+}
+
+/// Parse a notification as an `nvim_error_event`, if it is one and its
+/// parameters have the expected format.
+fn error_event(method: &str, params: &[Value]) -> Option<SessionError> {
+  match params {
+    [Value::Integer(i), Value::String(s)] if method == "nvim_error_event" => {
+      Some(SessionError::ErrorEvent(
+        i.as_i64()?,
+        s.as_str()?.to_owned(),
+      ))
+    }
+    _ => None,
+  }
+}
+
+/// Check if the error means neovim disconnected regularly.
+fn disconnect_reason(err: &DecodeError) -> Option<ShutdownReason> {
+  let DecodeError::ReaderError(e) = err else {
+    return None;
+  };
+
+  match e.kind() {
+    io::ErrorKind::UnexpectedEof => Some(ShutdownReason::Disconnected),
+    io::ErrorKind::ConnectionReset
+    | io::ErrorKind::ConnectionAborted
+    | io::ErrorKind::BrokenPipe => Some(ShutdownReason::ConnectionReset),
+    _ => None,
+  }
+}
+
+/// Read from `reader` until the responses to the handshake requests `msgid`
+/// and `msgid + 1` have been found, see [`Neovim::handshake`]. Returns the
+/// info from the second one, all data read after the first one that isn't
+/// part of the second, and the number of bytes read in total.
+async fn read_handshake<R>(
+  reader: &mut R,
+  msgid: u64,
+  message: &str,
+) -> Result<(HandshakeInfo, Vec<u8>, u64), Box<HandshakeError>>
+where
+  R: AsyncRead + Unpin,
+{
+  let mut bytes_read = 0;
+  let mut data = Vec::new();
+  // Where a response might start that has not been read completely yet
+  let mut scan = 0;
+  let (start, end) = 'found: loop {
+    bytes_read += read_some(reader, &mut data, None).await?;
+
+    let mut incomplete = None;
+    for start in scan..data.len() {
+      if !response_start(&data[start..], msgid) {
+        continue;
+      }
+      let len = match model::message_len(&data[start..]) {
+        Ok(len) => len,
+        Err(_) => {
+          incomplete.get_or_insert(start);
+          continue;
+        }
+      };
+      let Ok(RpcMessage::RpcResponse {
+        msgid: id,
+        error,
+        result,
+      }) = model::decode_buffer(&mut &data[start..start + len])
+      else {
+        continue;
+      };
+      // Neovim encodes some strings with an unusual, but valid, marker, and
+      // might send binary data instead of a string
+      let echoed = match result {
+        Value::String(ref s) => s.as_bytes() == message.as_bytes(),
+        Value::Binary(ref b) => b == message.as_bytes(),
+        _ => false,
+      };
+      if id != msgid || !(echoed || error != Value::Nil) {
+        continue;
+      }
+      if error != Value::Nil {
+        let noise = String::from_utf8_lossy(&data[..start]).to_string();
+        return Err(Box::new(HandshakeError::ErrorResponse(error, noise)));
+      }
+      break 'found (start, start + len);
+    }
+    scan = incomplete.unwrap_or(data.len());
+  };
+
+  let noise = String::from_utf8_lossy(&data[..start]).to_string();
+  if !noise.is_empty() {
+    warn!("Skipped output before the handshake response: {:?}", noise);
+  }
+
+  // From here on, neovim speaks msgpack-rpc, but might send other messages
+  // before the second response
+  let mut rest = data.split_off(end);
+  let mut kept = Vec::new();
+  let mut pos = 0;
+  loop {
+    let len = match model::message_len(&rest[pos..]) {
+      Ok(len) => len,
+      Err(_) => {
+        bytes_read += read_some(reader, &mut rest, Some(&noise)).await?;
+        continue;
+      }
+    };
+    let msg = &rest[pos..pos + len];
+    match model::decode_buffer(&mut &msg[..]) {
+      Ok(RpcMessage::RpcResponse {
+        msgid: id,
+        error: Value::Nil,
+        result,
+      }) if id == msgid + 1 => {
+        let info = api_info(result).ok_or_else(|| {
+          Box::new(HandshakeError::UnexpectedResponse(noise.clone()))
+        })?;
+        kept.extend_from_slice(&rest[pos + len..]);
+        return Ok((info, kept, bytes_read));
+      }
+      Ok(RpcMessage::RpcResponse { msgid: id, .. }) if id == msgid + 1 => {
+        return Err(Box::new(HandshakeError::UnexpectedResponse(noise)));
+      }
+      // Decoding errors are left to the io loop
+      _ => kept.extend_from_slice(msg),
+    }
+    pos += len;
+  }
+}
+
+/// Check if `buf` might start with a response to the request `msgid`, i.e.
+/// with a 4-element array of type 1 and that msgid. If `buf` is too short to
+/// tell, returns `true`.
+fn response_start(buf: &[u8], msgid: u64) -> bool {
+  use rmp::decode::NumValueReadError;
+
+  match buf {
+    [] | [0x94] => true,
+    [0x94, 0x01, id @ ..] => {
+      match rmp::decode::read_int::<u64, _>(&mut &id[..]) {
+        Ok(id) => id == msgid,
+        Err(NumValueReadError::InvalidMarkerRead(_))
+        | Err(NumValueReadError::InvalidDataRead(_)) => true,
+        Err(_) => false,
+      }
+    }
+    _ => false,
+  }
+}
+
+/// Read some more data from `reader` into `data`, and return the number of
+/// bytes read. The errors report `noise`, or all of `data` if that's not known
+/// yet.
+async fn read_some<R>(
+  reader: &mut R,
+  data: &mut Vec<u8>,
+  noise: Option<&str>,
+) -> Result<u64, Box<HandshakeError>>
+where
+  R: AsyncRead + Unpin,
+{
+  let noise = || match noise {
+    Some(noise) => noise.to_owned(),
+    None => String::from_utf8_lossy(data).to_string(),
+  };
+
+  let mut buf = [0; 1024];
+  let bytes_read = reader.read(&mut buf).await.map_err(|err| (err, noise()))?;
+  if bytes_read == 0 {
+    // The stream ended before the responses were found
+    return Err(Box::new(HandshakeError::UnexpectedResponse(noise())));
+  }
+  data.extend_from_slice(&buf[..bytes_read]);
+
+  Ok(bytes_read as u64)
+}
+
+/// Parse the result of `nvim_get_api_info`.
+fn api_info(result: Value) -> Option<HandshakeInfo> {
+  let Value::Array(mut arr) = result else {
+    return None;
+  };
+  if arr.len() != 2 {
+    return None;
+  }
+  let api_info = arr.pop()?;
+  let channel_id = arr.pop()?.as_u64()?;
+
+  Some(HandshakeInfo {
+    channel_id,
+    api_info,
+  })
+}
+
+fn find_sender(
   queue: &Queue,
   msgid: u64,
 ) -> Result<oneshot::Sender<ResponseResult>, Box<LoopError>> {
-  let mut queue = queue.lock().await;
+  queue.remove(msgid).ok_or_else(|| msgid.into())
+}
 
-  let pos = match queue.iter().position(|req| req.0 == msgid) {
-    Some(p) => p,
-    None => return Err(msgid.into()),
-  };
-  Ok(queue.remove(pos).1)
+/// The error neovim gets when a request is rejected because of
+/// [`OverflowPolicy::Reject`](crate::sessionoptions::OverflowPolicy::Reject).
+const TOO_MANY_REQUESTS: &str = "Too many requests";
+/// The error neovim gets for requests made after the session has been shut
+/// down.
+const SESSION_CLOSED: &str = "Session closed";
+/// The error neovim gets for requests whose method name isn't a string.
+const INVALID_METHOD_NAME: &str = "Invalid method name";
+
+/// The receiving ends of the channels to the session's tasks, created along
+/// with the [`Neovim`](crate::neovim::Neovim) instance.
+struct SessionReceivers {
+  write: Option<UnboundedReceiver<PendingWrite>>,
+  shutdown: UnboundedReceiver<ShutdownRequest>,
+}
+
+/// The sending half of the channel from the io loop to the handler loop.
+enum MessageSender {
+  Bounded(mpsc::Sender<RpcMessage>),
+  Unbounded(UnboundedSender<RpcMessage>),
+}
+
+impl MessageSender {
+  fn try_send(
+    &mut self,
+    msg: RpcMessage,
+  ) -> Result<(), TrySendError<RpcMessage>> {
+    match self {
+      MessageSender::Bounded(sender) => sender.try_send(msg),
+      MessageSender::Unbounded(sender) => sender.unbounded_send(msg),
+    }
+  }
+
+  /// Send a message, waiting for room in the channel if needed. If the
+  /// receiver is gone, the message is given back.
+  async fn send(&mut self, msg: RpcMessage) -> Result<(), RpcMessage> {
+    match self {
+      MessageSender::Bounded(sender) => {
+        if future::poll_fn(|cx| sender.poll_ready(cx)).await.is_err() {
+          return Err(msg);
+        }
+        sender.try_send(msg).map_err(TrySendError::into_inner)
+      }
+      MessageSender::Unbounded(sender) => {
+        sender.unbounded_send(msg).map_err(TrySendError::into_inner)
+      }
+    }
+  }
+}
+
+/// Create the channel from the io loop to the handler loop, holding at most
+/// `capacity` messages if given.
+fn message_channel(
+  capacity: Option<NonZeroUsize>,
+) -> (MessageSender, impl Stream<Item = RpcMessage> + Send + Unpin) {
+  match capacity {
+    Some(capacity) => {
+      // The channel has room for one more message per sender
+      let (sender, receiver) = mpsc::channel(capacity.get() - 1);
+      (MessageSender::Bounded(sender), Either::Left(receiver))
+    }
+    None => {
+      let (sender, receiver) = unbounded();
+      (MessageSender::Unbounded(sender), Either::Right(receiver))
+    }
+  }
+}
+
+/// Limits the number of concurrently running tasks. Every task needs a token to
+/// start, which is returned when the task finishes.
+struct TaskTokens {
+  tokens: UnboundedReceiver<()>,
+  returns: UnboundedSender<()>,
+}
+
+impl TaskTokens {
+  fn new(count: NonZeroUsize) -> Self {
+    let (returns, tokens) = unbounded();
+    for _ in 0..count.get() {
+      // Can't fail, we're holding the receiver
+      let _ = returns.unbounded_send(());
+    }
+
+    TaskTokens { tokens, returns }
+  }
+
+  async fn acquire(&mut self) -> TaskToken {
+    // We're holding a sender, so the stream never ends
+    let _ = self.tokens.next().await;
+    TaskToken(self.returns.clone())
+  }
+
+  fn try_acquire(&mut self) -> Option<TaskToken> {
+    self
+      .tokens
+      .try_recv()
+      .ok()
+      .map(|()| TaskToken(self.returns.clone()))
+  }
+}
+
+/// Returns itself to the [`TaskTokens`] when dropped.
+struct TaskToken(UnboundedSender<()>);
+
+impl Drop for TaskToken {
+  fn drop(&mut self) {
+    let _ = self.0.unbounded_send(());
+  }
+}
+
+/// Keeps track of the last notification task spawned for every key, so the
+/// next one with the same key can wait for it to finish.
+#[derive(Default)]
+struct NotificationOrder {
+  last: HashMap<String, oneshot::Receiver<()>>,
+  /// Remove finished tasks from `last` when it reaches this size
+  prune_at: usize,
+}
+
+impl NotificationOrder {
+  /// Register a new task for `key`. Returns the channel signalling that the
+  /// previous task for that key has finished (if any), and the channel the new
+  /// task needs to signal when it's finished.
+  fn next(
+    &mut self,
+    key: String,
+  ) -> (Option<oneshot::Receiver<()>>, oneshot::Sender<()>) {
+    if self.last.len() >= self.prune_at {
+      self
+        .last
+        .retain(|_, finished| matches!(finished.try_recv(), Ok(None)));
+      self.prune_at = (2 * self.last.len()).max(16);
+    }
+
+    let (sender, receiver) = oneshot::channel();
+    (self.last.insert(key, receiver), sender)
+  }
+}
+
+/// The writer task. Writes all messages that queued up while the last write
+/// was in progress at once, and reports the result to every sender. Finishes
+/// when all senders are gone.
+async fn write_loop<W>(
+  writer: Arc<Mutex<W>>,
+  queue: Arc<Queue>,
+  metrics: Option<Arc<Metrics>>,
+  mut receiver: UnboundedReceiver<PendingWrite>,
+) where
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  let mut data = Vec::new();
+  let mut registrations = Vec::new();
+  let mut senders = Vec::new();
+
+  while let Some((msg, registration, sender)) = receiver.next().await {
+    data.extend_from_slice(&msg);
+    registrations.extend(registration);
+    senders.push(sender);
+
+    while let Ok((msg, registration, sender)) = receiver.try_recv() {
+      data.extend_from_slice(&msg);
+      registrations.extend(registration);
+      senders.push(sender);
+    }
+
+    let res = write_locked(&writer, &data, || {
+      for registration in registrations.drain(..) {
+        register(&queue, metrics.as_deref(), registration);
+      }
+    })
+    .await;
+    if let Err(ref e) = res {
+      error!("Error writing {} messages: '{}'", senders.len(), e);
+    }
+
+    for sender in senders.drain(..) {
+      // io::Error isn't Clone, so every caller gets its own copy
+      let res = match res {
+        Ok(()) => Ok(()),
+        Err(ref e) => Err(io::Error::new(e.kind(), e.to_string()).into()),
+      };
+      // If the caller isn't waiting anymore, there's nobody to report to
+      let _ = sender.send(res);
+    }
+    data.clear();
+  }
+}
+
+/// Write `data` and flush the writer. `before_write` is called once the
+/// writer has been locked.
+///
+/// Requests are added to the queue there, so a request is only in the queue
+/// once it's being written to the current connection. This way, a session
+/// that reconnects can fail everything in the queue, without failing requests
+/// that are still waiting for the new connection.
+async fn write_locked<W, F>(
+  writer: &Mutex<W>,
+  data: &[u8],
+  before_write: F,
+) -> io::Result<()>
+where
+  W: AsyncWrite + Send + Unpin + 'static,
+  F: FnOnce(),
+{
+  let mut writer = writer.lock().await;
+  before_write();
+  writer.write_all(data).await?;
+  writer.flush().await?;
+
+  Ok(())
+}
+
+/// Add a request to the queue. If the queue has been closed, the sender is
+/// dropped, so the caller gets
+/// [`CallError::SessionClosed`](crate::error::CallError::SessionClosed).
+fn register(
+  queue: &Queue,
+  metrics: Option<&Metrics>,
+  (msgid, sender): Registration,
+) {
+  if queue.insert(msgid, sender).is_err() {
+    return;
+  }
+  if let Some(metrics) = metrics {
+    metrics.in_flight(queue.len());
+  }
+}
+
+fn writer_task_closed() -> Box<EncodeError> {
+  Box::new(EncodeError::WriterError(io::Error::new(
+    io::ErrorKind::BrokenPipe,
+    "The writer task has finished",
+  )))
+}
+
+/// Removes a request from the queue when dropped, unless it was disarmed
+/// because the response has been received. This way, requests whose callers
+/// stopped waiting don't stay in the queue.
+struct QueueGuard {
+  queue: Arc<Queue>,
+  msgid: u64,
+  armed: bool,
+}
+
+impl QueueGuard {
+  fn new(queue: Arc<Queue>, msgid: u64) -> Self {
+    QueueGuard {
+      queue,
+      msgid,
+      armed: true,
+    }
+  }
+
+  fn disarm(mut self) {
+    self.armed = false;
+  }
+}
+
+impl Drop for QueueGuard {
+  fn drop(&mut self) {
+    if self.armed {
+      self.queue.remove(self.msgid);
+    }
+  }
 }
 
 #[cfg(all(test, feature = "use_tokio"))]
 mod tests {
   use super::*;
 
-  #[tokio::test]
-  async fn test_find_sender() {
-    let queue = Arc::new(Mutex::new(Vec::new()));
+  #[test]
+  fn test_find_sender() {
+    let queue = Queue::default();
 
     {
       let (sender, _receiver) = oneshot::channel();
-      queue.lock().await.push((1, sender));
+      assert!(queue.insert(1, sender).is_ok());
     }
     {
       let (sender, _receiver) = oneshot::channel();
-      queue.lock().await.push((2, sender));
+      assert!(queue.insert(2, sender).is_ok());
     }
     {
       let (sender, _receiver) = oneshot::channel();
-      queue.lock().await.push((3, sender));
+      assert!(queue.insert(3, sender).is_ok());
     }
 
-    find_sender(&queue, 1).await.unwrap();
-    assert_eq!(2, queue.lock().await.len());
-    find_sender(&queue, 2).await.unwrap();
-    assert_eq!(1, queue.lock().await.len());
-    find_sender(&queue, 3).await.unwrap();
-    assert!(queue.lock().await.is_empty());
+    find_sender(&queue, 1).unwrap();
+    assert_eq!(2, queue.len());
+    find_sender(&queue, 2).unwrap();
+    assert_eq!(1, queue.len());
+    find_sender(&queue, 3).unwrap();
+    assert_eq!(0, queue.len());
 
-    if let LoopError::MsgidNotFound(17) =
-      *find_sender(&queue, 17).await.unwrap_err()
+    if let LoopError::MsgidNotFound(17) = *find_sender(&queue, 17).unwrap_err()
     {
     } else {
       panic!()
     }
   }
+
+  #[tokio::test]
+  async fn test_send_notification() {
+    let neovim = test_neovim();
+
+    neovim
+      .send_notification("nvim_input", call_args!["<Esc>"])
+      .await
+      .unwrap();
+
+    assert_eq!(0, neovim.queue.len());
+    assert_eq!(0, neovim.msgid_counter.load(Ordering::SeqCst));
+
+    let data = neovim.writer.lock().await.get_ref().clone();
+    let mut reader = futures::io::Cursor::new(data);
+    let msg = model::Decoder::new().decode(&mut reader).await.unwrap();
+    assert_eq!(
+      msg,
+      RpcMessage::RpcNotification {
+        method: "nvim_input".to_owned(),
+        params: vec![Value::from("<Esc>")],
+      }
+    );
+  }
+
+  fn test_neovim() -> Neovim<futures::io::Cursor<Vec<u8>>> {
+    let writer = futures::io::Cursor::new(Vec::new());
+    Neovim::from_writer(writer, &SessionOptions::default()).0
+  }
+
+  #[tokio::test]
+  async fn test_call_timeout() {
+    let neovim = test_neovim();
+
+    let res = neovim
+      .call_with_timeout(
+        "nvim_eval",
+        call_args!["1"],
+        Duration::from_millis(10),
+      )
+      .await;
+
+    match *res.unwrap_err() {
+      CallError::Timeout(ref method) => assert_eq!(method, "nvim_eval"),
+      ref err => panic!("Unexpected error {err}"),
+    }
+    assert_eq!(0, neovim.queue.len());
+  }
+
+  #[tokio::test]
+  async fn test_dropped_call() {
+    let neovim = test_neovim();
+
+    {
+      let call = neovim.call("nvim_eval", call_args!["1"]);
+      futures::pin_mut!(call);
+      assert!(futures::poll!(call).is_pending());
+      assert_eq!(1, neovim.queue.len());
+    }
+
+    assert_eq!(0, neovim.queue.len());
+  }
+
+  #[tokio::test]
+  async fn test_late_response_discarded() {
+    let neovim = test_neovim();
+    neovim.msgid_counter.store(1, Ordering::SeqCst);
+
+    let mut data = Vec::new();
+    model::encode_sync(
+      &mut data,
+      RpcMessage::RpcResponse {
+        msgid: 0,
+        error: Value::Nil,
+        result: Value::from(1),
+      },
+    )
+    .unwrap();
+
+    let (sender, _receiver) = message_channel(None);
+    let reason = neovim
+      .io_loop(
+        futures::io::Cursor::new(data),
+        sender,
+        OverflowPolicy::Wait,
+        None,
+      )
+      .await
+      .unwrap();
+
+    // The response was skipped, and the loop ended on EOF
+    assert_eq!(ShutdownReason::Disconnected, reason);
+  }
+
+  #[tokio::test]
+  async fn test_handler_loop_closed() {
+    let neovim = test_neovim();
+
+    let mut data = Vec::new();
+    model::encode_sync(
+      &mut data,
+      RpcMessage::RpcNotification {
+        method: "nvim_buf_lines_event".to_owned(),
+        params: vec![],
+      },
+    )
+    .unwrap();
+
+    // A session never gets here on its own, but the io loop must not panic
+    let (sender, receiver) = message_channel(None);
+    drop(receiver);
+    let err = neovim
+      .io_loop(
+        futures::io::Cursor::new(data),
+        sender,
+        OverflowPolicy::Wait,
+        None,
+      )
+      .await
+      .unwrap_err();
+
+    match *err {
+      LoopError::HandlerLoopClosed(ref method) => {
+        assert_eq!("nvim_buf_lines_event", method)
+      }
+      ref e => panic!("Unexpected error {e}"),
+    }
+  }
+
+  #[tokio::test]
+  async fn test_handler_loop_ignores_responses() {
+    let neovim = test_neovim();
+    let handler = crate::rpc::handler::Dummy::new();
+    let receiver = futures::stream::iter(vec![RpcMessage::RpcResponse {
+      msgid: 1,
+      error: Value::Nil,
+      result: Value::Nil,
+    }]);
+
+    neovim
+      .handler_loop(
+        handler.clone(),
+        HandlerSpawner(handler),
+        receiver,
+        None,
+        OverflowPolicy::Wait,
+      )
+      .await
+      .unwrap();
+  }
+
+  /// Counts the flushes, and fails every write if `fail` is set
+  #[derive(Default)]
+  struct TestWriter {
+    data: Vec<u8>,
+    flushes: usize,
+    fail: bool,
+  }
+
+  impl AsyncWrite for TestWriter {
+    fn poll_write(
+      mut self: std::pin::Pin<&mut Self>,
+      _cx: &mut std::task::Context<'_>,
+      buf: &[u8],
+    ) -> std::task::Poll<io::Result<usize>> {
+      if self.fail {
+        return std::task::Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+      }
+      self.data.extend_from_slice(buf);
+      std::task::Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(
+      mut self: std::pin::Pin<&mut Self>,
+      _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+      self.flushes += 1;
+      std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(
+      self: std::pin::Pin<&mut Self>,
+      _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+      std::task::Poll::Ready(Ok(()))
+    }
+  }
+
+  #[tokio::test]
+  async fn test_coalesced_writes() {
+    let mut options = SessionOptions::new();
+    options.set_coalesce_writes(true);
+    let (neovim, receivers) =
+      Neovim::from_writer(TestWriter::default(), &options);
+
+    let notifications = (0..10_i64)
+      .map(|i| neovim.send_notification("nvim_input", call_args![i]));
+    let write_loop = write_loop(
+      neovim.writer.clone(),
+      neovim.queue.clone(),
+      None,
+      receivers.write.unwrap(),
+    );
+    futures::pin_mut!(write_loop);
+
+    match future::select(future::join_all(notifications), write_loop).await {
+      Either::Left((results, _)) => {
+        assert!(results.into_iter().all(|r| r.is_ok()))
+      }
+      Either::Right(_) => panic!("Writer task finished early"),
+    }
+
+    let writer = neovim.writer.lock().await;
+    assert_eq!(1, writer.flushes);
+
+    let mut reader = futures::io::Cursor::new(writer.data.clone());
+    let mut decoder = Decoder::new();
+    for i in 0..10_i64 {
+      assert_eq!(
+        RpcMessage::RpcNotification {
+          method: "nvim_input".to_owned(),
+          params: vec![Value::from(i)],
+        },
+        decoder.decode(&mut reader).await.unwrap()
+      );
+    }
+  }
+
+  #[tokio::test]
+  async fn test_coalesced_write_errors() {
+    let mut options = SessionOptions::new();
+    options.set_coalesce_writes(true);
+    let writer = TestWriter {
+      fail: true,
+      ..Default::default()
+    };
+    let (neovim, receivers) = Neovim::from_writer(writer, &options);
+
+    let calls = (0..3).map(|_| neovim.call("nvim_eval", call_args!["1"]));
+    let write_loop = write_loop(
+      neovim.writer.clone(),
+      neovim.queue.clone(),
+      None,
+      receivers.write.unwrap(),
+    );
+    futures::pin_mut!(write_loop);
+
+    match future::select(future::join_all(calls), write_loop).await {
+      Either::Left((results, _)) => {
+        for res in results {
+          match *res.unwrap_err() {
+            CallError::SendError(EncodeError::WriterError(ref e), _) => {
+              assert_eq!(io::ErrorKind::BrokenPipe, e.kind())
+            }
+            ref e => panic!("Unexpected error {e}"),
+          }
+        }
+      }
+      Either::Right(_) => panic!("Writer task finished early"),
+    }
+
+    // Requests that failed to send don't stay in the queue
+    assert_eq!(0, neovim.queue.len());
+  }
+
+  #[test]
+  fn test_task_tokens() {
+    let mut tokens = TaskTokens::new(NonZeroUsize::new(2).unwrap());
+
+    let token1 = tokens.try_acquire().unwrap();
+    let _token2 = tokens.try_acquire().unwrap();
+    assert!(tokens.try_acquire().is_none());
+
+    drop(token1);
+    assert!(tokens.try_acquire().is_some());
+  }
+
+  #[test]
+  fn test_message_channel_capacity() {
+    let notification = || RpcMessage::RpcNotification {
+      method: "nvim_input".to_owned(),
+      params: vec![],
+    };
+
+    // The smallest capacity holds exactly one message
+    for capacity in [1, 3] {
+      let capacity = NonZeroUsize::new(capacity).unwrap();
+      let (mut sender, _receiver) = message_channel(Some(capacity));
+      for _ in 0..capacity.get() {
+        assert!(sender.try_send(notification()).is_ok());
+      }
+      assert!(sender.try_send(notification()).unwrap_err().is_full());
+    }
+  }
+
+  #[tokio::test]
+  async fn test_single_request_slot() {
+    let neovim = test_neovim();
+    let handler = crate::rpc::handler::Dummy::new();
+    let requests = (1..=2).map(|msgid| RpcMessage::RpcRequest {
+      msgid,
+      method: "nvim_eval".to_owned(),
+      params: vec![],
+    });
+
+    // The second request waits for the first to finish, instead of waiting
+    // forever
+    let handler_loop = neovim.clone().handler_loop(
+      handler.clone(),
+      HandlerSpawner(handler),
+      futures::stream::iter(requests),
+      Some(TaskTokens::new(NonZeroUsize::MIN)),
+      OverflowPolicy::Wait,
+    );
+    tokio::time::timeout(Duration::from_secs(5), async {
+      handler_loop.await.unwrap();
+      neovim.drained().await;
+    })
+    .await
+    .expect("The requests were not handled");
+
+    let data = neovim.writer.lock().await.get_ref().clone();
+    let mut reader = futures::io::Cursor::new(data);
+    let mut decoder = Decoder::new();
+    for id in 1..=2 {
+      match decoder.decode(&mut reader).await.unwrap() {
+        RpcMessage::RpcResponse { msgid, .. } => assert_eq!(id, msgid),
+        msg => panic!("Unexpected message {msg:?}"),
+      }
+    }
+  }
+
+  #[tokio::test]
+  async fn test_full_channel_rejects() {
+    let neovim = test_neovim();
+
+    let mut data = Vec::new();
+    let messages = [
+      RpcMessage::RpcNotification {
+        method: "first".to_owned(),
+        params: vec![],
+      },
+      RpcMessage::RpcRequest {
+        msgid: 7,
+        method: "second".to_owned(),
+        params: vec![],
+      },
+      RpcMessage::RpcNotification {
+        method: "third".to_owned(),
+        params: vec![],
+      },
+    ];
+    for msg in messages {
+      model::encode_sync(&mut data, msg).unwrap();
+    }
+
+    let (sender, mut receiver) = message_channel(Some(NonZeroUsize::MIN));
+    let reason = neovim
+      .clone()
+      .io_loop(
+        futures::io::Cursor::new(data),
+        sender,
+        OverflowPolicy::Reject,
+        None,
+      )
+      .await
+      .unwrap();
+    assert_eq!(ShutdownReason::Disconnected, reason);
+
+    // Only the first message made it to the handler
+    match receiver.next().await {
+      Some(RpcMessage::RpcNotification { method, .. }) => {
+        assert_eq!("first", method)
+      }
+      msg => panic!("Unexpected message {msg:?}"),
+    }
+    assert!(receiver.next().await.is_none());
+
+    // The request was answered with an error
+    let data = neovim.writer.lock().await.get_ref().clone();
+    let mut reader = futures::io::Cursor::new(data);
+    match Decoder::new().decode(&mut reader).await.unwrap() {
+      RpcMessage::RpcResponse { msgid, error, .. } => {
+        assert_eq!(7, msgid);
+        assert_ne!(Value::Nil, error);
+      }
+      msg => panic!("Unexpected message {msg:?}"),
+    }
+  }
+
+  #[derive(Clone)]
+  struct OrderHandler {
+    handled: Arc<StdMutex<Vec<String>>>,
+    unblock: Arc<tokio::sync::Notify>,
+    done: UnboundedSender<()>,
+  }
+
+  impl Handler for OrderHandler {
+    type Writer = futures::io::Cursor<Vec<u8>>;
+
+    fn notification_key(&self, name: &str, _args: &[Value]) -> Option<String> {
+      Some(name[..1].to_owned())
+    }
+
+    async fn handle_notify(
+      &self,
+      name: String,
+      _args: Vec<Value>,
+      _neovim: Neovim<Self::Writer>,
+    ) {
+      match name.as_str() {
+        "a1" => self.unblock.notified().await,
+        "b1" => self.unblock.notify_one(),
+        _ => {}
+      }
+      self.handled.lock().unwrap().push(name);
+      self.done.unbounded_send(()).unwrap();
+    }
+  }
+
+  #[tokio::test]
+  async fn test_notification_keys() {
+    let neovim = test_neovim();
+    let (done, mut done_receiver) = unbounded();
+    let handler = OrderHandler {
+      handled: Arc::new(StdMutex::new(vec![])),
+      unblock: Arc::new(tokio::sync::Notify::new()),
+      done,
+    };
+
+    let (mut sender, receiver) = message_channel(None);
+    for name in ["a1", "a2", "b1"] {
+      sender
+        .send(RpcMessage::RpcNotification {
+          method: name.to_owned(),
+          params: vec![],
+        })
+        .await
+        .unwrap();
+    }
+    drop(sender);
+
+    neovim
+      .handler_loop(
+        handler.clone(),
+        HandlerSpawner(handler.clone()),
+        receiver,
+        None,
+        OverflowPolicy::Wait,
+      )
+      .await
+      .unwrap();
+
+    // "a1" blocks until "b1" has been handled, and "a2" waits for "a1"
+    for _ in 0..3 {
+      tokio::time::timeout(Duration::from_secs(5), done_receiver.next())
+        .await
+        .expect("Notifications were not handled concurrently");
+    }
+    assert_eq!(vec!["b1", "a1", "a2"], *handler.handled.lock().unwrap());
+  }
+
+  type DuplexWriter =
+    tokio_util::compat::Compat<tokio::io::WriteHalf<tokio::io::DuplexStream>>;
+  type DuplexReader =
+    tokio_util::compat::Compat<tokio::io::ReadHalf<tokio::io::DuplexStream>>;
+  type IoHandle =
+    tokio::task::JoinHandle<Result<ShutdownReason, Box<LoopError>>>;
+
+  /// Start a session over an in-memory stream, and return the other end of
+  /// the stream to play neovim.
+  fn duplex_session() -> (
+    Neovim<DuplexWriter>,
+    IoHandle,
+    DuplexReader,
+    DuplexWriter,
+  ) {
+    use tokio_util::compat::{
+      TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
+    };
+
+    let (ours, theirs) = tokio::io::duplex(1024);
+    let (reader, writer) = tokio::io::split(ours);
+    let (their_reader, their_writer) = tokio::io::split(theirs);
+
+    let (neovim, io) = Neovim::new(
+      reader.compat(),
+      writer.compat_write(),
+      crate::rpc::handler::Dummy::new(),
+    );
+
+    (
+      neovim,
+      tokio::spawn(io),
+      their_reader.compat(),
+      their_writer.compat_write(),
+    )
+  }
+
+  #[tokio::test]
+  async fn test_close() {
+    let (neovim, io, mut their_reader, _their_writer) = duplex_session();
+
+    let nvim = neovim.clone();
+    let call = tokio::spawn(async move {
+      nvim.call("nvim_eval", call_args!["1"]).await
+    });
+    // Wait for the request, but never answer it
+    Decoder::new().decode(&mut their_reader).await.unwrap();
+
+    neovim.close().await;
+
+    match *call.await.unwrap().unwrap_err() {
+      CallError::SessionClosed(ref method) => assert_eq!("nvim_eval", method),
+      ref e => panic!("Unexpected error {e}"),
+    }
+    assert_eq!(ShutdownReason::Requested, io.await.unwrap().unwrap());
+
+    let err = neovim.call("nvim_eval", call_args!["1"]).await.unwrap_err();
+    assert!(matches!(*err, CallError::SessionClosed(_)));
+    let err = neovim
+      .send_notification("nvim_input", call_args!["<Esc>"])
+      .await
+      .unwrap_err();
+    assert!(matches!(*err, CallError::SessionClosed(_)));
+    // Neither can handlers
+    let err = neovim
+      .for_handler()
+      .send_notification("nvim_input", call_args!["<Esc>"])
+      .await
+      .unwrap_err();
+    assert!(matches!(*err, CallError::SessionClosed(_)));
+    assert!(!err.is_channel_closed());
+
+    // Closing again is a no-op
+    neovim.close().await;
+  }
+
+  #[tokio::test]
+  async fn test_shutdown_waits_for_calls() {
+    let (neovim, io, mut their_reader, their_writer) = duplex_session();
+    let their_writer = Arc::new(Mutex::new(their_writer));
+
+    let nvim = neovim.clone();
+    let call = tokio::spawn(async move {
+      nvim.call("nvim_eval", call_args!["1"]).await
+    });
+    let mut decoder = Decoder::new();
+    let msgid = match decoder.decode(&mut their_reader).await.unwrap() {
+      RpcMessage::RpcRequest { msgid, .. } => msgid,
+      msg => panic!("Unexpected message {msg:?}"),
+    };
+
+    let nvim = neovim.clone();
+    let shutdown = tokio::spawn(async move { nvim.shutdown().await });
+    // New calls fail right away, but the pending one is still answered
+    while !neovim.session.is_closed() {
+      tokio::task::yield_now().await;
+    }
+    let err = neovim.call("nvim_eval", call_args!["2"]).await.unwrap_err();
+    assert!(matches!(*err, CallError::SessionClosed(_)));
+    assert!(!shutdown.is_finished());
+
+    let response = RpcMessage::RpcResponse {
+      msgid,
+      error: Value::Nil,
+      result: Value::from(1),
+    };
+    model::encode(their_writer, response).await.unwrap();
+
+    assert_eq!(Value::from(1), call.await.unwrap().unwrap().unwrap());
+    shutdown.await.unwrap();
+    assert_eq!(ShutdownReason::Requested, io.await.unwrap().unwrap());
+
+    // The writer has been closed
+    let err = decoder.decode(&mut their_reader).await.unwrap_err();
+    match *err {
+      DecodeError::ReaderError(ref e) => {
+        assert_eq!(io::ErrorKind::UnexpectedEof, e.kind())
+      }
+      ref e => panic!("Unexpected error {e}"),
+    }
+  }
+
+  #[tokio::test]
+  async fn test_shutdown_unpolled() {
+    use tokio_util::compat::{
+      TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
+    };
+
+    for poll in [true, false] {
+      let (ours, _theirs) = tokio::io::duplex(1024);
+      let (reader, writer) = tokio::io::split(ours);
+      let (neovim, io) = Neovim::new(
+        reader.compat(),
+        writer.compat_write(),
+        crate::rpc::handler::Dummy::new(),
+      );
+
+      let shutdown = tokio::spawn(async move { neovim.shutdown().await });
+      tokio::time::sleep(Duration::from_millis(50)).await;
+      assert!(!shutdown.is_finished());
+
+      // Polling the io future lets the shutdown go ahead, dropping it ends the
+      // wait as well
+      if poll {
+        assert_eq!(ShutdownReason::Requested, io.await.unwrap());
+      } else {
+        drop(io);
+      }
+      tokio::time::timeout(Duration::from_secs(5), shutdown)
+        .await
+        .expect("Shutdown did not return")
+        .unwrap();
+    }
+  }
+
+  #[tokio::test]
+  async fn test_disconnect() {
+    let (neovim, io, mut their_reader, their_writer) = duplex_session();
+
+    let nvim = neovim.clone();
+    let call = tokio::spawn(async move {
+      nvim.call("nvim_eval", call_args!["1"]).await
+    });
+    Decoder::new().decode(&mut their_reader).await.unwrap();
+    // The stream only closes when both halves are dropped
+    drop(their_reader);
+    drop(their_writer);
+
+    assert_eq!(ShutdownReason::Disconnected, io.await.unwrap().unwrap());
+    // Pending calls still see the closed channel
+    assert!(call.await.unwrap().unwrap_err().is_channel_closed());
+  }
+
+  #[tokio::test]
+  async fn test_invalid_message() {
+    let (_neovim, io, _their_reader, their_writer) = duplex_session();
+
+    let mut data = vec![];
+    rmpv::encode::write_value(&mut data, &Value::from(vec![Value::from(7)]))
+      .unwrap();
+    let mut writer = their_writer;
+    writer.write_all(&data).await.unwrap();
+
+    // Invalid data is still an error
+    match *io.await.unwrap().unwrap_err() {
+      LoopError::DecodeError(ref e, _) => {
+        assert!(matches!(**e, DecodeError::InvalidMessage(_)))
+      }
+      ref e => panic!("Unexpected error {e}"),
+    }
+  }
+
+  #[derive(Clone)]
+  struct ErrorHandler {
+    errors: UnboundedSender<SessionError>,
+    notifications: UnboundedSender<(String, Vec<Value>)>,
+  }
+
+  impl Handler for ErrorHandler {
+    type Writer = TestWriter;
+
+    async fn handle_request(
+      &self,
+      _name: String,
+      _args: Vec<Value>,
+      _neovim: Neovim<Self::Writer>,
+    ) -> Result<Value, Value> {
+      Ok(Value::Nil)
+    }
+
+    async fn handle_notify(
+      &self,
+      name: String,
+      args: Vec<Value>,
+      _neovim: Neovim<Self::Writer>,
+    ) {
+      self.notifications.unbounded_send((name, args)).unwrap();
+    }
+
+    async fn handle_error(
+      &self,
+      error: SessionError,
+      _neovim: Neovim<Self::Writer>,
+    ) {
+      self.errors.unbounded_send(error).unwrap();
+    }
+  }
+
+  #[tokio::test]
+  async fn test_handle_error() {
+    let writer = TestWriter {
+      fail: true,
+      ..Default::default()
+    };
+    let (neovim, _receivers) =
+      Neovim::from_writer(writer, &SessionOptions::default());
+    let (errors, mut error_receiver) = unbounded();
+    let (notifications, mut notification_receiver) = unbounded();
+
+    let (mut sender, receiver) = message_channel(None);
+    let messages = vec![
+      RpcMessage::RpcNotification {
+        method: "nvim_error_event".to_owned(),
+        params: call_args![1, "Invalid method: nvim_foo"],
+      },
+      RpcMessage::RpcRequest {
+        msgid: 3,
+        method: "foo".to_owned(),
+        params: vec![],
+      },
+    ];
+    for msg in messages {
+      sender.send(msg).await.unwrap();
+    }
+    drop(sender);
+
+    let handler = ErrorHandler {
+      errors,
+      notifications,
+    };
+    neovim
+      .handler_loop(
+        handler.clone(),
+        HandlerSpawner(handler),
+        receiver,
+        None,
+        OverflowPolicy::Wait,
+      )
+      .await
+      .unwrap();
+
+    match error_receiver.next().await.unwrap() {
+      SessionError::ErrorEvent(1, ref s) => {
+        assert_eq!("Invalid method: nvim_foo", s)
+      }
+      e => panic!("Unexpected error {e}"),
+    }
+    match error_receiver.next().await.unwrap() {
+      SessionError::ResponseError(3, EncodeError::WriterError(ref e)) => {
+        assert_eq!(io::ErrorKind::BrokenPipe, e.kind())
+      }
+      e => panic!("Unexpected error {e}"),
+    }
+
+    // The error event is passed to handle_notify as well
+    assert_eq!(
+      Some((
+        "nvim_error_event".to_owned(),
+        call_args![1, "Invalid method: nvim_foo"]
+      )),
+      notification_receiver.next().await
+    );
+    assert_eq!(None, notification_receiver.next().await);
+  }
+
+  #[tokio::test]
+  async fn test_stats() {
+    use crate::{rpc::handler::Dummy, testing::script::Script};
+
+    let mut script = Script::new();
+    script
+      .expect_request("nvim_get_current_line", vec![], Ok(Value::from("a")))
+      .notify("bar", vec![])
+      .request("foo", vec![])
+      .expect_response(Err(Value::from("Not implemented")));
+    let (reader, writer, serve) = script.start();
+
+    let mut options = SessionOptions::new();
+    options.set_metrics(true);
+    let (nvim, io) =
+      Neovim::new_with_options(reader, writer, Dummy::new(), &options);
+    let io = tokio::spawn(io);
+    let serve = tokio::spawn(serve);
+
+    assert_eq!("a", nvim.get_current_line().await.unwrap());
+    serve.await.unwrap().unwrap();
+    assert_eq!(ShutdownReason::Disconnected, io.await.unwrap().unwrap());
+
+    let stats = nvim.stats().unwrap();
+    assert_eq!(
+      (1, 1, 0, 1, 1, 1),
+      (
+        stats.requests_sent,
+        stats.requests_received,
+        stats.notifications_sent,
+        stats.notifications_received,
+        stats.responses_sent,
+        stats.responses_received
+      )
+    );
+    assert!(stats.bytes_sent > 0 && stats.bytes_received > 0);
+    assert_eq!((0, 1), (stats.in_flight, stats.max_in_flight));
+    assert_eq!(1, stats.call_latencies["nvim_get_current_line"].count);
+    assert_eq!(1, stats.handler_durations["foo"].count);
+    assert_eq!(1, stats.handler_durations["bar"].count);
+    assert!(stats.to_string().contains("nvim_get_current_line: count 1"));
+
+    let (reader, writer, _serve) = Script::new().start();
+    let (nvim, _io) = Neovim::new(reader, writer, Dummy::new());
+    assert!(nvim.stats().is_none());
+  }
+
+  #[tokio::test]
+  async fn test_reconnecting() {
+    use crate::testing::{script::Script, PipeWriter};
+
+    #[derive(Clone)]
+    struct ReconnectHandler {
+      reconnected: UnboundedSender<()>,
+    }
+
+    impl Handler for ReconnectHandler {
+      type Writer = PipeWriter;
+
+      async fn handle_reconnect(&self, neovim: Neovim<PipeWriter>) {
+        neovim.call("hook", vec![]).await.unwrap().unwrap();
+        self.reconnected.unbounded_send(()).unwrap();
+      }
+    }
+
+    let mut first = Script::new();
+    first.expect_request("nvim_get_current_line", vec![], Ok(Value::from("a")));
+    let mut second = Script::new();
+    second
+      .expect_request("hook", vec![], Ok(Value::Nil))
+      .expect_request("nvim_get_current_line", vec![], Ok(Value::from("b")));
+    let mut scripts = vec![second, first];
+    let connect = move || {
+      let script = scripts.pop();
+      async move {
+        let script = script.ok_or(io::ErrorKind::ConnectionRefused)?;
+        let (reader, writer, serve) = script.start();
+        tokio::spawn(serve);
+        Ok((reader, writer))
+      }
+    };
+
+    let mut backoff = Backoff::new();
+    backoff
+      .set_initial_delay(Duration::from_millis(1))
+      .set_max_attempts(2);
+    let (reconnected, mut hook_done) = unbounded();
+    let mut options = SessionOptions::new();
+    options.set_metrics(true);
+    let (nvim, io) = Neovim::new_reconnecting(
+      connect,
+      ReconnectHandler { reconnected },
+      backoff,
+      &options,
+    )
+    .await
+    .unwrap();
+    let io = tokio::spawn(io);
+
+    assert_eq!("a", nvim.get_current_line().await.unwrap());
+    hook_done.next().await.unwrap();
+    assert_eq!("b", nvim.get_current_line().await.unwrap());
+    // No more scripts to connect to, so reconnecting is given up
+    assert_eq!(ShutdownReason::Disconnected, io.await.unwrap().unwrap());
+
+    // The bytes of both connections are counted
+    let mut data = Vec::new();
+    let results = [Value::from("a"), Value::Nil, Value::from("b")];
+    for (msgid, result) in (0..).zip(results) {
+      let response = RpcMessage::RpcResponse {
+        msgid,
+        error: Value::Nil,
+        result,
+      };
+      model::encode_sync(&mut data, response).unwrap();
+    }
+    let stats = nvim.stats().unwrap();
+    assert_eq!(data.len() as u64, stats.bytes_received);
+  }
+
+  #[tokio::test]
+  async fn test_new_local() {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::testing::{script::Script, PipeWriter};
+
+    /// Keeps state the session can't send to other threads.
+    #[derive(Clone)]
+    struct NoteHandler {
+      notes: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl LocalHandler for NoteHandler {
+      type Writer = PipeWriter;
+
+      async fn handle_request(
+        &self,
+        _name: String,
+        _args: Vec<Value>,
+        neovim: Neovim<PipeWriter>,
+      ) -> Result<Value, Value> {
+        let line = neovim.get_current_line().await.unwrap();
+        let notes = self.notes.borrow().join(" ");
+        Ok(Value::from(format!("{line}: {notes}")))
+      }
+
+      async fn handle_notify(
+        &self,
+        _name: String,
+        args: Vec<Value>,
+        _neovim: Neovim<PipeWriter>,
+      ) {
+        let note = args[0].as_str().unwrap().to_owned();
+        self.notes.borrow_mut().push(note);
+      }
+    }
+
+    let mut script = Script::new();
+    script
+      .notify("note", vec![Value::from("a")])
+      .notify("note", vec![Value::from("b")])
+      .request("notes", vec![])
+      .expect_request("nvim_get_current_line", vec![], Ok(Value::from("x")))
+      .expect_response(Ok(Value::from("x: a b")));
+    let (reader, writer, serve) = script.start();
+
+    let handler = NoteHandler {
+      notes: Rc::new(RefCell::new(vec![])),
+    };
+    let local = tokio::task::LocalSet::new();
+    let (_nvim, io) = Neovim::new_local(reader, writer, handler.clone());
+    let io = local.spawn_local(io);
+    let serve = tokio::spawn(serve);
+
+    local
+      .run_until(async move {
+        serve.await.unwrap().unwrap();
+        assert_eq!(ShutdownReason::Disconnected, io.await.unwrap().unwrap());
+      })
+      .await;
+    assert_eq!(vec!["a", "b"], *handler.notes.borrow());
+  }
 }
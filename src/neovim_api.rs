@@ -1,5 +1,10 @@
 //! The auto generated API for [`neovim`](crate::neovim::Neovim)
 //!
+//! Every function has a `_notify` variant that sends the request as a
+//! notification via
+//! [`send_notification`](crate::neovim::Neovim::send_notification), without
+//! waiting for a response.
+//!
 //! Auto generated 2025-03-01 14:48:09.493596
 use futures::io::AsyncWrite;
 
@@ -34,6 +39,16 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 1
+  pub async fn line_count_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_line_count",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
   /// since: 4
   pub async fn attach(
     &self,
@@ -51,6 +66,20 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 4
+  pub async fn attach_notify(
+    &self,
+    send_buffer: bool,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_attach",
+        call_args![self.code_data.clone(), send_buffer, opts],
+      )
+      .await
+  }
+  /// since: 4
   pub async fn detach(&self) -> Result<bool, Box<CallError>> {
     self
       .neovim
@@ -59,6 +88,13 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 4
+  pub async fn detach_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification("nvim_buf_detach", call_args![self.code_data.clone()])
+      .await
+  }
   /// since: 1
   pub async fn get_lines(
     &self,
@@ -77,6 +113,21 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn get_lines_notify(
+    &self,
+    start: i64,
+    end: i64,
+    strict_indexing: bool,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_get_lines",
+        call_args![self.code_data.clone(), start, end, strict_indexing],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn set_lines(
     &self,
     start: i64,
@@ -100,6 +151,28 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 1
+  pub async fn set_lines_notify(
+    &self,
+    start: i64,
+    end: i64,
+    strict_indexing: bool,
+    replacement: Vec<String>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_set_lines",
+        call_args![
+          self.code_data.clone(),
+          start,
+          end,
+          strict_indexing,
+          replacement
+        ],
+      )
+      .await
+  }
   /// since: 7
   pub async fn set_text(
     &self,
@@ -126,6 +199,30 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 7
+  pub async fn set_text_notify(
+    &self,
+    start_row: i64,
+    start_col: i64,
+    end_row: i64,
+    end_col: i64,
+    replacement: Vec<String>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_set_text",
+        call_args![
+          self.code_data.clone(),
+          start_row,
+          start_col,
+          end_row,
+          end_col,
+          replacement
+        ],
+      )
+      .await
+  }
   /// since: 9
   pub async fn get_text(
     &self,
@@ -152,6 +249,30 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 9
+  pub async fn get_text_notify(
+    &self,
+    start_row: i64,
+    start_col: i64,
+    end_row: i64,
+    end_col: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_get_text",
+        call_args![
+          self.code_data.clone(),
+          start_row,
+          start_col,
+          end_row,
+          end_col,
+          opts
+        ],
+      )
+      .await
+  }
   /// since: 5
   pub async fn get_offset(&self, index: i64) -> Result<i64, Box<CallError>> {
     self
@@ -164,6 +285,19 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 5
+  pub async fn get_offset_notify(
+    &self,
+    index: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_get_offset",
+        call_args![self.code_data.clone(), index],
+      )
+      .await
+  }
   /// since: 1
   pub async fn get_var(&self, name: &str) -> Result<Value, Box<CallError>> {
     self
@@ -173,6 +307,16 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 1
+  pub async fn get_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_get_var",
+        call_args![self.code_data.clone(), name],
+      )
+      .await
+  }
   /// since: 2
   pub async fn get_changedtick(&self) -> Result<i64, Box<CallError>> {
     self
@@ -185,6 +329,16 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 2
+  pub async fn get_changedtick_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_get_changedtick",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
   /// since: 3
   pub async fn get_keymap(
     &self,
@@ -200,6 +354,19 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 3
+  pub async fn get_keymap_notify(
+    &self,
+    mode: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_get_keymap",
+        call_args![self.code_data.clone(), mode],
+      )
+      .await
+  }
   /// since: 6
   pub async fn set_keymap(
     &self,
@@ -219,6 +386,22 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 6
+  pub async fn set_keymap_notify(
+    &self,
+    mode: &str,
+    lhs: &str,
+    rhs: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_set_keymap",
+        call_args![self.code_data.clone(), mode, lhs, rhs, opts],
+      )
+      .await
+  }
+  /// since: 6
   pub async fn del_keymap(
     &self,
     mode: &str,
@@ -234,6 +417,20 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 6
+  pub async fn del_keymap_notify(
+    &self,
+    mode: &str,
+    lhs: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_del_keymap",
+        call_args![self.code_data.clone(), mode, lhs],
+      )
+      .await
+  }
   /// since: 1
   pub async fn set_var(
     &self,
@@ -251,6 +448,20 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn set_var_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_set_var",
+        call_args![self.code_data.clone(), name, value],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn del_var(&self, name: &str) -> Result<(), Box<CallError>> {
     self
       .neovim
@@ -260,6 +471,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn del_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_del_var",
+        call_args![self.code_data.clone(), name],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn get_name(&self) -> Result<String, Box<CallError>> {
     self
       .neovim
@@ -269,6 +490,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn get_name_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_get_name",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn set_name(&self, name: &str) -> Result<(), Box<CallError>> {
     self
       .neovim
@@ -280,6 +511,19 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 1
+  pub async fn set_name_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_set_name",
+        call_args![self.code_data.clone(), name],
+      )
+      .await
+  }
   /// since: 5
   pub async fn is_loaded(&self) -> Result<bool, Box<CallError>> {
     self
@@ -289,6 +533,16 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 5
+  pub async fn is_loaded_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_is_loaded",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
   /// since: 7
   pub async fn delete(
     &self,
@@ -301,6 +555,19 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 7
+  pub async fn delete_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_delete",
+        call_args![self.code_data.clone(), opts],
+      )
+      .await
+  }
   /// since: 1
   pub async fn is_valid(&self) -> Result<bool, Box<CallError>> {
     self
@@ -310,6 +577,16 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 1
+  pub async fn is_valid_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_is_valid",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
   /// since: 8
   pub async fn del_mark(&self, name: &str) -> Result<bool, Box<CallError>> {
     self
@@ -323,6 +600,19 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 8
+  pub async fn del_mark_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_del_mark",
+        call_args![self.code_data.clone(), name],
+      )
+      .await
+  }
+  /// since: 8
   pub async fn set_mark(
     &self,
     name: &str,
@@ -340,6 +630,22 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 8
+  pub async fn set_mark_notify(
+    &self,
+    name: &str,
+    line: i64,
+    col: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_set_mark",
+        call_args![self.code_data.clone(), name, line, col, opts],
+      )
+      .await
+  }
   /// since: 1
   pub async fn get_mark(
     &self,
@@ -355,6 +661,19 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 1
+  pub async fn get_mark_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_get_mark",
+        call_args![self.code_data.clone(), name],
+      )
+      .await
+  }
   /// since: 9
   pub async fn create_user_command(
     &self,
@@ -373,6 +692,21 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 9
+  pub async fn create_user_command_notify(
+    &self,
+    name: &str,
+    command: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_create_user_command",
+        call_args![self.code_data.clone(), name, command, opts],
+      )
+      .await
+  }
+  /// since: 9
   pub async fn del_user_command(
     &self,
     name: &str,
@@ -387,6 +721,19 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 9
+  pub async fn del_user_command_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_del_user_command",
+        call_args![self.code_data.clone(), name],
+      )
+      .await
+  }
   /// since: 4
   pub async fn get_commands(
     &self,
@@ -402,6 +749,19 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 4
+  pub async fn get_commands_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_get_commands",
+        call_args![self.code_data.clone(), opts],
+      )
+      .await
+  }
   /// since: 1
   pub async fn get_number(&self) -> Result<i64, Box<CallError>> {
     self
@@ -412,6 +772,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn get_number_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_get_number",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn clear_highlight(
     &self,
     ns_id: i64,
@@ -429,6 +799,21 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn clear_highlight_notify(
+    &self,
+    ns_id: i64,
+    line_start: i64,
+    line_end: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_clear_highlight",
+        call_args![self.code_data.clone(), ns_id, line_start, line_end],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn add_highlight(
     &self,
     ns_id: i64,
@@ -454,6 +839,30 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 1
+  pub async fn add_highlight_notify(
+    &self,
+    ns_id: i64,
+    hl_group: &str,
+    line: i64,
+    col_start: i64,
+    col_end: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_add_highlight",
+        call_args![
+          self.code_data.clone(),
+          ns_id,
+          hl_group,
+          line,
+          col_start,
+          col_end
+        ],
+      )
+      .await
+  }
   /// since: 5
   pub async fn set_virtual_text(
     &self,
@@ -472,6 +881,22 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 5
+  pub async fn set_virtual_text_notify(
+    &self,
+    src_id: i64,
+    line: i64,
+    chunks: Vec<Value>,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_set_virtual_text",
+        call_args![self.code_data.clone(), src_id, line, chunks, opts],
+      )
+      .await
+  }
   /// since: 1
   pub async fn get_option(&self, name: &str) -> Result<Value, Box<CallError>> {
     self
@@ -485,6 +910,19 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn get_option_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_get_option",
+        call_args![self.code_data.clone(), name],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn set_option(
     &self,
     name: &str,
@@ -500,6 +938,20 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 1
+  pub async fn set_option_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_set_option",
+        call_args![self.code_data.clone(), name, value],
+      )
+      .await
+  }
   /// since: 7
   pub async fn get_extmark_by_id(
     &self,
@@ -518,6 +970,21 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 7
+  pub async fn get_extmark_by_id_notify(
+    &self,
+    ns_id: i64,
+    id: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_get_extmark_by_id",
+        call_args![self.code_data.clone(), ns_id, id, opts],
+      )
+      .await
+  }
+  /// since: 7
   pub async fn get_extmarks(
     &self,
     ns_id: i64,
@@ -536,6 +1003,22 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 7
+  pub async fn get_extmarks_notify(
+    &self,
+    ns_id: i64,
+    start: Value,
+    end: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_get_extmarks",
+        call_args![self.code_data.clone(), ns_id, start, end, opts],
+      )
+      .await
+  }
+  /// since: 7
   pub async fn set_extmark(
     &self,
     ns_id: i64,
@@ -554,6 +1037,22 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 7
+  pub async fn set_extmark_notify(
+    &self,
+    ns_id: i64,
+    line: i64,
+    col: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_set_extmark",
+        call_args![self.code_data.clone(), ns_id, line, col, opts],
+      )
+      .await
+  }
+  /// since: 7
   pub async fn del_extmark(
     &self,
     ns_id: i64,
@@ -569,6 +1068,20 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 7
+  pub async fn del_extmark_notify(
+    &self,
+    ns_id: i64,
+    id: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_del_extmark",
+        call_args![self.code_data.clone(), ns_id, id],
+      )
+      .await
+  }
   /// since: 5
   pub async fn clear_namespace(
     &self,
@@ -586,6 +1099,21 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 5
+  pub async fn clear_namespace_notify(
+    &self,
+    ns_id: i64,
+    line_start: i64,
+    line_end: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_buf_clear_namespace",
+        call_args![self.code_data.clone(), ns_id, line_start, line_end],
+      )
+      .await
+  }
 }
 
 impl<W> Window<W>
@@ -616,6 +1144,19 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn get_option_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_get_option",
+        call_args![self.code_data.clone(), name],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn set_option(
     &self,
     name: &str,
@@ -631,6 +1172,20 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 1
+  pub async fn set_option_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_set_option",
+        call_args![self.code_data.clone(), name, value],
+      )
+      .await
+  }
   /// since: 6
   pub async fn set_config(
     &self,
@@ -647,6 +1202,19 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 6
+  pub async fn set_config_notify(
+    &self,
+    config: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_set_config",
+        call_args![self.code_data.clone(), config],
+      )
+      .await
+  }
+  /// since: 6
   pub async fn get_config(
     &self,
   ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
@@ -657,6 +1225,16 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 6
+  pub async fn get_config_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_get_config",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
   /// since: 5
   pub async fn set_buf(
     &self,
@@ -672,6 +1250,19 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 5
+  pub async fn set_buf_notify(
+    &self,
+    buffer: &Buffer<W>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_set_buf",
+        call_args![self.code_data.clone(), buffer],
+      )
+      .await
+  }
   /// since: 1
   pub async fn get_cursor(&self) -> Result<(i64, i64), Box<CallError>> {
     self
@@ -682,6 +1273,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn get_cursor_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_get_cursor",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn set_cursor(
     &self,
     pos: (i64, i64),
@@ -697,6 +1298,19 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn set_cursor_notify(
+    &self,
+    pos: (i64, i64),
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_set_cursor",
+        call_args![self.code_data.clone(), pos],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn get_height(&self) -> Result<i64, Box<CallError>> {
     self
       .neovim
@@ -706,6 +1320,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn get_height_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_get_height",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn set_height(&self, height: i64) -> Result<(), Box<CallError>> {
     self
       .neovim
@@ -713,9 +1337,22 @@ where
         "nvim_win_set_height",
         call_args![self.code_data.clone(), height],
       )
-      .await??
-      .try_unpack()
-      .map_err(|v| Box::new(CallError::WrongValueType(v)))
+      .await??
+      .try_unpack()
+      .map_err(|v| Box::new(CallError::WrongValueType(v)))
+  }
+  /// since: 1
+  pub async fn set_height_notify(
+    &self,
+    height: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_set_height",
+        call_args![self.code_data.clone(), height],
+      )
+      .await
   }
   /// since: 1
   pub async fn get_width(&self) -> Result<i64, Box<CallError>> {
@@ -727,6 +1364,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn get_width_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_get_width",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn set_width(&self, width: i64) -> Result<(), Box<CallError>> {
     self
       .neovim
@@ -739,6 +1386,19 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn set_width_notify(
+    &self,
+    width: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_set_width",
+        call_args![self.code_data.clone(), width],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn get_var(&self, name: &str) -> Result<Value, Box<CallError>> {
     self
       .neovim
@@ -748,6 +1408,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn get_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_get_var",
+        call_args![self.code_data.clone(), name],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn set_var(
     &self,
     name: &str,
@@ -764,6 +1434,20 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn set_var_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_set_var",
+        call_args![self.code_data.clone(), name, value],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn del_var(&self, name: &str) -> Result<(), Box<CallError>> {
     self
       .neovim
@@ -773,6 +1457,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn del_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_del_var",
+        call_args![self.code_data.clone(), name],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn get_position(&self) -> Result<(i64, i64), Box<CallError>> {
     self
       .neovim
@@ -782,6 +1476,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn get_position_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_get_position",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn get_number(&self) -> Result<i64, Box<CallError>> {
     self
       .neovim
@@ -791,6 +1495,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn get_number_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_get_number",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn is_valid(&self) -> Result<bool, Box<CallError>> {
     self
       .neovim
@@ -799,6 +1513,16 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 1
+  pub async fn is_valid_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_is_valid",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
   /// since: 7
   pub async fn hide(&self) -> Result<(), Box<CallError>> {
     self
@@ -808,6 +1532,13 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 7
+  pub async fn hide_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification("nvim_win_hide", call_args![self.code_data.clone()])
+      .await
+  }
   /// since: 6
   pub async fn close(&self, force: bool) -> Result<(), Box<CallError>> {
     self
@@ -817,6 +1548,16 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 6
+  pub async fn close_notify(&self, force: bool) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_close",
+        call_args![self.code_data.clone(), force],
+      )
+      .await
+  }
   /// since: 10
   pub async fn set_hl_ns(&self, ns_id: i64) -> Result<(), Box<CallError>> {
     self
@@ -829,6 +1570,19 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 10
+  pub async fn set_hl_ns_notify(
+    &self,
+    ns_id: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_set_hl_ns",
+        call_args![self.code_data.clone(), ns_id],
+      )
+      .await
+  }
   /// since: 12
   pub async fn text_height(
     &self,
@@ -844,6 +1598,19 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 12
+  pub async fn text_height_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_win_text_height",
+        call_args![self.code_data.clone(), opts],
+      )
+      .await
+  }
 }
 
 impl<W> Tabpage<W>
@@ -874,6 +1641,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn get_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_tabpage_get_var",
+        call_args![self.code_data.clone(), name],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn set_var(
     &self,
     name: &str,
@@ -890,6 +1667,20 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn set_var_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_tabpage_set_var",
+        call_args![self.code_data.clone(), name, value],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn del_var(&self, name: &str) -> Result<(), Box<CallError>> {
     self
       .neovim
@@ -901,6 +1692,16 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 1
+  pub async fn del_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_tabpage_del_var",
+        call_args![self.code_data.clone(), name],
+      )
+      .await
+  }
   /// since: 12
   pub async fn set_win(&self, win: &Window<W>) -> Result<(), Box<CallError>> {
     self
@@ -913,6 +1714,19 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 12
+  pub async fn set_win_notify(
+    &self,
+    win: &Window<W>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_tabpage_set_win",
+        call_args![self.code_data.clone(), win],
+      )
+      .await
+  }
   /// since: 1
   pub async fn get_number(&self) -> Result<i64, Box<CallError>> {
     self
@@ -926,6 +1740,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
   /// since: 1
+  pub async fn get_number_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_tabpage_get_number",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
+  /// since: 1
   pub async fn is_valid(&self) -> Result<bool, Box<CallError>> {
     self
       .neovim
@@ -934,6 +1758,16 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+  /// since: 1
+  pub async fn is_valid_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .neovim
+      .send_notification(
+        "nvim_tabpage_is_valid",
+        call_args![self.code_data.clone()],
+      )
+      .await
+  }
 }
 
 impl<W> Neovim<W>
@@ -951,6 +1785,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_autocmds_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_autocmds", call_args![opts])
+      .await
+  }
+
   pub async fn create_autocmd(
     &self,
     event: Value,
@@ -963,6 +1806,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn create_autocmd_notify(
+    &self,
+    event: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_create_autocmd", call_args![event, opts])
+      .await
+  }
+
   pub async fn del_autocmd(&self, id: i64) -> Result<(), Box<CallError>> {
     self
       .call("nvim_del_autocmd", call_args![id])
@@ -971,6 +1824,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn del_autocmd_notify(
+    &self,
+    id: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_del_autocmd", call_args![id])
+      .await
+  }
+
   pub async fn clear_autocmds(
     &self,
     opts: Vec<(Value, Value)>,
@@ -982,6 +1844,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn clear_autocmds_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_clear_autocmds", call_args![opts])
+      .await
+  }
+
   pub async fn create_augroup(
     &self,
     name: &str,
@@ -994,6 +1865,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn create_augroup_notify(
+    &self,
+    name: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_create_augroup", call_args![name, opts])
+      .await
+  }
+
   pub async fn del_augroup_by_id(&self, id: i64) -> Result<(), Box<CallError>> {
     self
       .call("nvim_del_augroup_by_id", call_args![id])
@@ -1002,6 +1883,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn del_augroup_by_id_notify(
+    &self,
+    id: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_del_augroup_by_id", call_args![id])
+      .await
+  }
+
   pub async fn del_augroup_by_name(
     &self,
     name: &str,
@@ -1013,6 +1903,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn del_augroup_by_name_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_del_augroup_by_name", call_args![name])
+      .await
+  }
+
   pub async fn exec_autocmds(
     &self,
     event: Value,
@@ -1025,6 +1924,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn exec_autocmds_notify(
+    &self,
+    event: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_exec_autocmds", call_args![event, opts])
+      .await
+  }
+
   pub async fn parse_cmd(
     &self,
     str: &str,
@@ -1037,6 +1946,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn parse_cmd_notify(
+    &self,
+    str: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_parse_cmd", call_args![str, opts])
+      .await
+  }
+
   pub async fn cmd(
     &self,
     cmd: Vec<(Value, Value)>,
@@ -1049,6 +1968,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn cmd_notify(
+    &self,
+    cmd: Vec<(Value, Value)>,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_cmd", call_args![cmd, opts])
+      .await
+  }
+
   pub async fn create_user_command(
     &self,
     name: &str,
@@ -1062,6 +1991,20 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn create_user_command_notify(
+    &self,
+    name: &str,
+    command: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification(
+        "nvim_create_user_command",
+        call_args![name, command, opts],
+      )
+      .await
+  }
+
   pub async fn del_user_command(
     &self,
     name: &str,
@@ -1073,6 +2016,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn del_user_command_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_del_user_command", call_args![name])
+      .await
+  }
+
   pub async fn get_commands(
     &self,
     opts: Vec<(Value, Value)>,
@@ -1084,6 +2036,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_commands_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_commands", call_args![opts])
+      .await
+  }
+
   pub async fn exec(
     &self,
     src: &str,
@@ -1096,6 +2057,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn exec_notify(
+    &self,
+    src: &str,
+    output: bool,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_exec", call_args![src, output])
+      .await
+  }
+
   pub async fn command_output(
     &self,
     command: &str,
@@ -1107,6 +2078,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn command_output_notify(
+    &self,
+    command: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_command_output", call_args![command])
+      .await
+  }
+
   pub async fn execute_lua(
     &self,
     code: &str,
@@ -1119,6 +2099,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn execute_lua_notify(
+    &self,
+    code: &str,
+    args: Vec<Value>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_execute_lua", call_args![code, args])
+      .await
+  }
+
   pub async fn get_hl_by_id(
     &self,
     hl_id: i64,
@@ -1131,6 +2121,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_hl_by_id_notify(
+    &self,
+    hl_id: i64,
+    rgb: bool,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_hl_by_id", call_args![hl_id, rgb])
+      .await
+  }
+
   pub async fn get_hl_by_name(
     &self,
     name: &str,
@@ -1143,6 +2143,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_hl_by_name_notify(
+    &self,
+    name: &str,
+    rgb: bool,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_hl_by_name", call_args![name, rgb])
+      .await
+  }
+
   pub async fn get_option_info(
     &self,
     name: &str,
@@ -1154,6 +2164,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_option_info_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_option_info", call_args![name])
+      .await
+  }
+
   pub async fn set_option(
     &self,
     name: &str,
@@ -1166,6 +2185,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_option_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_set_option", call_args![name, value])
+      .await
+  }
+
   pub async fn get_option(&self, name: &str) -> Result<Value, Box<CallError>> {
     self
       .call("nvim_get_option", call_args![name])
@@ -1174,6 +2203,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_option_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_option", call_args![name])
+      .await
+  }
+
   pub async fn call_atomic(
     &self,
     calls: Vec<Value>,
@@ -1185,6 +2223,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn call_atomic_notify(
+    &self,
+    calls: Vec<Value>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_call_atomic", call_args![calls])
+      .await
+  }
+
   pub async fn subscribe(&self, event: &str) -> Result<(), Box<CallError>> {
     self
       .call("nvim_subscribe", call_args![event])
@@ -1193,6 +2240,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn subscribe_notify(
+    &self,
+    event: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_subscribe", call_args![event])
+      .await
+  }
+
   pub async fn unsubscribe(&self, event: &str) -> Result<(), Box<CallError>> {
     self
       .call("nvim_unsubscribe", call_args![event])
@@ -1201,6 +2257,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn unsubscribe_notify(
+    &self,
+    event: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_unsubscribe", call_args![event])
+      .await
+  }
+
   pub async fn out_write(&self, str: &str) -> Result<(), Box<CallError>> {
     self
       .call("nvim_out_write", call_args![str])
@@ -1209,6 +2274,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn out_write_notify(
+    &self,
+    str: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_out_write", call_args![str])
+      .await
+  }
+
   pub async fn err_write(&self, str: &str) -> Result<(), Box<CallError>> {
     self
       .call("nvim_err_write", call_args![str])
@@ -1217,6 +2291,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn err_write_notify(
+    &self,
+    str: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_err_write", call_args![str])
+      .await
+  }
+
   pub async fn err_writeln(&self, str: &str) -> Result<(), Box<CallError>> {
     self
       .call("nvim_err_writeln", call_args![str])
@@ -1225,6 +2308,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn err_writeln_notify(
+    &self,
+    str: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_err_writeln", call_args![str])
+      .await
+  }
+
   pub async fn notify(
     &self,
     msg: &str,
@@ -1238,6 +2330,17 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn notify_notify(
+    &self,
+    msg: &str,
+    log_level: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_notify", call_args![msg, log_level, opts])
+      .await
+  }
+
   pub async fn create_namespace(
     &self,
     name: &str,
@@ -1249,6 +2352,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn create_namespace_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_create_namespace", call_args![name])
+      .await
+  }
+
   pub async fn get_namespaces(
     &self,
   ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
@@ -1259,6 +2371,12 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_namespaces_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_namespaces", call_args![])
+      .await
+  }
+
   pub async fn set_decoration_provider(
     &self,
     ns_id: i64,
@@ -1271,6 +2389,19 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_decoration_provider_notify(
+    &self,
+    ns_id: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification(
+        "nvim_set_decoration_provider",
+        call_args![ns_id, opts],
+      )
+      .await
+  }
+
   pub async fn get_option_value(
     &self,
     name: &str,
@@ -1283,6 +2414,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_option_value_notify(
+    &self,
+    name: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_option_value", call_args![name, opts])
+      .await
+  }
+
   pub async fn set_option_value(
     &self,
     name: &str,
@@ -1296,6 +2437,17 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_option_value_notify(
+    &self,
+    name: &str,
+    value: Value,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_set_option_value", call_args![name, value, opts])
+      .await
+  }
+
   pub async fn get_all_options_info(
     &self,
   ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
@@ -1306,6 +2458,14 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_all_options_info_notify(
+    &self,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_all_options_info", call_args![])
+      .await
+  }
+
   pub async fn get_option_info2(
     &self,
     name: &str,
@@ -1318,6 +2478,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_option_info2_notify(
+    &self,
+    name: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_option_info2", call_args![name, opts])
+      .await
+  }
+
   pub async fn ui_set_focus(&self, gained: bool) -> Result<(), Box<CallError>> {
     self
       .call("nvim_ui_set_focus", call_args![gained])
@@ -1326,6 +2496,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn ui_set_focus_notify(
+    &self,
+    gained: bool,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_ui_set_focus", call_args![gained])
+      .await
+  }
+
   pub async fn ui_detach(&self) -> Result<(), Box<CallError>> {
     self
       .call("nvim_ui_detach", call_args![])
@@ -1334,6 +2513,10 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn ui_detach_notify(&self) -> Result<(), Box<CallError>> {
+    self.send_notification("nvim_ui_detach", call_args![]).await
+  }
+
   pub async fn ui_try_resize(
     &self,
     width: i64,
@@ -1346,6 +2529,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn ui_try_resize_notify(
+    &self,
+    width: i64,
+    height: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_ui_try_resize", call_args![width, height])
+      .await
+  }
+
   pub async fn ui_set_option(
     &self,
     name: &str,
@@ -1358,6 +2551,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn ui_set_option_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_ui_set_option", call_args![name, value])
+      .await
+  }
+
   pub async fn ui_try_resize_grid(
     &self,
     grid: i64,
@@ -1371,6 +2574,20 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn ui_try_resize_grid_notify(
+    &self,
+    grid: i64,
+    width: i64,
+    height: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification(
+        "nvim_ui_try_resize_grid",
+        call_args![grid, width, height],
+      )
+      .await
+  }
+
   pub async fn ui_pum_set_height(
     &self,
     height: i64,
@@ -1382,6 +2599,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn ui_pum_set_height_notify(
+    &self,
+    height: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_ui_pum_set_height", call_args![height])
+      .await
+  }
+
   pub async fn ui_pum_set_bounds(
     &self,
     width: f64,
@@ -1399,6 +2625,21 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn ui_pum_set_bounds_notify(
+    &self,
+    width: f64,
+    height: f64,
+    row: f64,
+    col: f64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification(
+        "nvim_ui_pum_set_bounds",
+        call_args![width, height, row, col],
+      )
+      .await
+  }
+
   pub async fn ui_term_event(
     &self,
     event: &str,
@@ -1411,6 +2652,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn ui_term_event_notify(
+    &self,
+    event: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_ui_term_event", call_args![event, value])
+      .await
+  }
+
   pub async fn get_hl_id_by_name(
     &self,
     name: &str,
@@ -1422,6 +2673,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_hl_id_by_name_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_hl_id_by_name", call_args![name])
+      .await
+  }
+
   pub async fn get_hl(
     &self,
     ns_id: i64,
@@ -1434,6 +2694,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_hl_notify(
+    &self,
+    ns_id: i64,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_hl", call_args![ns_id, opts])
+      .await
+  }
+
   pub async fn set_hl(
     &self,
     ns_id: i64,
@@ -1447,6 +2717,17 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_hl_notify(
+    &self,
+    ns_id: i64,
+    name: &str,
+    val: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_set_hl", call_args![ns_id, name, val])
+      .await
+  }
+
   pub async fn get_hl_ns(
     &self,
     opts: Vec<(Value, Value)>,
@@ -1458,6 +2739,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_hl_ns_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_hl_ns", call_args![opts])
+      .await
+  }
+
   pub async fn set_hl_ns(&self, ns_id: i64) -> Result<(), Box<CallError>> {
     self
       .call("nvim_set_hl_ns", call_args![ns_id])
@@ -1466,6 +2756,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_hl_ns_notify(
+    &self,
+    ns_id: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_set_hl_ns", call_args![ns_id])
+      .await
+  }
+
   pub async fn set_hl_ns_fast(&self, ns_id: i64) -> Result<(), Box<CallError>> {
     self
       .call("nvim_set_hl_ns_fast", call_args![ns_id])
@@ -1474,6 +2773,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_hl_ns_fast_notify(
+    &self,
+    ns_id: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_set_hl_ns_fast", call_args![ns_id])
+      .await
+  }
+
   pub async fn feedkeys(
     &self,
     keys: &str,
@@ -1487,6 +2795,17 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn feedkeys_notify(
+    &self,
+    keys: &str,
+    mode: &str,
+    escape_ks: bool,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_feedkeys", call_args![keys, mode, escape_ks])
+      .await
+  }
+
   pub async fn input(&self, keys: &str) -> Result<i64, Box<CallError>> {
     self
       .call("nvim_input", call_args![keys])
@@ -1495,6 +2814,10 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn input_notify(&self, keys: &str) -> Result<(), Box<CallError>> {
+    self.send_notification("nvim_input", call_args![keys]).await
+  }
+
   pub async fn input_mouse(
     &self,
     button: &str,
@@ -1514,6 +2837,23 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn input_mouse_notify(
+    &self,
+    button: &str,
+    action: &str,
+    modifier: &str,
+    grid: i64,
+    row: i64,
+    col: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification(
+        "nvim_input_mouse",
+        call_args![button, action, modifier, grid, row, col],
+      )
+      .await
+  }
+
   pub async fn replace_termcodes(
     &self,
     str: &str,
@@ -1531,6 +2871,21 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn replace_termcodes_notify(
+    &self,
+    str: &str,
+    from_part: bool,
+    do_lt: bool,
+    special: bool,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification(
+        "nvim_replace_termcodes",
+        call_args![str, from_part, do_lt, special],
+      )
+      .await
+  }
+
   pub async fn exec_lua(
     &self,
     code: &str,
@@ -1543,6 +2898,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn exec_lua_notify(
+    &self,
+    code: &str,
+    args: Vec<Value>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_exec_lua", call_args![code, args])
+      .await
+  }
+
   pub async fn strwidth(&self, text: &str) -> Result<i64, Box<CallError>> {
     self
       .call("nvim_strwidth", call_args![text])
@@ -1551,6 +2916,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn strwidth_notify(
+    &self,
+    text: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_strwidth", call_args![text])
+      .await
+  }
+
   pub async fn list_runtime_paths(
     &self,
   ) -> Result<Vec<String>, Box<CallError>> {
@@ -1561,6 +2935,12 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn list_runtime_paths_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_list_runtime_paths", call_args![])
+      .await
+  }
+
   pub async fn get_runtime_file(
     &self,
     name: &str,
@@ -1573,6 +2953,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_runtime_file_notify(
+    &self,
+    name: &str,
+    all: bool,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_runtime_file", call_args![name, all])
+      .await
+  }
+
   pub async fn set_current_dir(&self, dir: &str) -> Result<(), Box<CallError>> {
     self
       .call("nvim_set_current_dir", call_args![dir])
@@ -1581,6 +2971,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_current_dir_notify(
+    &self,
+    dir: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_set_current_dir", call_args![dir])
+      .await
+  }
+
   pub async fn get_current_line(&self) -> Result<String, Box<CallError>> {
     self
       .call("nvim_get_current_line", call_args![])
@@ -1589,6 +2988,12 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_current_line_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_current_line", call_args![])
+      .await
+  }
+
   pub async fn set_current_line(
     &self,
     line: &str,
@@ -1600,6 +3005,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_current_line_notify(
+    &self,
+    line: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_set_current_line", call_args![line])
+      .await
+  }
+
   pub async fn del_current_line(&self) -> Result<(), Box<CallError>> {
     self
       .call("nvim_del_current_line", call_args![])
@@ -1608,6 +3022,12 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn del_current_line_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_del_current_line", call_args![])
+      .await
+  }
+
   pub async fn get_var(&self, name: &str) -> Result<Value, Box<CallError>> {
     self
       .call("nvim_get_var", call_args![name])
@@ -1616,6 +3036,12 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_var", call_args![name])
+      .await
+  }
+
   pub async fn set_var(
     &self,
     name: &str,
@@ -1628,6 +3054,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_var_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_set_var", call_args![name, value])
+      .await
+  }
+
   pub async fn del_var(&self, name: &str) -> Result<(), Box<CallError>> {
     self
       .call("nvim_del_var", call_args![name])
@@ -1636,6 +3072,12 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn del_var_notify(&self, name: &str) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_del_var", call_args![name])
+      .await
+  }
+
   pub async fn get_vvar(&self, name: &str) -> Result<Value, Box<CallError>> {
     self
       .call("nvim_get_vvar", call_args![name])
@@ -1644,6 +3086,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_vvar_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_vvar", call_args![name])
+      .await
+  }
+
   pub async fn set_vvar(
     &self,
     name: &str,
@@ -1656,6 +3107,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_vvar_notify(
+    &self,
+    name: &str,
+    value: Value,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_set_vvar", call_args![name, value])
+      .await
+  }
+
   pub async fn echo(
     &self,
     chunks: Vec<Value>,
@@ -1669,6 +3130,17 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn echo_notify(
+    &self,
+    chunks: Vec<Value>,
+    history: bool,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_echo", call_args![chunks, history, opts])
+      .await
+  }
+
   pub async fn set_current_buf(
     &self,
     buffer: &Buffer<W>,
@@ -1680,6 +3152,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_current_buf_notify(
+    &self,
+    buffer: &Buffer<W>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_set_current_buf", call_args![buffer])
+      .await
+  }
+
   pub async fn set_current_win(
     &self,
     window: &Window<W>,
@@ -1691,6 +3172,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_current_win_notify(
+    &self,
+    window: &Window<W>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_set_current_win", call_args![window])
+      .await
+  }
+
   pub async fn open_term(
     &self,
     buffer: &Buffer<W>,
@@ -1703,6 +3193,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn open_term_notify(
+    &self,
+    buffer: &Buffer<W>,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_open_term", call_args![buffer, opts])
+      .await
+  }
+
   pub async fn chan_send(
     &self,
     chan: i64,
@@ -1715,6 +3215,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn chan_send_notify(
+    &self,
+    chan: i64,
+    data: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_chan_send", call_args![chan, data])
+      .await
+  }
+
   pub async fn set_current_tabpage(
     &self,
     tabpage: &Tabpage<W>,
@@ -1726,6 +3236,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_current_tabpage_notify(
+    &self,
+    tabpage: &Tabpage<W>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_set_current_tabpage", call_args![tabpage])
+      .await
+  }
+
   pub async fn paste(
     &self,
     data: &str,
@@ -1739,6 +3258,17 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn paste_notify(
+    &self,
+    data: &str,
+    crlf: bool,
+    phase: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_paste", call_args![data, crlf, phase])
+      .await
+  }
+
   pub async fn put(
     &self,
     lines: Vec<String>,
@@ -1753,6 +3283,18 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn put_notify(
+    &self,
+    lines: Vec<String>,
+    typ: &str,
+    after: bool,
+    follow: bool,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_put", call_args![lines, typ, after, follow])
+      .await
+  }
+
   pub async fn get_color_by_name(
     &self,
     name: &str,
@@ -1764,6 +3306,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_color_by_name_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_color_by_name", call_args![name])
+      .await
+  }
+
   pub async fn get_color_map(
     &self,
   ) -> Result<Vec<(Value, Value)>, Box<CallError>> {
@@ -1774,6 +3325,12 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_color_map_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_color_map", call_args![])
+      .await
+  }
+
   pub async fn get_context(
     &self,
     opts: Vec<(Value, Value)>,
@@ -1785,6 +3342,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_context_notify(
+    &self,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_context", call_args![opts])
+      .await
+  }
+
   pub async fn load_context(
     &self,
     dict: Vec<(Value, Value)>,
@@ -1796,6 +3362,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn load_context_notify(
+    &self,
+    dict: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_load_context", call_args![dict])
+      .await
+  }
+
   pub async fn get_mode(&self) -> Result<Vec<(Value, Value)>, Box<CallError>> {
     self
       .call("nvim_get_mode", call_args![])
@@ -1804,6 +3379,10 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_mode_notify(&self) -> Result<(), Box<CallError>> {
+    self.send_notification("nvim_get_mode", call_args![]).await
+  }
+
   pub async fn get_keymap(
     &self,
     mode: &str,
@@ -1815,6 +3394,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_keymap_notify(
+    &self,
+    mode: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_keymap", call_args![mode])
+      .await
+  }
+
   pub async fn set_keymap(
     &self,
     mode: &str,
@@ -1829,6 +3417,18 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_keymap_notify(
+    &self,
+    mode: &str,
+    lhs: &str,
+    rhs: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_set_keymap", call_args![mode, lhs, rhs, opts])
+      .await
+  }
+
   pub async fn del_keymap(
     &self,
     mode: &str,
@@ -1841,6 +3441,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn del_keymap_notify(
+    &self,
+    mode: &str,
+    lhs: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_del_keymap", call_args![mode, lhs])
+      .await
+  }
+
   pub async fn get_api_info(&self) -> Result<Vec<Value>, Box<CallError>> {
     self
       .call("nvim_get_api_info", call_args![])
@@ -1849,6 +3459,12 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_api_info_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_api_info", call_args![])
+      .await
+  }
+
   pub async fn set_client_info(
     &self,
     name: &str,
@@ -1867,6 +3483,22 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn set_client_info_notify(
+    &self,
+    name: &str,
+    version: Vec<(Value, Value)>,
+    typ: &str,
+    methods: Vec<(Value, Value)>,
+    attributes: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification(
+        "nvim_set_client_info",
+        call_args![name, version, typ, methods, attributes],
+      )
+      .await
+  }
+
   pub async fn get_chan_info(
     &self,
     chan: i64,
@@ -1878,6 +3510,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_chan_info_notify(
+    &self,
+    chan: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_chan_info", call_args![chan])
+      .await
+  }
+
   pub async fn list_chans(&self) -> Result<Vec<Value>, Box<CallError>> {
     self
       .call("nvim_list_chans", call_args![])
@@ -1886,6 +3527,12 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn list_chans_notify(&self) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_list_chans", call_args![])
+      .await
+  }
+
   pub async fn list_uis(&self) -> Result<Vec<Value>, Box<CallError>> {
     self
       .call("nvim_list_uis", call_args![])
@@ -1894,6 +3541,10 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn list_uis_notify(&self) -> Result<(), Box<CallError>> {
+    self.send_notification("nvim_list_uis", call_args![]).await
+  }
+
   pub async fn get_proc_children(
     &self,
     pid: i64,
@@ -1905,6 +3556,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_proc_children_notify(
+    &self,
+    pid: i64,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_proc_children", call_args![pid])
+      .await
+  }
+
   pub async fn get_proc(&self, pid: i64) -> Result<Value, Box<CallError>> {
     self
       .call("nvim_get_proc", call_args![pid])
@@ -1913,6 +3573,12 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_proc_notify(&self, pid: i64) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_proc", call_args![pid])
+      .await
+  }
+
   pub async fn select_popupmenu_item(
     &self,
     item: i64,
@@ -1930,6 +3596,21 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn select_popupmenu_item_notify(
+    &self,
+    item: i64,
+    insert: bool,
+    finish: bool,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification(
+        "nvim_select_popupmenu_item",
+        call_args![item, insert, finish, opts],
+      )
+      .await
+  }
+
   pub async fn del_mark(&self, name: &str) -> Result<bool, Box<CallError>> {
     self
       .call("nvim_del_mark", call_args![name])
@@ -1938,6 +3619,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn del_mark_notify(
+    &self,
+    name: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_del_mark", call_args![name])
+      .await
+  }
+
   pub async fn get_mark(
     &self,
     name: &str,
@@ -1950,6 +3640,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn get_mark_notify(
+    &self,
+    name: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_get_mark", call_args![name, opts])
+      .await
+  }
+
   pub async fn eval_statusline(
     &self,
     str: &str,
@@ -1962,6 +3662,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn eval_statusline_notify(
+    &self,
+    str: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_eval_statusline", call_args![str, opts])
+      .await
+  }
+
   pub async fn exec2(
     &self,
     src: &str,
@@ -1974,6 +3684,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn exec2_notify(
+    &self,
+    src: &str,
+    opts: Vec<(Value, Value)>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_exec2", call_args![src, opts])
+      .await
+  }
+
   pub async fn command(&self, command: &str) -> Result<(), Box<CallError>> {
     self
       .call("nvim_command", call_args![command])
@@ -1982,6 +3702,15 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn command_notify(
+    &self,
+    command: &str,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_command", call_args![command])
+      .await
+  }
+
   pub async fn eval(&self, expr: &str) -> Result<Value, Box<CallError>> {
     self
       .call("nvim_eval", call_args![expr])
@@ -1990,6 +3719,10 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn eval_notify(&self, expr: &str) -> Result<(), Box<CallError>> {
+    self.send_notification("nvim_eval", call_args![expr]).await
+  }
+
   pub async fn call_function(
     &self,
     fname: &str,
@@ -2002,6 +3735,16 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn call_function_notify(
+    &self,
+    fname: &str,
+    args: Vec<Value>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification("nvim_call_function", call_args![fname, args])
+      .await
+  }
+
   pub async fn call_dict_function(
     &self,
     dict: Value,
@@ -2015,6 +3758,20 @@ where
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
 
+  pub async fn call_dict_function_notify(
+    &self,
+    dict: Value,
+    fname: &str,
+    args: Vec<Value>,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification(
+        "nvim_call_dict_function",
+        call_args![dict, fname, args],
+      )
+      .await
+  }
+
   pub async fn parse_expression(
     &self,
     expr: &str,
@@ -2027,4 +3784,18 @@ where
       .try_unpack()
       .map_err(|v| Box::new(CallError::WrongValueType(v)))
   }
+
+  pub async fn parse_expression_notify(
+    &self,
+    expr: &str,
+    flags: &str,
+    highlight: bool,
+  ) -> Result<(), Box<CallError>> {
+    self
+      .send_notification(
+        "nvim_parse_expression",
+        call_args![expr, flags, highlight],
+      )
+      .await
+  }
 }
@@ -0,0 +1,419 @@
+//! Managing sessions with several neovim instances at once.
+//!
+//! A [`Registry`] owns a number of [`Neovim`] sessions, keyed by e.g. the
+//! server address or the channel id. For each session, the io future is
+//! handed to [`Registry::insert`], which returns a future watching it.
+//! Spawn that instead of the io future itself: when the io loop ends, the
+//! session is removed from the registry and an [`Event::Closed`] is sent.
+//!
+//! Notifications of all sessions can be received together by wrapping each
+//! session's handler in a [`Router`], see [`Registry::router`]. They arrive
+//! as [`Event::Notification`] with the key of the session attached.
+//!
+//! [`Registry::broadcast`] makes the same call on all sessions.
+use std::{
+  collections::HashMap,
+  fmt::Debug,
+  future::Future,
+  hash::Hash,
+  sync::{Arc, Mutex, MutexGuard},
+};
+
+use futures::{
+  channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+  future::join_all,
+  io::AsyncWrite,
+};
+use rmpv::Value;
+
+use crate::{
+  error::{CallError, LoopError, SessionError},
+  neovim::{Neovim, ShutdownReason},
+  rpc::handler::Handler,
+};
+
+/// Something that happened in one of the sessions of a [`Registry`].
+#[derive(Debug)]
+pub enum Event<K, T> {
+  /// A notification was received on the session with this key. Only sent for
+  /// sessions using a [`Router`].
+  Notification {
+    key: K,
+    name: String,
+    args: Vec<Value>,
+  },
+  /// The io loop of the session with this key ended with `result`. The
+  /// session has been removed from the registry.
+  Closed { key: K, result: T },
+}
+
+/// The receiving end of the events of a [`Registry`].
+pub type Events<K, T = IoResult> = UnboundedReceiver<Event<K, T>>;
+
+/// The output of the io future returned by
+/// [`Neovim::new`](crate::neovim::Neovim::new).
+pub type IoResult = Result<ShutdownReason, Box<LoopError>>;
+
+struct Entry<W>
+where
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  neovim: Neovim<W>,
+  /// Tells apart sessions that were inserted with the same key
+  id: u64,
+}
+
+struct Sessions<K, W>
+where
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  entries: HashMap<K, Entry<W>>,
+  next_id: u64,
+}
+
+/// A collection of sessions, see the [module docs](crate::registry).
+///
+/// `T` is the output of the io futures. It is [`IoResult`] for the futures
+/// returned by [`Neovim::new`](crate::neovim::Neovim::new), but the handles
+/// of spawned io futures can be inserted as well.
+///
+/// Cloning the registry gives another handle to the same sessions.
+pub struct Registry<K, W, T = IoResult>
+where
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  sessions: Arc<Mutex<Sessions<K, W>>>,
+  events: UnboundedSender<Event<K, T>>,
+}
+
+impl<K, W, T> Clone for Registry<K, W, T>
+where
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  fn clone(&self) -> Self {
+    Registry {
+      sessions: self.sessions.clone(),
+      events: self.events.clone(),
+    }
+  }
+}
+
+impl<K, W, T> Debug for Registry<K, W, T>
+where
+  K: Debug,
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+    f.debug_struct("Registry")
+      .field("keys", &sessions.entries.keys().collect::<Vec<_>>())
+      .finish()
+  }
+}
+
+impl<K, W, T> Registry<K, W, T>
+where
+  K: Eq + Hash + Clone + Send + Sync + 'static,
+  W: AsyncWrite + Send + Unpin + 'static,
+  T: Send + 'static,
+{
+  /// Create an empty registry, and the receiver for its events.
+  #[must_use]
+  pub fn new() -> (Self, Events<K, T>) {
+    let (events, receiver) = unbounded();
+    let registry = Registry {
+      sessions: Arc::new(Mutex::new(Sessions {
+        entries: HashMap::new(),
+        next_id: 0,
+      })),
+      events,
+    };
+
+    (registry, receiver)
+  }
+
+  fn sessions(&self) -> MutexGuard<'_, Sessions<K, W>> {
+    self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+  }
+
+  /// Add a session under `key`, replacing any session with the same key.
+  /// `io` is the io future of the session, or the handle of the spawned io
+  /// future.
+  ///
+  /// The returned future needs to be spawned (or otherwise polled) for the
+  /// session to make progress. It resolves after `io`, when the session has
+  /// been removed and [`Event::Closed`] has been sent. No event is sent for a
+  /// session that has been removed or replaced before.
+  pub fn insert<F>(
+    &self,
+    key: K,
+    neovim: Neovim<W>,
+    io: F,
+  ) -> impl Future<Output = ()> + Send + use<F, K, W, T>
+  where
+    F: Future<Output = T> + Send + 'static,
+  {
+    let id = {
+      let mut sessions = self.sessions();
+      let id = sessions.next_id;
+      sessions.next_id += 1;
+      sessions.entries.insert(key.clone(), Entry { neovim, id });
+      id
+    };
+    let registry = self.clone();
+
+    async move {
+      let result = io.await;
+
+      let removed = {
+        let mut sessions = registry.sessions();
+        match sessions.entries.get(&key) {
+          Some(entry) if entry.id == id => {
+            sessions.entries.remove(&key);
+            true
+          }
+          _ => false,
+        }
+      };
+      if removed {
+        // The receiver might be gone, the session was removed anyways
+        let _ = registry
+          .events
+          .unbounded_send(Event::Closed { key, result });
+      }
+    }
+  }
+
+  /// Remove the session with this key. The session itself is not closed,
+  /// use [`Neovim::shutdown`](crate::neovim::Neovim::shutdown) for that.
+  pub fn remove(&self, key: &K) -> Option<Neovim<W>> {
+    self.sessions().entries.remove(key).map(|e| e.neovim)
+  }
+
+  /// The session with this key.
+  pub fn get(&self, key: &K) -> Option<Neovim<W>> {
+    self.sessions().entries.get(key).map(|e| e.neovim.clone())
+  }
+
+  /// The keys of all sessions, in no particular order.
+  pub fn keys(&self) -> Vec<K> {
+    self.sessions().entries.keys().cloned().collect()
+  }
+
+  /// The number of sessions.
+  pub fn len(&self) -> usize {
+    self.sessions().entries.len()
+  }
+
+  /// Whether there are no sessions.
+  pub fn is_empty(&self) -> bool {
+    self.sessions().entries.is_empty()
+  }
+
+  /// Call `method` with `args` on all sessions concurrently, and collect the
+  /// results with the key of their session, in no particular order.
+  pub async fn broadcast(
+    &self,
+    method: &str,
+    args: Vec<Value>,
+  ) -> Vec<(K, Result<Result<Value, Value>, Box<CallError>>)> {
+    let sessions: Vec<_> = self
+      .sessions()
+      .entries
+      .iter()
+      .map(|(key, entry)| (key.clone(), entry.neovim.clone()))
+      .collect();
+
+    join_all(sessions.into_iter().map(|(key, neovim)| {
+      let args = args.clone();
+      async move { (key, neovim.call(method, args).await) }
+    }))
+    .await
+  }
+
+  /// Wrap `handler` so the notifications of the session with this key are
+  /// sent as [`Event::Notification`], after `handler` has handled them.
+  pub fn router<H>(&self, key: K, handler: H) -> Router<K, H, T>
+  where
+    H: Handler<Writer = W>,
+  {
+    Router {
+      key,
+      handler,
+      events: self.events.clone(),
+    }
+  }
+}
+
+/// A [`Handler`] that passes the notifications it receives on to a
+/// [`Registry`], see [`Registry::router`]. Everything else is left to the
+/// wrapped handler.
+pub struct Router<K, H, T> {
+  key: K,
+  handler: H,
+  events: UnboundedSender<Event<K, T>>,
+}
+
+impl<K, H, T> Clone for Router<K, H, T>
+where
+  K: Clone,
+  H: Clone,
+{
+  fn clone(&self) -> Self {
+    Router {
+      key: self.key.clone(),
+      handler: self.handler.clone(),
+      events: self.events.clone(),
+    }
+  }
+}
+
+impl<K, H, T> Handler for Router<K, H, T>
+where
+  K: Clone + Send + Sync + 'static,
+  H: Handler,
+  T: Send + 'static,
+{
+  type Writer = H::Writer;
+
+  fn handle_request(
+    &self,
+    name: String,
+    args: Vec<Value>,
+    neovim: Neovim<Self::Writer>,
+  ) -> impl Future<Output = Result<Value, Value>> + Send {
+    self.handler.handle_request(name, args, neovim)
+  }
+
+  async fn handle_notify(
+    &self,
+    name: String,
+    args: Vec<Value>,
+    neovim: Neovim<Self::Writer>,
+  ) {
+    self
+      .handler
+      .handle_notify(name.clone(), args.clone(), neovim)
+      .await;
+    // The receiver might be gone, there's no one to tell then
+    let _ = self.events.unbounded_send(Event::Notification {
+      key: self.key.clone(),
+      name,
+      args,
+    });
+  }
+
+  fn notification_key(&self, name: &str, args: &[Value]) -> Option<String> {
+    self.handler.notification_key(name, args)
+  }
+
+  fn handle_error(
+    &self,
+    error: SessionError,
+    neovim: Neovim<Self::Writer>,
+  ) -> impl Future<Output = ()> + Send {
+    self.handler.handle_error(error, neovim)
+  }
+
+  fn handle_reconnect(
+    &self,
+    neovim: Neovim<Self::Writer>,
+  ) -> impl Future<Output = ()> + Send {
+    self.handler.handle_reconnect(neovim)
+  }
+}
+
+#[cfg(all(test, feature = "use_tokio"))]
+mod tests {
+  use super::*;
+
+  use futures::StreamExt;
+
+  use crate::{
+    rpc::handler::Dummy,
+    testing::{fake::FakeNeovim, PipeWriter},
+  };
+
+  type TestRegistry = Registry<&'static str, PipeWriter>;
+
+  fn connect(
+    registry: &TestRegistry,
+    key: &'static str,
+    fake: &FakeNeovim,
+  ) -> Neovim<PipeWriter> {
+    let (reader, writer, serve) = fake.start();
+    tokio::spawn(serve);
+    let handler = registry.router(key, Dummy::new());
+    let (nvim, io) = Neovim::new(reader, writer, handler);
+    tokio::spawn(registry.insert(key, nvim.clone(), io));
+    nvim
+  }
+
+  #[tokio::test]
+  async fn test_broadcast() {
+    let (registry, _events) = TestRegistry::new();
+    let (one, two) = (FakeNeovim::new(), FakeNeovim::new());
+    connect(&registry, "one", &one);
+    connect(&registry, "two", &two);
+    one.set_lines(1, &["a"]);
+    two.set_lines(1, &["b"]);
+
+    let mut results = registry.broadcast("nvim_get_current_line", vec![]).await;
+    results.sort_by_key(|(key, _)| *key);
+
+    let results: Vec<_> = results
+      .into_iter()
+      .map(|(key, res)| (key, res.unwrap().unwrap()))
+      .collect();
+    assert_eq!(
+      vec![("one", Value::from("a")), ("two", Value::from("b"))],
+      results
+    );
+  }
+
+  #[tokio::test]
+  async fn test_events() {
+    let (registry, mut events) = TestRegistry::new();
+    let (one, two) = (FakeNeovim::new(), FakeNeovim::new());
+    connect(&registry, "one", &one);
+    let nvim = connect(&registry, "two", &two);
+
+    let buf = nvim.get_current_buf().await.unwrap();
+    assert!(buf.attach(false, vec![]).await.unwrap());
+    two.set_lines(1, &["x"]);
+    match events.next().await.unwrap() {
+      Event::Notification { key, name, .. } => {
+        assert_eq!(("two", "nvim_buf_lines_event"), (key, name.as_str()));
+      }
+      e => panic!("Unexpected event {e:?}"),
+    }
+
+    nvim.shutdown().await;
+    match events.next().await.unwrap() {
+      Event::Closed { key, result } => {
+        assert_eq!("two", key);
+        assert!(result.is_ok());
+      }
+      e => panic!("Unexpected event {e:?}"),
+    }
+    assert_eq!(vec!["one"], registry.keys());
+    assert!(registry.get(&"two").is_none());
+  }
+
+  #[tokio::test]
+  async fn test_replaced() {
+    let (registry, mut events) = TestRegistry::new();
+    let fake = FakeNeovim::new();
+    let old = connect(&registry, "one", &fake);
+    let new = connect(&registry, "one", &fake);
+
+    old.shutdown().await;
+    new.shutdown().await;
+    // Only the replacing session was still registered when it closed
+    assert!(matches!(
+      events.next().await.unwrap(),
+      Event::Closed { key: "one", .. }
+    ));
+    assert!(registry.is_empty());
+  }
+}
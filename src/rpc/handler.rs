@@ -4,10 +4,19 @@
 //! [`handler`](crate::rpc::handler::Handler).
 use std::{future::Future, marker::PhantomData, sync::Arc};
 
-use futures::io::AsyncWrite;
+#[cfg(not(any(feature = "use_tokio", feature = "use_smol")))]
+use futures::future::BoxFuture;
+use futures::{
+  channel::{
+    mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    oneshot,
+  },
+  io::AsyncWrite,
+  StreamExt,
+};
 use rmpv::Value;
 
-use crate::Neovim;
+use crate::{create::LocalSpawner, error::SessionError, Neovim};
 
 /// The central functionality of a plugin. The trait bounds asure that each
 /// asynchronous task can receive a copy of the handler, so some state can be
@@ -29,9 +38,11 @@ pub trait Handler: Send + Sync + Clone + 'static {
     async { Err(Value::from("Not implemented")) }
   }
 
-  /// Handling an rpc notification. Notifications are handled one at a time in
-  /// the order in which they were received, and will block new requests from
-  /// being received until handle_notify returns.
+  /// Handling an rpc notification. By default, notifications are handled one
+  /// at a time in the order in which they were received, and will block new
+  /// requests from being received until handle_notify returns. See
+  /// [`notification_key`](Handler::notification_key) to handle them
+  /// concurrently.
   fn handle_notify(
     &self,
     _name: String,
@@ -40,6 +51,46 @@ pub trait Handler: Send + Sync + Clone + 'static {
   ) -> impl Future<Output = ()> + Send {
     async {}
   }
+
+  /// Decide how a notification is handled. If this returns `None` (the
+  /// default), the notification is handled as described in
+  /// [`handle_notify`](Handler::handle_notify).
+  ///
+  /// If this returns a key, the notification is handled in its own task like a
+  /// request, so it does not block other messages. Notifications with the
+  /// same key are still handled one at a time in the order in which they were
+  /// received, so e.g. returning the buffer for `nvim_buf_lines_event` keeps
+  /// the events of each buffer in order.
+  fn notification_key(&self, _name: &str, _args: &[Value]) -> Option<String> {
+    None
+  }
+
+  /// Handling a failure that can't be reported to a caller, e.g. because the
+  /// response to a request could not be sent. Neovim's `nvim_error_event`
+  /// notifications are passed here as well, in addition to
+  /// [`handle_notify`](Handler::handle_notify). This can be used to tear down
+  /// the session, or to reconnect. The error has already been logged, by
+  /// default nothing else happens.
+  fn handle_error(
+    &self,
+    _error: SessionError,
+    _neovim: Neovim<Self::Writer>,
+  ) -> impl Future<Output = ()> + Send {
+    async {}
+  }
+
+  /// Called after a session created with
+  /// [`Neovim::new_reconnecting`](crate::neovim::Neovim::new_reconnecting)
+  /// reconnected to neovim. The new connection knows nothing about the old
+  /// one, so this is the place to e.g. subscribe to events, attach to buffers
+  /// or set the client info again. Messages from neovim are handled
+  /// concurrently. By default, nothing happens.
+  fn handle_reconnect(
+    &self,
+    _neovim: Neovim<Self::Writer>,
+  ) -> impl Future<Output = ()> + Send {
+    async {}
+  }
 }
 
 /// The dummy handler defaults to doing nothing with a notification, and
@@ -81,3 +132,239 @@ where
     }
   }
 }
+
+/// Like [`Handler`], but neither the handler nor its futures need to be
+/// `Send`, so e.g. `Rc<RefCell<_>>` state or toolkit objects can be used. A
+/// local handler is run on a single-threaded executor, see
+/// [`Neovim::new_local`](crate::neovim::Neovim::new_local).
+///
+/// Notifications are handled one at a time in the order in which they were
+/// received, there is no equivalent of
+/// [`notification_key`](Handler::notification_key).
+pub trait LocalHandler: Clone + 'static {
+  /// The type where we write our responses to requests.
+  type Writer: AsyncWrite + Send + Unpin + 'static;
+
+  /// Handling an rpc request, see
+  /// [`Handler::handle_request`](Handler::handle_request).
+  fn handle_request(
+    &self,
+    _name: String,
+    _args: Vec<Value>,
+    _neovim: Neovim<Self::Writer>,
+  ) -> impl Future<Output = Result<Value, Value>> {
+    async { Err(Value::from("Not implemented")) }
+  }
+
+  /// Handling an rpc notification, see
+  /// [`Handler::handle_notify`](Handler::handle_notify).
+  fn handle_notify(
+    &self,
+    _name: String,
+    _args: Vec<Value>,
+    _neovim: Neovim<Self::Writer>,
+  ) -> impl Future<Output = ()> {
+    async {}
+  }
+
+  /// Handling a failure that can't be reported to a caller, see
+  /// [`Handler::handle_error`](Handler::handle_error).
+  fn handle_error(
+    &self,
+    _error: SessionError,
+    _neovim: Neovim<Self::Writer>,
+  ) -> impl Future<Output = ()> {
+    async {}
+  }
+}
+
+/// What a [`LocalProxy`] passes on to the local handler.
+pub(crate) enum LocalMessage<W>
+where
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  Request {
+    name: String,
+    args: Vec<Value>,
+    neovim: Neovim<W>,
+    response: oneshot::Sender<Result<Value, Value>>,
+  },
+  Notify {
+    name: String,
+    args: Vec<Value>,
+    neovim: Neovim<W>,
+    done: oneshot::Sender<()>,
+  },
+  Error {
+    error: SessionError,
+    neovim: Neovim<W>,
+    done: oneshot::Sender<()>,
+  },
+  /// A task of the session itself, if no runtime feature provides a
+  /// [`Spawner`](crate::create::Spawner).
+  #[cfg(not(any(feature = "use_tokio", feature = "use_smol")))]
+  Spawn(BoxFuture<'static, ()>),
+}
+
+/// A [`Handler`] standing in for a [`LocalHandler`], passing everything on to
+/// [`run_local`] on the local executor.
+pub(crate) struct LocalProxy<W>
+where
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  sender: UnboundedSender<LocalMessage<W>>,
+}
+
+impl<W> Clone for LocalProxy<W>
+where
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  fn clone(&self) -> Self {
+    LocalProxy {
+      sender: self.sender.clone(),
+    }
+  }
+}
+
+impl<W> LocalProxy<W>
+where
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  pub(crate) fn new() -> (Self, UnboundedReceiver<LocalMessage<W>>) {
+    let (sender, receiver) = unbounded();
+    (LocalProxy { sender }, receiver)
+  }
+
+  /// Pass on a message and wait for `receiver`. Fails if the local executor
+  /// is gone.
+  async fn pass<T>(
+    &self,
+    message: LocalMessage<W>,
+    receiver: oneshot::Receiver<T>,
+  ) -> Option<T> {
+    self.sender.unbounded_send(message).ok()?;
+    receiver.await.ok()
+  }
+}
+
+impl<W> Handler for LocalProxy<W>
+where
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  type Writer = W;
+
+  async fn handle_request(
+    &self,
+    name: String,
+    args: Vec<Value>,
+    neovim: Neovim<W>,
+  ) -> Result<Value, Value> {
+    let (response, receiver) = oneshot::channel();
+    let message = LocalMessage::Request {
+      name,
+      args,
+      neovim,
+      response,
+    };
+    self
+      .pass(message, receiver)
+      .await
+      .unwrap_or_else(|| Err(Value::from("Handler is gone")))
+  }
+
+  async fn handle_notify(
+    &self,
+    name: String,
+    args: Vec<Value>,
+    neovim: Neovim<W>,
+  ) {
+    let (done, receiver) = oneshot::channel();
+    let message = LocalMessage::Notify {
+      name,
+      args,
+      neovim,
+      done,
+    };
+    self.pass(message, receiver).await;
+  }
+
+  async fn handle_error(&self, error: SessionError, neovim: Neovim<W>) {
+    let (done, receiver) = oneshot::channel();
+    let message = LocalMessage::Error {
+      error,
+      neovim,
+      done,
+    };
+    self.pass(message, receiver).await;
+  }
+}
+
+#[cfg(not(any(feature = "use_tokio", feature = "use_smol")))]
+impl<W> crate::create::Spawner for LocalProxy<W>
+where
+  W: AsyncWrite + Send + Unpin + 'static,
+{
+  type Handle = ();
+
+  fn spawn<Fut>(&self, future: Fut)
+  where
+    Fut: Future<Output = ()> + Send + 'static,
+  {
+    // This only fails if the local executor is gone, and the session with it
+    let _ = self
+      .sender
+      .unbounded_send(LocalMessage::Spawn(Box::pin(future)));
+  }
+}
+
+/// Run everything passed on by the [`LocalProxy`] of `messages` on `handler`,
+/// each in its own local task. Resolves when all proxies are gone.
+pub(crate) async fn run_local<H>(
+  handler: H,
+  mut messages: UnboundedReceiver<LocalMessage<H::Writer>>,
+) where
+  H: LocalSpawner,
+{
+  while let Some(message) = messages.next().await {
+    let h = handler.clone();
+    match message {
+      LocalMessage::Request {
+        name,
+        args,
+        neovim,
+        response,
+      } => {
+        handler.spawn_local(async move {
+          let res = h.handle_request(name, args, neovim).await;
+          // The io loop might be gone already
+          let _ = response.send(res);
+        });
+      }
+      LocalMessage::Notify {
+        name,
+        args,
+        neovim,
+        done,
+      } => {
+        handler.spawn_local(async move {
+          h.handle_notify(name, args, neovim).await;
+          let _ = done.send(());
+        });
+      }
+      LocalMessage::Error {
+        error,
+        neovim,
+        done,
+      } => {
+        handler.spawn_local(async move {
+          h.handle_error(error, neovim).await;
+          let _ = done.send(());
+        });
+      }
+      #[cfg(not(any(feature = "use_tokio", feature = "use_smol")))]
+      LocalMessage::Spawn(task) => {
+        handler.spawn_local(task);
+      }
+    }
+  }
+}
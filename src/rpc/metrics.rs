@@ -0,0 +1,407 @@
+//! Metrics about the usage of a session.
+//!
+//! Collecting metrics is opt-in via
+//! [`SessionOptions::set_metrics`](crate::sessionoptions::SessionOptions::set_metrics).
+//! Then, [`Neovim::stats`](crate::neovim::Neovim::stats) returns a
+//! [`Stats`](crate::rpc::metrics::Stats) snapshot, containing
+//!
+//!   * the number of messages of each type sent and received,
+//!   * the number of bytes sent and received,
+//!   * the number of calls currently waiting for a response, and the maximum
+//!     seen so far,
+//!   * the latencies of calls to neovim, measured from sending the request to
+//!     receiving the response, per method,
+//!   * the time the handler took for requests and notifications, per method.
+//!
+//! Its [`Display`](std::fmt::Display) implementation gives a report suitable
+//! for logging or showing to the user.
+use std::{
+  collections::HashMap,
+  fmt,
+  sync::{
+    atomic::{AtomicU64, AtomicUsize, Ordering},
+    Arc, Mutex, MutexGuard, PoisonError,
+  },
+  time::{Duration, Instant},
+};
+
+use crate::rpc::model::RpcMessage;
+
+/// The upper bounds of the buckets of a
+/// [`Histogram`](crate::rpc::metrics::Histogram). Durations above the last
+/// bound are counted in an additional bucket.
+pub const BUCKET_BOUNDS: [Duration; 12] = [
+  Duration::from_micros(100),
+  Duration::from_micros(250),
+  Duration::from_micros(500),
+  Duration::from_millis(1),
+  Duration::from_micros(2500),
+  Duration::from_millis(5),
+  Duration::from_millis(10),
+  Duration::from_millis(25),
+  Duration::from_millis(50),
+  Duration::from_millis(100),
+  Duration::from_millis(250),
+  Duration::from_secs(1),
+];
+
+/// A histogram of durations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+  /// The number of durations in each bucket. The bucket at index `i` counts
+  /// the durations up to
+  /// [`BUCKET_BOUNDS[i]`](crate::rpc::metrics::BUCKET_BOUNDS), that aren't
+  /// counted in a previous bucket. The last bucket counts all larger
+  /// durations.
+  pub buckets: [u64; BUCKET_BOUNDS.len() + 1],
+  pub count: u64,
+  pub sum: Duration,
+  pub min: Duration,
+  pub max: Duration,
+}
+
+impl Default for Histogram {
+  fn default() -> Self {
+    Histogram {
+      buckets: [0; BUCKET_BOUNDS.len() + 1],
+      count: 0,
+      sum: Duration::ZERO,
+      min: Duration::MAX,
+      max: Duration::ZERO,
+    }
+  }
+}
+
+impl Histogram {
+  /// Add a duration.
+  pub fn add(&mut self, duration: Duration) {
+    let bucket = BUCKET_BOUNDS
+      .iter()
+      .position(|&bound| duration <= bound)
+      .unwrap_or(BUCKET_BOUNDS.len());
+    self.buckets[bucket] += 1;
+    self.count += 1;
+    self.sum += duration;
+    self.min = self.min.min(duration);
+    self.max = self.max.max(duration);
+  }
+
+  /// The mean of the durations, or `None` if there are none.
+  #[must_use]
+  pub fn mean(&self) -> Option<Duration> {
+    let count = u32::try_from(self.count).unwrap_or(u32::MAX);
+    (count > 0).then(|| self.sum / count)
+  }
+
+  /// An upper bound for the `q`-quantile of the durations (e.g. `q = 0.5` for
+  /// the median), or `None` if there are none. This is the upper bound of the
+  /// bucket containing the quantile, or the maximum if that's smaller.
+  #[must_use]
+  pub fn quantile(&self, q: f64) -> Option<Duration> {
+    if self.count == 0 {
+      return None;
+    }
+
+    let rank = ((q.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+    let mut seen = 0;
+    for (i, &n) in self.buckets.iter().enumerate() {
+      seen += n;
+      if seen >= rank {
+        let bound = BUCKET_BOUNDS.get(i).copied().unwrap_or(self.max);
+        return Some(bound.min(self.max));
+      }
+    }
+
+    Some(self.max)
+  }
+}
+
+/// A snapshot of the metrics of a session, see the [module docs](self).
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+  /// The time since the session was created
+  pub uptime: Duration,
+  pub requests_sent: u64,
+  pub requests_received: u64,
+  pub notifications_sent: u64,
+  pub notifications_received: u64,
+  pub responses_sent: u64,
+  pub responses_received: u64,
+  pub bytes_sent: u64,
+  pub bytes_received: u64,
+  /// The number of calls waiting for a response
+  pub in_flight: usize,
+  /// The maximum of `in_flight` over the whole session
+  pub max_in_flight: usize,
+  /// The latencies of calls to neovim, by method. Calls that didn't get a
+  /// response, e.g. because of a timeout, are not included.
+  pub call_latencies: HashMap<String, Histogram>,
+  /// The durations of the handler's
+  /// [`handle_request`](crate::rpc::handler::Handler::handle_request) and
+  /// [`handle_notify`](crate::rpc::handler::Handler::handle_notify), by
+  /// method.
+  pub handler_durations: HashMap<String, Histogram>,
+}
+
+impl Stats {
+  /// The number of messages sent per second, averaged over the uptime.
+  #[must_use]
+  pub fn sent_per_second(&self) -> f64 {
+    let sent =
+      self.requests_sent + self.notifications_sent + self.responses_sent;
+    sent as f64 / self.uptime.as_secs_f64().max(f64::EPSILON)
+  }
+
+  /// The number of messages received per second, averaged over the uptime.
+  #[must_use]
+  pub fn received_per_second(&self) -> f64 {
+    let received = self.requests_received
+      + self.notifications_received
+      + self.responses_received;
+    received as f64 / self.uptime.as_secs_f64().max(f64::EPSILON)
+  }
+}
+
+fn write_histograms(
+  fmt: &mut fmt::Formatter,
+  title: &str,
+  histograms: &HashMap<String, Histogram>,
+) -> fmt::Result {
+  writeln!(fmt, "{title}:")?;
+  if histograms.is_empty() {
+    return writeln!(fmt, "  none");
+  }
+
+  let mut methods: Vec<_> = histograms.iter().collect();
+  methods.sort_by(|a, b| b.1.sum.cmp(&a.1.sum).then(a.0.cmp(b.0)));
+  for (method, h) in methods {
+    writeln!(
+      fmt,
+      "  {method}: count {}, mean {:?}, p50 <= {:?}, p99 <= {:?}, max {:?}",
+      h.count,
+      h.mean().unwrap_or_default(),
+      h.quantile(0.5).unwrap_or_default(),
+      h.quantile(0.99).unwrap_or_default(),
+      h.max,
+    )?;
+  }
+
+  Ok(())
+}
+
+impl fmt::Display for Stats {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(fmt, "Uptime: {:?}", self.uptime)?;
+    writeln!(
+      fmt,
+      "Sent: {} requests, {} notifications, {} responses, {} bytes \
+       ({:.1} messages/s)",
+      self.requests_sent,
+      self.notifications_sent,
+      self.responses_sent,
+      self.bytes_sent,
+      self.sent_per_second(),
+    )?;
+    writeln!(
+      fmt,
+      "Received: {} requests, {} notifications, {} responses, {} bytes \
+       ({:.1} messages/s)",
+      self.requests_received,
+      self.notifications_received,
+      self.responses_received,
+      self.bytes_received,
+      self.received_per_second(),
+    )?;
+    writeln!(
+      fmt,
+      "Calls in flight: {} (max {})",
+      self.in_flight, self.max_in_flight
+    )?;
+    write_histograms(fmt, "Call latencies", &self.call_latencies)?;
+    write_histograms(fmt, "Handler durations", &self.handler_durations)
+  }
+}
+
+/// The type of an [`RpcMessage`](crate::rpc::model::RpcMessage).
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum MessageType {
+  Request,
+  Notification,
+  Response,
+}
+
+impl MessageType {
+  pub(crate) fn of(msg: &RpcMessage) -> Self {
+    match msg {
+      RpcMessage::RpcRequest { .. } => MessageType::Request,
+      RpcMessage::RpcNotification { .. } => MessageType::Notification,
+      RpcMessage::RpcResponse { .. } => MessageType::Response,
+    }
+  }
+}
+
+/// Counters for each message type.
+#[derive(Default)]
+struct MessageCounts {
+  requests: AtomicU64,
+  notifications: AtomicU64,
+  responses: AtomicU64,
+}
+
+impl MessageCounts {
+  fn count(&self, typ: MessageType) {
+    let counter = match typ {
+      MessageType::Request => &self.requests,
+      MessageType::Notification => &self.notifications,
+      MessageType::Response => &self.responses,
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+  }
+}
+
+/// The metrics collected for a session.
+pub(crate) struct Metrics {
+  started: Instant,
+  sent: MessageCounts,
+  received: MessageCounts,
+  bytes_sent: AtomicU64,
+  bytes_received: AtomicU64,
+  max_in_flight: AtomicUsize,
+  call_latencies: Histograms,
+  handler_durations: Histograms,
+}
+
+/// Histograms by method.
+type Histograms = Mutex<HashMap<String, Histogram>>;
+
+fn lock(histograms: &Histograms) -> MutexGuard<'_, HashMap<String, Histogram>> {
+  histograms.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Add `d` to the histogram for `method`.
+fn add(histograms: &Histograms, method: &str, d: Duration) {
+  let mut histograms = lock(histograms);
+  match histograms.get_mut(method) {
+    Some(histogram) => histogram.add(d),
+    None => {
+      let mut histogram = Histogram::default();
+      histogram.add(d);
+      histograms.insert(method.to_owned(), histogram);
+    }
+  }
+}
+
+impl Metrics {
+  pub(crate) fn new() -> Self {
+    Metrics {
+      started: Instant::now(),
+      sent: MessageCounts::default(),
+      received: MessageCounts::default(),
+      bytes_sent: AtomicU64::new(0),
+      bytes_received: AtomicU64::new(0),
+      max_in_flight: AtomicUsize::new(0),
+      call_latencies: Mutex::new(HashMap::new()),
+      handler_durations: Mutex::new(HashMap::new()),
+    }
+  }
+
+  /// Count a message that has been sent, encoded into `len` bytes.
+  pub(crate) fn sent(&self, typ: MessageType, len: usize) {
+    self.sent.count(typ);
+    self.bytes_sent.fetch_add(len as u64, Ordering::Relaxed);
+  }
+
+  /// Count a received message.
+  pub(crate) fn received(&self, msg: &RpcMessage) {
+    self.received.count(MessageType::of(msg));
+  }
+
+  /// Count `len` bytes read from neovim. Messages are split over reads, so
+  /// this is counted separately from the messages.
+  pub(crate) fn bytes_received(&self, len: u64) {
+    self.bytes_received.fetch_add(len, Ordering::Relaxed);
+  }
+
+  /// Update the maximum number of calls in flight.
+  pub(crate) fn in_flight(&self, in_flight: usize) {
+    self.max_in_flight.fetch_max(in_flight, Ordering::Relaxed);
+  }
+
+  /// Add the latency of a call started at `start`.
+  pub(crate) fn call_finished(&self, method: &str, start: Instant) {
+    add(&self.call_latencies, method, start.elapsed());
+  }
+
+  /// Start timing the handler for `method`. The time is added when the
+  /// returned value is dropped.
+  pub(crate) fn time_handler(self: &Arc<Self>, method: &str) -> HandlerTimer {
+    HandlerTimer {
+      metrics: self.clone(),
+      method: method.to_owned(),
+      start: Instant::now(),
+    }
+  }
+
+  pub(crate) fn snapshot(&self, in_flight: usize) -> Stats {
+    let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+
+    Stats {
+      uptime: self.started.elapsed(),
+      requests_sent: load(&self.sent.requests),
+      requests_received: load(&self.received.requests),
+      notifications_sent: load(&self.sent.notifications),
+      notifications_received: load(&self.received.notifications),
+      responses_sent: load(&self.sent.responses),
+      responses_received: load(&self.received.responses),
+      bytes_sent: load(&self.bytes_sent),
+      bytes_received: load(&self.bytes_received),
+      in_flight,
+      max_in_flight: self.max_in_flight.load(Ordering::Relaxed),
+      call_latencies: lock(&self.call_latencies).clone(),
+      handler_durations: lock(&self.handler_durations).clone(),
+    }
+  }
+}
+
+/// Times a handler, see
+/// [`Metrics::time_handler`](crate::rpc::metrics::Metrics::time_handler).
+pub(crate) struct HandlerTimer {
+  metrics: Arc<Metrics>,
+  method: String,
+  start: Instant,
+}
+
+impl Drop for HandlerTimer {
+  fn drop(&mut self) {
+    add(
+      &self.metrics.handler_durations,
+      &self.method,
+      self.start.elapsed(),
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_histogram() {
+    let mut h = Histogram::default();
+    assert_eq!(None, h.mean());
+    assert_eq!(None, h.quantile(0.5));
+
+    for micros in [50, 80, 300, 900, 20_000] {
+      h.add(Duration::from_micros(micros));
+    }
+    h.add(Duration::from_secs(3));
+
+    assert_eq!(6, h.count);
+    assert_eq!([2, 0, 1, 1, 0, 0, 0, 1, 0, 0, 0, 0, 1], h.buckets);
+    assert_eq!(Duration::from_micros(50), h.min);
+    assert_eq!(Some(Duration::from_micros(500)), h.quantile(0.5));
+    assert_eq!(Some(Duration::from_micros(100)), h.quantile(0.0));
+    assert_eq!(Some(Duration::from_secs(3)), h.quantile(1.0));
+    assert_eq!(Some(Duration::from_micros(503_555)), h.mean());
+  }
+}
@@ -3,7 +3,10 @@
 //! For most plugins, the main implementation work will consist of defining and
 //! implementing the [`handler`](crate::rpc::handler::Handler).
 pub mod handler;
+pub mod metrics;
 pub mod model;
+#[cfg(feature = "recorder")]
+pub mod recorder;
 pub mod unpack;
 
 pub use self::model::{IntoVal, RpcMessage};
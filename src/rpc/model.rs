@@ -2,7 +2,7 @@
 use std::{
   self,
   convert::TryInto,
-  io::{self, Cursor, ErrorKind, Read, Write},
+  io::{self, ErrorKind, Read, Write},
   sync::Arc,
 };
 
@@ -12,7 +12,7 @@ use futures::{
 };
 use rmpv::{decode::read_value, encode::write_value, Value};
 
-use crate::error::{DecodeError, EncodeError};
+use crate::error::{DecodeError, EncodeError, InvalidMessage};
 
 /// A msgpack-rpc message, see
 /// <https://github.com/msgpack-rpc/msgpack-rpc/blob/master/spec.md>
@@ -43,52 +43,225 @@ macro_rules! rpc_args {
     }}
 }
 
-/// Continously reads from reader, pushing onto `rest`. Then tries to decode the
-/// contents of `rest`. If it succeeds, returns the message, and leaves any
-/// non-decoded bytes in `rest`. If we did not read enough for a full message,
-/// read more. Return on all other errors.
-//
-// TODO: This might be inefficient. Can't we read into `rest` directly?
-pub async fn decode<R: AsyncRead + Send + Unpin + 'static>(
-  reader: &mut R,
-  rest: &mut Vec<u8>,
-) -> std::result::Result<RpcMessage, Box<DecodeError>> {
-  let mut buf = Box::new([0_u8; 80 * 1024]);
-  let mut bytes_read;
+/// The size of the buffer a [`Decoder`](crate::rpc::model::Decoder) starts
+/// with. It grows if a message doesn't fit.
+const INITIAL_BUFFER_SIZE: usize = 80 * 1024;
+
+/// The minimal number of bytes we try to read at once.
+const MIN_READ_SIZE: usize = 8 * 1024;
+
+/// The default maximum size of a message a
+/// [`Decoder`](crate::rpc::model::Decoder) accepts.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 256 * 1024 * 1024;
+
+/// A decoder for a stream of rpc messages.
+///
+/// The decoder owns a buffer that is reused for all messages, and data is read
+/// from the reader directly into its free space. Before a message is decoded,
+/// its length is determined by scanning the msgpack markers, so incomplete
+/// messages are not decoded over and over again while waiting for more data.
+///
+/// The buffer only grows as data arrives, not to the length a message
+/// declares. Messages larger than the maximum size are rejected with
+/// [`InvalidMessage::TooLarge`](crate::error::InvalidMessage::TooLarge).
+#[derive(Debug)]
+pub struct Decoder {
+  buf: Vec<u8>,
+  /// The start of the data that has not been decoded yet
+  start: usize,
+  /// The end of the data that has been read into `buf`
+  end: usize,
+  /// The minimal number of bytes from `start` needed for the next message to
+  /// be complete
+  needed: usize,
+  /// The total number of bytes read
+  bytes_read: u64,
+  /// The maximum number of bytes of a single message
+  max_message_size: usize,
+}
+
+impl Default for Decoder {
+  fn default() -> Self {
+    Decoder {
+      buf: vec![0; INITIAL_BUFFER_SIZE],
+      start: 0,
+      end: 0,
+      needed: 1,
+      bytes_read: 0,
+      max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+    }
+  }
+}
 
-  loop {
-    let mut c = Cursor::new(&rest);
+impl Decoder {
+  #[must_use]
+  pub fn new() -> Self {
+    Self::default()
+  }
 
-    match decode_buffer(&mut c).map_err(|b| *b) {
-      Ok(msg) => {
-        let pos = c.position();
-        *rest = rest.split_off(pos as usize); // TODO: more efficiency
-        return Ok(msg);
+  /// Set the maximum size of a message. The default is
+  /// [`DEFAULT_MAX_MESSAGE_SIZE`](crate::rpc::model::DEFAULT_MAX_MESSAGE_SIZE).
+  pub fn set_max_message_size(&mut self, val: usize) -> &mut Self {
+    self.max_message_size = val;
+    self
+  }
+
+  /// The total number of bytes read from the reader.
+  #[must_use]
+  pub fn bytes_read(&self) -> u64 {
+    self.bytes_read
+  }
+
+  /// Reads from `reader` until a full message is available, and decodes it.
+  /// Any data read beyond that message is kept for the next call.
+  pub async fn decode<R: AsyncRead + Send + Unpin + 'static>(
+    &mut self,
+    reader: &mut R,
+  ) -> std::result::Result<RpcMessage, Box<DecodeError>> {
+    loop {
+      if self.end - self.start >= self.needed {
+        match message_len(&self.buf[self.start..self.end]) {
+          Ok(len) | Err(len) if len > self.max_message_size => {
+            return Err(
+              InvalidMessage::TooLarge(len, self.max_message_size).into(),
+            );
+          }
+          Ok(len) => {
+            let mut msg = &self.buf[self.start..self.start + len];
+            self.start += len;
+            self.needed = 1;
+            return decode_buffer(&mut msg);
+          }
+          Err(needed) => {
+            debug!("Not enough data, reading more!");
+            self.needed = needed;
+          }
+        }
       }
-      Err(DecodeError::BufferError(e))
-        if e.kind() == ErrorKind::UnexpectedEof =>
-      {
-        debug!("Not enough data, reading more!");
-        bytes_read = reader.read(&mut *buf).await;
+
+      self.make_room();
+
+      match reader.read(&mut self.buf[self.end..]).await {
+        Ok(0) => {
+          return Err(io::Error::new(ErrorKind::UnexpectedEof, "EOF").into());
+        }
+        Ok(n) => {
+          self.end += n;
+          self.bytes_read += n as u64;
+        }
+        Err(err) => return Err(err.into()),
       }
-      Err(err) => return Err(err.into()),
     }
+  }
 
-    match bytes_read {
-      Ok(n) if n == 0 => {
-        return Err(io::Error::new(ErrorKind::UnexpectedEof, "EOF").into());
-      }
-      Ok(n) => {
-        rest.extend_from_slice(&buf[..n]);
+  /// Make sure there's space after `end` to read at least `MIN_READ_SIZE`
+  /// bytes. Moves undecoded data to the front of the buffer if that's needed
+  /// to make room, and grows the buffer only if the data doesn't fit
+  /// otherwise.
+  ///
+  /// The length the next message declares is never allocated up front, since
+  /// it's not known to be real yet. Instead, the buffer at most doubles with
+  /// the data that has actually arrived.
+  fn make_room(&mut self) {
+    if self.start == self.end {
+      self.start = 0;
+      self.end = 0;
+    }
+
+    let pending = self.end - self.start;
+    let wanted = self
+      .needed
+      .min(pending.saturating_mul(2))
+      .max(pending + MIN_READ_SIZE);
+
+    if self.buf.len() - self.start < wanted {
+      self.buf.copy_within(self.start..self.end, 0);
+      self.start = 0;
+      self.end = pending;
+    }
+
+    if self.buf.len() < wanted {
+      self.buf.resize(wanted, 0);
+    }
+  }
+}
+
+/// Determines the length of the msgpack value at the start of `buf` by
+/// scanning the markers, without decoding the value itself. If `buf` does not
+/// contain the complete value, returns the minimal length `buf` needs to have
+/// for that.
+///
+/// Invalid markers are not reported here, but lead to returning a length that
+/// makes the caller try to decode the data, and get a proper error that way.
+pub(crate) fn message_len(buf: &[u8]) -> std::result::Result<usize, usize> {
+  use rmp::Marker::*;
+
+  let mut pos = 0;
+  // The number of values we still need to skip
+  let mut remaining: usize = 1;
+
+  while remaining > 0 {
+    remaining -= 1;
+
+    let Some(&byte) = buf.get(pos) else {
+      // Every value takes at least one byte
+      return Err(pos.saturating_add(remaining + 1));
+    };
+
+    // Read a big-endian length of `n` bytes after the marker
+    let read_len = |n: usize| -> std::result::Result<usize, usize> {
+      match buf.get(pos + 1..pos + 1 + n) {
+        Some(bytes) => {
+          Ok(bytes.iter().fold(0, |acc, b| (acc << 8) | usize::from(*b)))
+        }
+        None => Err((pos + 1 + n).saturating_add(remaining)),
       }
-      Err(err) => return Err(err.into()),
+    };
+
+    // The size of the marker and its length field, the size of the data
+    // following it, and the number of values contained in it
+    let (header, data, values) = match rmp::Marker::from_u8(byte) {
+      FixPos(_) | FixNeg(_) | Null | True | False => (1, 0, 0),
+      U8 | I8 => (1, 1, 0),
+      U16 | I16 => (1, 2, 0),
+      U32 | I32 | F32 => (1, 4, 0),
+      U64 | I64 | F64 => (1, 8, 0),
+      FixStr(n) => (1, usize::from(n), 0),
+      Str8 | Bin8 => (2, read_len(1)?, 0),
+      Str16 | Bin16 => (3, read_len(2)?, 0),
+      Str32 | Bin32 => (5, read_len(4)?, 0),
+      FixArray(n) => (1, 0, usize::from(n)),
+      Array16 => (3, 0, read_len(2)?),
+      Array32 => (5, 0, read_len(4)?),
+      FixMap(n) => (1, 0, 2 * usize::from(n)),
+      Map16 => (3, 0, 2 * read_len(2)?),
+      Map32 => (5, 0, read_len(4)?.saturating_mul(2)),
+      // The type byte counts as data here
+      FixExt1 => (1, 2, 0),
+      FixExt2 => (1, 3, 0),
+      FixExt4 => (1, 5, 0),
+      FixExt8 => (1, 9, 0),
+      FixExt16 => (1, 17, 0),
+      Ext8 => (2, read_len(1)? + 1, 0),
+      Ext16 => (3, read_len(2)? + 1, 0),
+      Ext32 => (5, read_len(4)?.saturating_add(1), 0),
+      Reserved => return Ok(pos + 1),
+    };
+
+    pos = pos.saturating_add(header).saturating_add(data);
+    remaining = remaining.saturating_add(values);
+
+    if pos > buf.len() {
+      return Err(pos.saturating_add(remaining));
     }
   }
+
+  Ok(pos)
 }
 
 /// Syncronously decode the content of a reader into an rpc message. Tries to
 /// give detailed errors if something went wrong.
-fn decode_buffer<R: Read>(
+pub(crate) fn decode_buffer<R: Read>(
   reader: &mut R,
 ) -> std::result::Result<RpcMessage, Box<DecodeError>> {
   use crate::error::InvalidMessage::*;
@@ -111,10 +284,10 @@ fn decode_buffer<R: Read>(
         .try_into()
         .map_err(InvalidMsgid)?;
       let method = match arr.next() {
-        Some(Value::String(s)) if s.is_str() => {
-          s.into_str().expect("Can remove using #230 of rmpv")
-        }
-        Some(val) => return Err(InvalidRequestName(msgid, val).into()),
+        Some(val) => match val.as_str() {
+          Some(method) => method.to_owned(),
+          None => return Err(InvalidRequestName(msgid, val).into()),
+        },
         None => return Err(WrongArrayLength(4..=4, 2).into()),
       };
       let params: Vec<Value> = arr
@@ -145,10 +318,10 @@ fn decode_buffer<R: Read>(
     }
     2 => {
       let method = match arr.next() {
-        Some(Value::String(s)) if s.is_str() => {
-          s.into_str().expect("Can remove using #230 of rmpv")
-        }
-        Some(val) => return Err(InvalidNotificationName(val).into()),
+        Some(val) => match val.as_str() {
+          Some(method) => method.to_owned(),
+          None => return Err(InvalidNotificationName(val).into()),
+        },
         None => return Err(WrongArrayLength(3..=3, 1).into()),
       };
       let params: Vec<Value> = arr
@@ -202,8 +375,18 @@ pub async fn encode<W: AsyncWrite + Send + Unpin + 'static>(
   let mut v: Vec<u8> = vec![];
   encode_sync(&mut v, msg)?;
 
+  write_all(&writer, &v).await?;
+
+  Ok(())
+}
+
+/// Write already encoded data into the writer and flush it.
+pub(crate) async fn write_all<W: AsyncWrite + Send + Unpin + 'static>(
+  writer: &Mutex<W>,
+  data: &[u8],
+) -> io::Result<()> {
   let mut writer = writer.lock().await;
-  writer.write_all(&v).await?;
+  writer.write_all(data).await?;
   writer.flush().await?;
 
   Ok(())
@@ -277,7 +460,8 @@ impl IntoVal<Value> for Vec<(Value, Value)> {
 #[cfg(all(test, feature = "use_tokio"))]
 mod test {
   use super::*;
-  use futures::{io::BufWriter, lock::Mutex};
+  use crate::error::InvalidMessage;
+  use futures::{io::BufWriter, lock::Mutex, stream::TryStreamExt};
   use std::{io::Cursor, sync::Arc};
 
   use tokio;
@@ -343,4 +527,118 @@ mod test {
     let msg_dest_2 = decode_buffer(&mut cursor).unwrap();
     assert_eq!(msg_2, msg_dest_2);
   }
+
+  fn test_messages() -> Vec<RpcMessage> {
+    vec![
+      RpcMessage::RpcRequest {
+        msgid: 1,
+        method: "test_method".to_owned(),
+        params: vec![],
+      },
+      RpcMessage::RpcNotification {
+        method: "redraw".to_owned(),
+        params: vec![
+          Value::from(vec![Value::from("grid_line"); 20]),
+          Value::from(vec![(Value::from(1), Value::from(-1.5)); 20]),
+          Value::Ext(0, vec![1, 2, 3]),
+          Value::Ext(1, vec![0; 300]),
+          Value::from("x".repeat(40)),
+          Value::from(vec![0_u8; 300]),
+          Value::from(u64::MAX),
+          Value::from(i64::MIN),
+        ],
+      },
+      RpcMessage::RpcResponse {
+        msgid: 2,
+        error: Value::Nil,
+        result: Value::from("y".repeat(200 * 1024)),
+      },
+    ]
+  }
+
+  #[test]
+  fn message_len_test() {
+    for msg in test_messages() {
+      let mut data = vec![];
+      encode_sync(&mut data, msg).unwrap();
+
+      assert_eq!(Ok(data.len()), message_len(&data));
+      for i in 0..data.len() {
+        let needed = message_len(&data[..i]).unwrap_err();
+        assert!(i < needed && needed <= data.len());
+      }
+    }
+  }
+
+  #[tokio::test]
+  async fn decoder_test() {
+    let msgs = test_messages();
+
+    let mut data = vec![];
+    for msg in msgs.iter().cloned() {
+      encode_sync(&mut data, msg).unwrap();
+    }
+
+    // Feed the data in small chunks that don't line up with the messages
+    let chunks: Vec<io::Result<Vec<u8>>> =
+      data.chunks(1000).map(|c| Ok(c.to_vec())).collect();
+    let mut reader = futures::stream::iter(chunks).into_async_read();
+
+    let mut decoder = Decoder::new();
+    for msg in msgs {
+      assert_eq!(msg, decoder.decode(&mut reader).await.unwrap());
+    }
+    assert_eq!(data.len() as u64, decoder.bytes_read());
+
+    match *decoder.decode(&mut reader).await.unwrap_err() {
+      DecodeError::ReaderError(ref e) => {
+        assert_eq!(e.kind(), ErrorKind::UnexpectedEof)
+      }
+      ref e => panic!("Unexpected error {e}"),
+    }
+  }
+
+  #[tokio::test]
+  async fn decoder_invalid_message_test() {
+    let mut data = vec![];
+    write_value(&mut data, &Value::from(vec![Value::from(7), Value::Nil]))
+      .unwrap();
+    let mut reader = futures::io::Cursor::new(data);
+
+    match *Decoder::new().decode(&mut reader).await.unwrap_err() {
+      DecodeError::InvalidMessage(InvalidMessage::UnknownMessageType(7)) => {}
+      ref e => panic!("Unexpected error {e}"),
+    }
+  }
+
+  #[tokio::test]
+  async fn decoder_declared_length_test() {
+    // Nested array32 headers declaring billions of elements, and a str32
+    // header declaring 4 GB. Nothing but the headers ever arrives.
+    let arrays = [0xdd, 0xff, 0xff, 0xff, 0xff].repeat(6);
+    let string = vec![0xdb, 0xff, 0xff, 0xff, 0xff];
+
+    for data in [arrays, string] {
+      let mut reader = futures::io::Cursor::new(data);
+      let mut decoder = Decoder::new();
+      match *decoder.decode(&mut reader).await.unwrap_err() {
+        DecodeError::InvalidMessage(InvalidMessage::TooLarge(needed, max)) => {
+          assert!(needed > max);
+          assert_eq!(DEFAULT_MAX_MESSAGE_SIZE, max);
+        }
+        ref e => panic!("Unexpected error {e}"),
+      }
+      assert_eq!(INITIAL_BUFFER_SIZE, decoder.buf.len());
+    }
+
+    let mut data = vec![];
+    write_value(&mut data, &Value::from("x".repeat(100))).unwrap();
+    let mut reader = futures::io::Cursor::new(data);
+    let mut decoder = Decoder::new();
+    decoder.set_max_message_size(50);
+    match *decoder.decode(&mut reader).await.unwrap_err() {
+      DecodeError::InvalidMessage(InvalidMessage::TooLarge(102, 50)) => {}
+      ref e => panic!("Unexpected error {e}"),
+    }
+  }
 }
@@ -0,0 +1,755 @@
+//! Recording the messages exchanged with neovim, for debugging.
+//!
+//! A [`Recorder`](crate::rpc::recorder::Recorder) writes every
+//! [`RpcMessage`](crate::rpc::model::RpcMessage) as one line of JSON, e.g.
+//!
+//! ```text
+//! {"time":1700000000.123456,"direction":"out","type":"request","msgid":1,"method":"nvim_get_current_buf","params":[]}
+//! {"time":1700000000.124012,"direction":"in","type":"response","msgid":1,"error":null,"result":{"buffer":1}}
+//! ```
+//!
+//! The time is given in seconds since the unix epoch. Buffers, windows and
+//! tabpages are shown as their handles, other binary data as hex strings.
+//! Messages are written by a background thread, so recording doesn't hold up
+//! the session.
+//!
+//! This module is only available with the `recorder` feature.
+//!
+//! There are 2 ways to record a session:
+//!
+//!   1. Wrap the reader and writer passed to
+//!      [`Neovim::new`](crate::neovim::Neovim::new) via
+//!      [`reader`](crate::rpc::recorder::Recorder::reader) and
+//!      [`writer`](crate::rpc::recorder::Recorder::writer).
+//!   2. Pass the recorder to
+//!      [`SessionOptions::set_recorder`](crate::sessionoptions::SessionOptions::set_recorder).
+//!      This also works with the constructors in `create::tokio` and
+//!      `create::smol`, via their `_with_options` variants, e.g.
+//!      [`new_tcp_with_options`](crate::create::tokio::new_tcp_with_options).
+//!
+//! A transcript can be read back with
+//! [`read_transcript`](crate::rpc::recorder::read_transcript), e.g. to replay
+//! it with a [`Script`](crate::testing::script::Script). Floats are written
+//! with a fraction or exponent, so they're told apart from integers. Since
+//! JSON has fewer types than msgpack, some information is still lost: 32 bit
+//! floats are read as 64 bit floats, maps without string keys stay lists of
+//! pairs, and an object with the single key `buffer`, `window`, `tabpage` or
+//! `binary` is always read as the corresponding value.
+use std::{
+  fmt,
+  fs::OpenOptions,
+  io::{self, BufRead, BufWriter, Write},
+  path::Path,
+  pin::Pin,
+  sync::mpsc::{self, Receiver, Sender},
+  task::{Context, Poll},
+  thread,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures::io::{AsyncRead, AsyncWrite};
+use rmpv::Value;
+use serde_json::{Map, Number, Value as Json};
+
+use crate::rpc::model::{self, RpcMessage};
+
+/// Whether a message was sent to neovim or received from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+  /// Received from neovim
+  Incoming,
+  /// Sent to neovim
+  Outgoing,
+}
+
+impl Direction {
+  fn as_str(self) -> &'static str {
+    match self {
+      Direction::Incoming => "in",
+      Direction::Outgoing => "out",
+    }
+  }
+}
+
+/// What the recorder thread is asked to do.
+enum Command {
+  Record(Duration, Direction, RpcMessage),
+  /// Flush the sink, and report back when done
+  Flush(Sender<()>),
+}
+
+/// Writes a transcript of messages to a sink. Clones write to the same sink,
+/// every message is written as a whole line.
+///
+/// The sink is owned by a background thread, which ends after all clones of
+/// the recorder have been dropped.
+#[derive(Clone)]
+pub struct Recorder {
+  sender: Sender<Command>,
+}
+
+impl fmt::Debug for Recorder {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("Recorder")
+  }
+}
+
+impl Recorder {
+  /// Record to `sink`. Writes are buffered, and the sink is flushed whenever
+  /// the recorder thread runs out of messages to write.
+  pub fn new<S: Write + Send + 'static>(sink: S) -> Self {
+    let (sender, receiver) = mpsc::channel();
+    thread::Builder::new()
+      .name("nvim-rs-recorder".to_owned())
+      .spawn(move || write_transcript(BufWriter::new(sink), receiver))
+      .expect("Could not spawn the recorder thread");
+
+    Recorder { sender }
+  }
+
+  /// Record to the file at `path`. The file is created if it does not exist,
+  /// otherwise the transcript is appended.
+  pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    Ok(Self::new(file))
+  }
+
+  /// Pass a message to the recorder thread, which writes it to the transcript.
+  /// Errors are logged, but otherwise ignored, so they don't interfere with
+  /// the session.
+  pub fn record(&self, direction: Direction, msg: &RpcMessage) {
+    let time = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .unwrap_or_default();
+    if self
+      .sender
+      .send(Command::Record(time, direction, msg.clone()))
+      .is_err()
+    {
+      error!("Recorder thread is gone, could not record message");
+    }
+  }
+
+  /// Block until all messages recorded so far have been written to the sink,
+  /// and the sink has been flushed.
+  pub fn flush(&self) {
+    let (sender, receiver) = mpsc::channel();
+    if self.sender.send(Command::Flush(sender)).is_ok() {
+      let _ = receiver.recv();
+    }
+  }
+
+  /// Wrap a reader, recording all messages read from it as incoming.
+  pub fn reader<R: AsyncRead + Unpin>(&self, reader: R) -> RecordingReader<R> {
+    RecordingReader {
+      inner: reader,
+      tap: Tap::new(self.clone(), Direction::Incoming),
+    }
+  }
+
+  /// Wrap a writer, recording all messages written to it as outgoing.
+  pub fn writer<W: AsyncWrite + Unpin>(&self, writer: W) -> RecordingWriter<W> {
+    RecordingWriter {
+      inner: writer,
+      tap: Tap::new(self.clone(), Direction::Outgoing),
+    }
+  }
+}
+
+/// The loop of the recorder thread. Runs until all senders are gone.
+fn write_transcript<S: Write>(mut sink: S, receiver: Receiver<Command>) {
+  let mut pending = false;
+
+  loop {
+    let command = match receiver.try_recv() {
+      Ok(command) => command,
+      Err(mpsc::TryRecvError::Empty) if pending => {
+        flush(&mut sink);
+        pending = false;
+        continue;
+      }
+      Err(mpsc::TryRecvError::Empty) => match receiver.recv() {
+        Ok(command) => command,
+        Err(_) => break,
+      },
+      Err(mpsc::TryRecvError::Disconnected) => break,
+    };
+
+    match command {
+      Command::Record(time, direction, msg) => {
+        let mut line = to_line(time, direction, msg).to_string();
+        line.push('\n');
+        if let Err(e) = sink.write_all(line.as_bytes()) {
+          error!("Error recording message: '{}'", e);
+        }
+        pending = true;
+      }
+      Command::Flush(done) => {
+        flush(&mut sink);
+        pending = false;
+        let _ = done.send(());
+      }
+    }
+  }
+
+  flush(&mut sink);
+}
+
+fn flush<S: Write>(sink: &mut S) {
+  if let Err(e) = sink.flush() {
+    error!("Error flushing the transcript: '{}'", e);
+  }
+}
+
+/// Collects the bytes passing through a stream, and records every message as
+/// soon as it is complete.
+struct Tap {
+  recorder: Recorder,
+  direction: Direction,
+  buf: Vec<u8>,
+}
+
+impl Tap {
+  fn new(recorder: Recorder, direction: Direction) -> Self {
+    Tap {
+      recorder,
+      direction,
+      buf: Vec::new(),
+    }
+  }
+
+  fn feed(&mut self, data: &[u8]) {
+    self.buf.extend_from_slice(data);
+
+    let mut start = 0;
+    while let Ok(len) = model::message_len(&self.buf[start..]) {
+      let mut msg = &self.buf[start..start + len];
+      start += len;
+      match model::decode_buffer(&mut msg) {
+        Ok(msg) => self.recorder.record(self.direction, &msg),
+        Err(e) => warn!("Could not record message: '{}'", e),
+      }
+    }
+    self.buf.drain(..start);
+  }
+}
+
+/// A reader recording the messages read from it, see
+/// [`Recorder::reader`](crate::rpc::recorder::Recorder::reader).
+pub struct RecordingReader<R> {
+  inner: R,
+  tap: Tap,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for RecordingReader<R> {
+  fn poll_read(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut [u8],
+  ) -> Poll<io::Result<usize>> {
+    let this = &mut *self;
+    let res = Pin::new(&mut this.inner).poll_read(cx, buf);
+    if let Poll::Ready(Ok(n)) = res {
+      this.tap.feed(&buf[..n]);
+    }
+    res
+  }
+}
+
+/// A writer recording the messages written to it, see
+/// [`Recorder::writer`](crate::rpc::recorder::Recorder::writer).
+pub struct RecordingWriter<W> {
+  inner: W,
+  tap: Tap,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for RecordingWriter<W> {
+  fn poll_write(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<io::Result<usize>> {
+    let this = &mut *self;
+    let res = Pin::new(&mut this.inner).poll_write(cx, buf);
+    if let Poll::Ready(Ok(n)) = res {
+      this.tap.feed(&buf[..n]);
+    }
+    res
+  }
+
+  fn poll_flush(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.inner).poll_flush(cx)
+  }
+
+  fn poll_close(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.inner).poll_close(cx)
+  }
+}
+
+/// The transcript line for a message, without the trailing newline.
+fn to_line(time: Duration, direction: Direction, msg: RpcMessage) -> Json {
+  let mut line = Map::new();
+  line.insert("time".to_owned(), Json::from(time.as_secs_f64()));
+  line.insert("direction".to_owned(), Json::from(direction.as_str()));
+
+  let mut field = |key: &str, val: Json| line.insert(key.to_owned(), val);
+  match msg {
+    RpcMessage::RpcRequest {
+      msgid,
+      method,
+      params,
+    } => {
+      field("type", Json::from("request"));
+      field("msgid", Json::from(msgid));
+      field("method", Json::from(method));
+      field("params", params.iter().map(to_json).collect());
+    }
+    RpcMessage::RpcResponse {
+      msgid,
+      error,
+      result,
+    } => {
+      field("type", Json::from("response"));
+      field("msgid", Json::from(msgid));
+      field("error", to_json(&error));
+      field("result", to_json(&result));
+    }
+    RpcMessage::RpcNotification { method, params } => {
+      field("type", Json::from("notification"));
+      field("method", Json::from(method));
+      field("params", params.iter().map(to_json).collect());
+    }
+  }
+
+  Json::Object(line)
+}
+
+fn to_json(val: &Value) -> Json {
+  match val {
+    Value::Nil => Json::Null,
+    Value::Boolean(b) => Json::from(*b),
+    Value::Integer(i) => match i.as_i64() {
+      Some(i) => Json::from(i),
+      None => Json::from(i.as_u64()),
+    },
+    Value::F32(f) => float(f64::from(*f)),
+    Value::F64(f) => float(*f),
+    Value::String(s) => Json::from(String::from_utf8_lossy(s.as_bytes())),
+    Value::Binary(data) => object([("binary", Json::from(to_hex(data)))]),
+    Value::Array(arr) => arr.iter().map(to_json).collect(),
+    Value::Map(map) => {
+      let object: Map<_, _> = map
+        .iter()
+        .filter_map(|(k, v)| Some((k.as_str()?.to_owned(), to_json(v))))
+        .collect();
+      if object.len() == map.len() {
+        Json::Object(object)
+      } else {
+        // JSON only has unique string keys, so write a list of pairs
+        map
+          .iter()
+          .map(|(k, v)| Json::from(vec![to_json(k), to_json(v)]))
+          .collect()
+      }
+    }
+    Value::Ext(typ, data) => {
+      let name = match typ {
+        0 => Some("buffer"),
+        1 => Some("window"),
+        2 => Some("tabpage"),
+        _ => None,
+      };
+      let handle = rmpv::decode::read_value(&mut data.as_slice())
+        .ok()
+        .and_then(|v| v.as_i64());
+
+      match (name, handle) {
+        (Some(name), Some(handle)) => object([(name, Json::from(handle))]),
+        _ => object([
+          ("ext", Json::from(*typ)),
+          ("data", Json::from(to_hex(data))),
+        ]),
+      }
+    }
+  }
+}
+
+/// Non-finite floats can't be represented in JSON, and are written as `null`.
+fn float(f: f64) -> Json {
+  Number::from_f64(f).map_or(Json::Null, Json::Number)
+}
+
+fn object<const N: usize>(fields: [(&str, Json); N]) -> Json {
+  Json::Object(fields.into_iter().map(|(k, v)| (k.to_owned(), v)).collect())
+}
+
+fn to_hex(data: &[u8]) -> String {
+  data.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Read a transcript written by a [`Recorder`](crate::rpc::recorder::Recorder).
+/// Empty lines are skipped, a line that can't be parsed is reported as an
+/// error of kind [`InvalidData`](std::io::ErrorKind::InvalidData).
+pub fn read_transcript<R: BufRead>(
+  reader: R,
+) -> io::Result<Vec<(Direction, RpcMessage)>> {
+  let mut entries = Vec::new();
+
+  for (i, line) in reader.lines().enumerate() {
+    let line = line?;
+    if line.trim().is_empty() {
+      continue;
+    }
+    let entry = parse_line(&line).ok_or_else(|| {
+      io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid transcript entry in line {}: '{}'", i + 1, line),
+      )
+    })?;
+    entries.push(entry);
+  }
+
+  Ok(entries)
+}
+
+fn parse_line(line: &str) -> Option<(Direction, RpcMessage)> {
+  let Json::Object(mut fields) = serde_json::from_str(line).ok()? else {
+    return None;
+  };
+  let mut field = |name: &str| fields.remove(name);
+  let string = |val: Option<Json>| match val? {
+    Json::String(s) => Some(s),
+    _ => None,
+  };
+  let msgid = |val: Option<Json>| val?.as_u64();
+  let params = |val: Option<Json>| match from_json(val?) {
+    Value::Array(params) => Some(params),
+    _ => None,
+  };
+
+  let direction = match string(field("direction"))?.as_str() {
+    "in" => Direction::Incoming,
+    "out" => Direction::Outgoing,
+    _ => return None,
+  };
+  let msg = match string(field("type"))?.as_str() {
+    "request" => RpcMessage::RpcRequest {
+      msgid: msgid(field("msgid"))?,
+      method: string(field("method"))?,
+      params: params(field("params"))?,
+    },
+    "response" => RpcMessage::RpcResponse {
+      msgid: msgid(field("msgid"))?,
+      error: from_json(field("error")?),
+      result: from_json(field("result")?),
+    },
+    "notification" => RpcMessage::RpcNotification {
+      method: string(field("method"))?,
+      params: params(field("params"))?,
+    },
+    _ => return None,
+  };
+
+  Some((direction, msg))
+}
+
+/// Convert to the value that was written as this JSON value, see
+/// [`to_json`](crate::rpc::recorder::to_json).
+fn from_json(json: Json) -> Value {
+  match json {
+    Json::Null => Value::Nil,
+    Json::Bool(b) => Value::from(b),
+    Json::Number(n) => number(&n),
+    Json::String(s) => Value::from(s),
+    Json::Array(arr) => {
+      Value::from(arr.into_iter().map(from_json).collect::<Vec<_>>())
+    }
+    Json::Object(fields) => {
+      if let Some(val) = special_object(&fields) {
+        return val;
+      }
+      Value::from(
+        fields
+          .into_iter()
+          .map(|(k, v)| (Value::from(k), from_json(v)))
+          .collect::<Vec<_>>(),
+      )
+    }
+  }
+}
+
+/// Numbers with a fraction or exponent are floats, all others integers.
+fn number(n: &Number) -> Value {
+  if let Some(i) = n.as_i64() {
+    Value::from(i)
+  } else if let Some(u) = n.as_u64() {
+    Value::from(u)
+  } else {
+    Value::from(n.as_f64().unwrap_or(f64::NAN))
+  }
+}
+
+/// Recognize the objects written for binary data and ext types.
+fn special_object(fields: &Map<String, Json>) -> Option<Value> {
+  if fields.len() == 1 {
+    let (key, val) = fields.iter().next()?;
+    let typ = match (key.as_str(), val) {
+      ("binary", Json::String(hex)) => {
+        return Some(Value::Binary(from_hex(hex)?));
+      }
+      ("buffer", Json::Number(_)) => 0,
+      ("window", Json::Number(_)) => 1,
+      ("tabpage", Json::Number(_)) => 2,
+      _ => return None,
+    };
+    let handle = val.as_i64()?;
+    let mut data = Vec::new();
+    rmpv::encode::write_value(&mut data, &Value::from(handle)).ok()?;
+    return Some(Value::Ext(typ, data));
+  }
+
+  match (fields.get("ext")?, fields.get("data")?) {
+    (Json::Number(typ), Json::String(hex)) if fields.len() == 2 => Some(
+      Value::Ext(i8::try_from(typ.as_i64()?).ok()?, from_hex(hex)?),
+    ),
+    _ => None,
+  }
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+  if !hex.len().is_multiple_of(2) {
+    return None;
+  }
+  (0..hex.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::sync::{Arc, Mutex};
+
+  use futures::{executor::block_on, io::AsyncWriteExt};
+
+  /// A sink that can be read from after handing it to a recorder
+  #[derive(Clone, Default)]
+  struct Shared(Arc<Mutex<Vec<u8>>>);
+
+  impl Write for Shared {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+      self.0.lock().unwrap().extend_from_slice(buf);
+      Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+      Ok(())
+    }
+  }
+
+  impl Shared {
+    /// The recorded lines, without the timestamps
+    fn lines(&self) -> Vec<String> {
+      let data = self.0.lock().unwrap();
+      String::from_utf8(data.clone())
+        .unwrap()
+        .lines()
+        .map(|line| {
+          let rest = line.find(",\"direction\"").unwrap();
+          format!("{{{}", &line[rest + 1..])
+        })
+        .collect()
+    }
+  }
+
+  #[test]
+  fn test_values() {
+    let val = Value::from(vec![
+      Value::Nil,
+      Value::from(true),
+      Value::from(-3),
+      Value::from(1.5),
+      Value::from(1.0),
+      Value::from("a \"quote\"\n"),
+      Value::Binary(vec![0, 255]),
+      Value::from(vec![(Value::from("key"), Value::from(1))]),
+      Value::from(vec![(Value::from(1), Value::from(2))]),
+      Value::Ext(0, vec![5]),
+      Value::Ext(2, vec![0xcd, 0x01, 0x00]),
+      Value::Ext(7, vec![1]),
+    ]);
+    let out = to_json(&val).to_string();
+
+    assert_eq!(
+      "[null,true,-3,1.5,1.0,\"a \\\"quote\\\"\\n\",{\"binary\":\"00ff\"},\
+       {\"key\":1},[[1,2]],{\"buffer\":5},{\"tabpage\":256},\
+       {\"ext\":7,\"data\":\"01\"}]",
+      out
+    );
+  }
+
+  #[test]
+  fn test_recording_writer() {
+    let sink = Shared::default();
+    let recorder = Recorder::new(sink.clone());
+    let mut writer = recorder.writer(futures::io::Cursor::new(Vec::new()));
+
+    let mut data = Vec::new();
+    let messages = vec![
+      RpcMessage::RpcRequest {
+        msgid: 1,
+        method: "nvim_get_current_buf".to_owned(),
+        params: vec![],
+      },
+      RpcMessage::RpcNotification {
+        method: "nvim_input".to_owned(),
+        params: vec![Value::from("<Esc>")],
+      },
+    ];
+    for msg in messages {
+      model::encode_sync(&mut data, msg).unwrap();
+    }
+
+    // Messages split over several writes are recorded once they're complete
+    block_on(async {
+      for chunk in data.chunks(5) {
+        writer.write_all(chunk).await.unwrap();
+      }
+    });
+    recorder.flush();
+
+    assert_eq!(
+      vec![
+        "{\"direction\":\"out\",\"type\":\"request\",\"msgid\":1,\
+         \"method\":\"nvim_get_current_buf\",\"params\":[]}",
+        "{\"direction\":\"out\",\"type\":\"notification\",\
+         \"method\":\"nvim_input\",\"params\":[\"<Esc>\"]}",
+      ],
+      sink.lines()
+    );
+  }
+
+  #[test]
+  fn test_record_response() {
+    let sink = Shared::default();
+    let recorder = Recorder::new(sink.clone());
+
+    recorder.record(
+      Direction::Incoming,
+      &RpcMessage::RpcResponse {
+        msgid: 1,
+        error: Value::Nil,
+        result: Value::Ext(0, vec![1]),
+      },
+    );
+    recorder.flush();
+
+    assert_eq!(
+      vec![
+        "{\"direction\":\"in\",\"type\":\"response\",\"msgid\":1,\
+         \"error\":null,\"result\":{\"buffer\":1}}"
+      ],
+      sink.lines()
+    );
+  }
+
+  #[cfg(feature = "use_tokio")]
+  #[tokio::test]
+  async fn test_tokio_constructor() {
+    use tokio::{io::split, net::TcpListener};
+    use tokio_util::compat::{
+      TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
+    };
+
+    use crate::{
+      create::tokio::new_tcp_with_options, rpc::handler::Dummy,
+      testing::fake::FakeNeovim, SessionOptions,
+    };
+
+    let fake = FakeNeovim::new();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+      let (stream, _) = listener.accept().await.unwrap();
+      let (reader, writer) = split(stream);
+      let _ = fake.serve(reader.compat(), writer.compat_write()).await;
+    });
+
+    let sink = Shared::default();
+    let recorder = Recorder::new(sink.clone());
+    let mut options = SessionOptions::new();
+    options.set_recorder(recorder.clone());
+    let (nvim, _io) = new_tcp_with_options(addr, Dummy::new(), &options)
+      .await
+      .unwrap();
+    nvim.set_var("answer", Value::from(42)).await.unwrap();
+    recorder.flush();
+
+    let lines = sink.lines();
+    assert_eq!(2, lines.len());
+    assert!(lines[0].starts_with("{\"direction\":\"out\",\"type\":\"request\""));
+    assert!(lines[0].contains("\"method\":\"nvim_set_var\""));
+    assert!(lines[1].starts_with("{\"direction\":\"in\",\"type\":\"response\""));
+  }
+
+  #[test]
+  fn test_read_transcript() {
+    let transcript = concat!(
+      "{\"time\":1.5,\"direction\":\"out\",\"type\":\"request\",",
+      "\"msgid\":2,\"method\":\"nvim_buf_set_lines\",\"params\":",
+      "[{\"buffer\":5},-1,1e3,2.0,false,[\"a\\\"\\u00e4\\n\"]]}\n",
+      "\n",
+      "{\"time\":1.6,\"direction\":\"in\",\"type\":\"notification\",",
+      "\"method\":\"redraw\",\"params\":[{\"binary\":\"00ff\"},",
+      "{\"key\":null},[[1,2]],{\"ext\":7,\"data\":\"01\"}]}\n",
+    );
+
+    let entries = read_transcript(transcript.as_bytes()).unwrap();
+    assert_eq!(
+      vec![
+        (
+          Direction::Outgoing,
+          RpcMessage::RpcRequest {
+            msgid: 2,
+            method: "nvim_buf_set_lines".to_owned(),
+            params: vec![
+              Value::Ext(0, vec![5]),
+              Value::from(-1),
+              Value::from(1000.0),
+              Value::from(2.0),
+              Value::from(false),
+              Value::from(vec![Value::from("a\"\u{e4}\n")]),
+            ],
+          }
+        ),
+        (
+          Direction::Incoming,
+          RpcMessage::RpcNotification {
+            method: "redraw".to_owned(),
+            params: vec![
+              Value::Binary(vec![0, 255]),
+              Value::from(vec![(Value::from("key"), Value::Nil)]),
+              Value::from(vec![Value::from(vec![
+                Value::from(1),
+                Value::from(2)
+              ])]),
+              Value::Ext(7, vec![1]),
+            ],
+          }
+        ),
+      ],
+      entries
+    );
+
+    let err = read_transcript("{\"time\":1}\n[".as_bytes()).unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidData, err.kind());
+  }
+}
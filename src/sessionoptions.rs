@@ -0,0 +1,165 @@
+//! Options for a [`neovim`](crate::neovim::Neovim) session
+//!
+//! This should be used with
+//! [`Neovim::new_with_options`](crate::neovim::Neovim::new_with_options)
+use std::{num::NonZeroUsize, time::Duration};
+
+#[cfg(feature = "recorder")]
+use crate::rpc::recorder::Recorder;
+
+/// What to do with a message from neovim if one of the limits set in
+/// [`SessionOptions`](crate::sessionoptions::SessionOptions) has been hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+  /// Wait until there's room again. While waiting, no more messages are read
+  /// from neovim.
+  #[default]
+  Wait,
+  /// Drop notifications, and wait with requests. Dropped notifications are
+  /// logged.
+  DropNotifications,
+  /// Drop notifications, and answer requests with an error response. This way,
+  /// reading from neovim never blocks.
+  Reject,
+}
+
+/// Configuration of the io loop of a session. The defaults match the behavior
+/// of [`Neovim::new`](crate::neovim::Neovim::new).
+#[derive(Debug, Clone, Default)]
+pub struct SessionOptions {
+  pub(crate) coalesce_writes: bool,
+  pub(crate) channel_capacity: Option<NonZeroUsize>,
+  pub(crate) max_concurrent_requests: Option<NonZeroUsize>,
+  pub(crate) overflow_policy: OverflowPolicy,
+  pub(crate) max_message_size: Option<usize>,
+  #[cfg(feature = "recorder")]
+  pub(crate) recorder: Option<Recorder>,
+  pub(crate) metrics: bool,
+}
+
+impl SessionOptions {
+  #[must_use]
+  pub fn new() -> SessionOptions {
+    SessionOptions::default()
+  }
+
+  /// Write all messages through a dedicated writer task. Messages queued while
+  /// the task is writing are combined into a single write and flush, which
+  /// reduces lock contention and syscalls under heavy concurrent use.
+  pub fn set_coalesce_writes(&mut self, val: bool) -> &mut Self {
+    self.coalesce_writes = val;
+    self
+  }
+
+  /// Limit the number of messages that have been read from neovim, but not yet
+  /// been taken up by the handler. By default, this is unbounded.
+  pub fn set_channel_capacity(&mut self, val: NonZeroUsize) -> &mut Self {
+    self.channel_capacity = Some(val);
+    self
+  }
+
+  /// Limit the number of concurrently running
+  /// [`handle_request`](crate::rpc::handler::Handler::handle_request) tasks.
+  /// By default, this is unbounded.
+  pub fn set_max_concurrent_requests(
+    &mut self,
+    val: NonZeroUsize,
+  ) -> &mut Self {
+    self.max_concurrent_requests = Some(val);
+    self
+  }
+
+  /// Set what happens when one of the limits above has been hit. The default
+  /// is [`OverflowPolicy::Wait`](crate::sessionoptions::OverflowPolicy::Wait).
+  pub fn set_overflow_policy(&mut self, val: OverflowPolicy) -> &mut Self {
+    self.overflow_policy = val;
+    self
+  }
+
+  /// Limit the size of a single message from neovim. A larger message ends
+  /// the session with
+  /// [`InvalidMessage::TooLarge`](crate::error::InvalidMessage::TooLarge). The
+  /// default is
+  /// [`DEFAULT_MAX_MESSAGE_SIZE`](crate::rpc::model::DEFAULT_MAX_MESSAGE_SIZE).
+  pub fn set_max_message_size(&mut self, val: usize) -> &mut Self {
+    self.max_message_size = Some(val);
+    self
+  }
+
+  /// Record all messages of the session, see
+  /// [`recorder`](crate::rpc::recorder). By default, no messages are
+  /// recorded.
+  ///
+  /// Only available with the `recorder` feature.
+  #[cfg(feature = "recorder")]
+  pub fn set_recorder(&mut self, val: Recorder) -> &mut Self {
+    self.recorder = Some(val);
+    self
+  }
+
+  /// Collect metrics about the session, see [`metrics`](crate::rpc::metrics).
+  /// By default, no metrics are collected.
+  pub fn set_metrics(&mut self, val: bool) -> &mut Self {
+    self.metrics = val;
+    self
+  }
+}
+
+/// How to retry connecting after neovim disconnected, see
+/// [`Neovim::new_reconnecting`](crate::neovim::Neovim::new_reconnecting).
+///
+/// The first attempt is made after the initial delay. The delay doubles with
+/// each failed attempt, up to the maximum delay. By default, it starts at 100
+/// milliseconds, goes up to 5 seconds, and attempts are made indefinitely.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+  pub(crate) initial_delay: Duration,
+  pub(crate) max_delay: Duration,
+  pub(crate) max_attempts: Option<u32>,
+}
+
+impl Default for Backoff {
+  fn default() -> Self {
+    Backoff {
+      initial_delay: Duration::from_millis(100),
+      max_delay: Duration::from_secs(5),
+      max_attempts: None,
+    }
+  }
+}
+
+impl Backoff {
+  #[must_use]
+  pub fn new() -> Backoff {
+    Backoff::default()
+  }
+
+  /// Set the delay before the first attempt.
+  pub fn set_initial_delay(&mut self, val: Duration) -> &mut Self {
+    self.initial_delay = val;
+    self
+  }
+
+  /// Set the maximum delay between two attempts.
+  pub fn set_max_delay(&mut self, val: Duration) -> &mut Self {
+    self.max_delay = val;
+    self
+  }
+
+  /// Give up after `val` failed attempts. The session then ends as if
+  /// reconnecting was not enabled.
+  pub fn set_max_attempts(&mut self, val: u32) -> &mut Self {
+    self.max_attempts = Some(val);
+    self
+  }
+
+  /// The delays before each attempt.
+  pub(crate) fn delays(&self) -> impl Iterator<Item = Duration> + use<> {
+    let max_delay = self.max_delay;
+    let delays = std::iter::successors(Some(self.initial_delay), move |d| {
+      Some(d.saturating_mul(2).min(max_delay))
+    });
+
+    delays.take(self.max_attempts.map_or(usize::MAX, |n| n as usize))
+  }
+}
@@ -0,0 +1,1391 @@
+//! An in-memory stand-in for neovim.
+//!
+//! A [`FakeNeovim`] keeps a small model of neovim's state, and answers
+//! requests for a subset of the API by changing or reading that state:
+//!
+//!   * Buffers with lines, names and a changedtick, see
+//!     `nvim_buf_get_lines`, `nvim_buf_set_lines`, `nvim_buf_set_text` and
+//!     friends. Buffers can be attached to via `nvim_buf_attach`, and send
+//!     `nvim_buf_lines_event` notifications for all changes.
+//!   * Windows showing a buffer, with a cursor.
+//!   * Tabpages containing windows.
+//!   * Global, buffer, window and tabpage variables.
+//!
+//! All other requests are answered with an error, like neovim does for
+//! unknown methods, and notifications are ignored. See
+//! [`FakeNeovim::METHODS`] for the full list.
+//!
+//! Any number of clients can connect to the same `FakeNeovim`, each on its
+//! own channel. The state can be set up and inspected directly through the
+//! `FakeNeovim`, without going through a client.
+//!
+//! Like the rest of [`testing`](crate::testing), this needs the `testing`
+//! feature, so it's not compiled into plugins that only use it in their tests.
+//!
+//! ```
+//! # #[cfg(feature = "use_tokio")]
+//! # #[tokio::main]
+//! # async fn main() {
+//! use nvim_rs::{rpc::handler::Dummy, testing::fake::FakeNeovim, Neovim};
+//!
+//! let fake = FakeNeovim::new();
+//! let (reader, writer, serve) = fake.start();
+//! let _serve = tokio::spawn(serve);
+//!
+//! let (nvim, io) = Neovim::new(reader, writer, Dummy::new());
+//! let _io = tokio::spawn(io);
+//!
+//! let buf = nvim.get_current_buf().await.unwrap();
+//! buf
+//!   .set_lines(0, -1, true, vec!["Hello".into(), "World".into()])
+//!   .await
+//!   .unwrap();
+//!
+//! assert_eq!(
+//!   fake.lines(fake.current_buffer()).unwrap(),
+//!   vec!["Hello", "World"]
+//! );
+//! # }
+//! # #[cfg(not(feature = "use_tokio"))]
+//! # fn main() {}
+//! ```
+use std::{
+  collections::{BTreeMap, HashMap},
+  future::Future,
+  io,
+  sync::{Arc, Mutex},
+};
+
+use futures::{
+  channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+  future::{self, Either},
+  io::{AsyncRead, AsyncWrite, AsyncWriteExt},
+  StreamExt,
+};
+
+use crate::{
+  error::{DecodeError, ScriptError},
+  rpc::model::{Decoder, RpcMessage},
+  testing::{duplex, send, PipeReader, PipeWriter},
+  Value,
+};
+
+/// Neovim's error type for general errors
+const EXCEPTION: i64 = 0;
+/// Neovim's error type for invalid arguments
+const VALIDATION: i64 = 1;
+
+/// The ext type of buffers
+const BUFFER: i8 = 0;
+/// The ext type of windows
+const WINDOW: i8 = 1;
+/// The ext type of tabpages
+const TABPAGE: i8 = 2;
+
+/// The handle of the first window. Neovim uses window ids starting at 1000,
+/// so they can be told apart from window numbers.
+const FIRST_WINDOW: i64 = 1000;
+
+type RequestResult = Result<Value, Value>;
+
+fn error(typ: i64, msg: impl Into<String>) -> Value {
+  Value::from(vec![Value::from(typ), Value::from(msg.into())])
+}
+
+fn handle_value(typ: i8, handle: i64) -> Value {
+  let mut data = Vec::new();
+  rmpv::encode::write_value(&mut data, &Value::from(handle))
+    .expect("Writing to a Vec can't fail");
+  Value::Ext(typ, data)
+}
+
+#[derive(Debug)]
+struct BufferState {
+  name: String,
+  lines: Vec<String>,
+  changedtick: i64,
+  vars: HashMap<String, Value>,
+  /// The channels attached to this buffer
+  attached: Vec<u64>,
+}
+
+impl BufferState {
+  fn new() -> Self {
+    BufferState {
+      name: String::new(),
+      lines: vec![String::new()],
+      changedtick: 1,
+      vars: HashMap::new(),
+      attached: Vec::new(),
+    }
+  }
+}
+
+#[derive(Debug)]
+struct WindowState {
+  buffer: i64,
+  tabpage: i64,
+  /// The cursor as (row, col), with a 1-based row and a 0-based byte column
+  cursor: (i64, i64),
+  vars: HashMap<String, Value>,
+}
+
+#[derive(Debug)]
+struct TabpageState {
+  windows: Vec<i64>,
+  current_window: i64,
+  vars: HashMap<String, Value>,
+}
+
+#[derive(Debug)]
+struct State {
+  buffers: BTreeMap<i64, BufferState>,
+  windows: BTreeMap<i64, WindowState>,
+  tabpages: BTreeMap<i64, TabpageState>,
+  current_tabpage: i64,
+  vars: HashMap<String, Value>,
+  next_buffer: i64,
+  next_window: i64,
+  next_tabpage: i64,
+  next_channel: u64,
+  /// The senders for notifications to the connected channels
+  channels: HashMap<u64, UnboundedSender<RpcMessage>>,
+}
+
+impl Default for State {
+  fn default() -> Self {
+    let mut state = State {
+      buffers: BTreeMap::new(),
+      windows: BTreeMap::new(),
+      tabpages: BTreeMap::new(),
+      current_tabpage: 1,
+      vars: HashMap::new(),
+      next_buffer: 1,
+      next_window: FIRST_WINDOW,
+      next_tabpage: 1,
+      next_channel: 1,
+      channels: HashMap::new(),
+    };
+
+    let buffer = state.create_buffer();
+    state.current_tabpage = state.create_tabpage(buffer).0;
+    state
+  }
+}
+
+impl State {
+  fn create_buffer(&mut self) -> i64 {
+    let buffer = self.next_buffer;
+    self.next_buffer += 1;
+    self.buffers.insert(buffer, BufferState::new());
+    buffer
+  }
+
+  fn create_window(&mut self, buffer: i64, tabpage: i64) -> i64 {
+    let window = self.next_window;
+    self.next_window += 1;
+    self.windows.insert(
+      window,
+      WindowState {
+        buffer,
+        tabpage,
+        cursor: (1, 0),
+        vars: HashMap::new(),
+      },
+    );
+    window
+  }
+
+  fn create_tabpage(&mut self, buffer: i64) -> (i64, i64) {
+    let tabpage = self.next_tabpage;
+    self.next_tabpage += 1;
+    let window = self.create_window(buffer, tabpage);
+    self.tabpages.insert(
+      tabpage,
+      TabpageState {
+        windows: vec![window],
+        current_window: window,
+        vars: HashMap::new(),
+      },
+    );
+    (tabpage, window)
+  }
+
+  fn current_window(&self) -> i64 {
+    self.tabpages[&self.current_tabpage].current_window
+  }
+
+  fn current_buffer(&self) -> i64 {
+    self.windows[&self.current_window()].buffer
+  }
+
+  fn set_current_window(&mut self, window: i64) {
+    let tabpage = self.windows[&window].tabpage;
+    self.current_tabpage = tabpage;
+    self
+      .tabpages
+      .get_mut(&tabpage)
+      .expect("Windows belong to existing tabpages")
+      .current_window = window;
+  }
+
+  /// Replace the lines `start..end` of `buffer`, and notify the attached
+  /// channels. The range needs to be valid.
+  fn replace_lines(
+    &mut self,
+    buffer: i64,
+    start: usize,
+    end: usize,
+    replacement: Vec<String>,
+  ) {
+    let buf = self.buffers.get_mut(&buffer).expect("Buffer was checked");
+    let linedata = replacement.iter().cloned().map(Value::from).collect();
+    buf.lines.splice(start..end, replacement);
+    if buf.lines.is_empty() {
+      buf.lines.push(String::new());
+    }
+    buf.changedtick += 1;
+
+    let line_count = buf.lines.len() as i64;
+    for win in self.windows.values_mut() {
+      if win.buffer == buffer && win.cursor.0 > line_count {
+        win.cursor.0 = line_count;
+      }
+    }
+
+    let event = RpcMessage::RpcNotification {
+      method: "nvim_buf_lines_event".to_owned(),
+      params: vec![
+        handle_value(BUFFER, buffer),
+        Value::from(buf.changedtick),
+        Value::from(start),
+        Value::from(end),
+        Value::Array(linedata),
+        Value::from(false),
+      ],
+    };
+    self.notify_attached(buffer, &event);
+  }
+
+  /// Send `event` to all channels attached to `buffer`. Channels that are
+  /// gone are detached.
+  fn notify_attached(&mut self, buffer: i64, event: &RpcMessage) {
+    let channels = &mut self.channels;
+    if let Some(buf) = self.buffers.get_mut(&buffer) {
+      buf.attached.retain(|channel| {
+        channels
+          .get(channel)
+          .is_some_and(|sender| sender.unbounded_send(event.clone()).is_ok())
+      });
+    }
+  }
+}
+
+/// The arguments of a request, with accessors that produce neovim's errors.
+struct Args<'a> {
+  method: &'a str,
+  params: Vec<Value>,
+}
+
+impl Args<'_> {
+  fn expect_len(&self, len: usize) -> Result<(), Value> {
+    if self.params.len() == len {
+      Ok(())
+    } else {
+      Err(error(
+        VALIDATION,
+        format!(
+          "Wrong number of arguments: expecting {} but got {}",
+          len,
+          self.params.len()
+        ),
+      ))
+    }
+  }
+
+  fn get(&self, i: usize) -> Result<&Value, Value> {
+    self.params.get(i).ok_or_else(|| {
+      error(
+        VALIDATION,
+        format!("Wrong number of arguments for {}", self.method),
+      )
+    })
+  }
+
+  fn invalid(&self, i: usize, expected: &str) -> Value {
+    error(
+      VALIDATION,
+      format!(
+        "Wrong type for argument {} when calling {}, expecting {}",
+        i + 1,
+        self.method,
+        expected
+      ),
+    )
+  }
+
+  fn int(&self, i: usize) -> Result<i64, Value> {
+    self
+      .get(i)?
+      .as_i64()
+      .ok_or_else(|| self.invalid(i, "Integer"))
+  }
+
+  fn bool(&self, i: usize) -> Result<bool, Value> {
+    self
+      .get(i)?
+      .as_bool()
+      .ok_or_else(|| self.invalid(i, "Boolean"))
+  }
+
+  fn string(&self, i: usize) -> Result<String, Value> {
+    self
+      .get(i)?
+      .as_str()
+      .map(str::to_owned)
+      .ok_or_else(|| self.invalid(i, "String"))
+  }
+
+  fn value(&self, i: usize) -> Result<Value, Value> {
+    self.get(i).cloned()
+  }
+
+  fn lines(&self, i: usize) -> Result<Vec<String>, Value> {
+    let Value::Array(arr) = self.get(i)? else {
+      return Err(self.invalid(i, "Array"));
+    };
+    arr
+      .iter()
+      .map(|line| {
+        line
+          .as_str()
+          .map(str::to_owned)
+          .ok_or_else(|| self.invalid(i, "Array of Strings"))
+      })
+      .collect()
+  }
+
+  /// A handle of ext type `typ`, also accepting integers like neovim does
+  fn handle(&self, i: usize, typ: i8, expected: &str) -> Result<i64, Value> {
+    match self.get(i)? {
+      Value::Ext(t, data) if *t == typ => {
+        rmpv::decode::read_value(&mut data.as_slice())
+          .ok()
+          .and_then(|val| val.as_i64())
+          .ok_or_else(|| self.invalid(i, expected))
+      }
+      val => val.as_i64().ok_or_else(|| self.invalid(i, expected)),
+    }
+  }
+
+  fn buffer(&self, i: usize, state: &State) -> Result<i64, Value> {
+    let buffer = match self.handle(i, BUFFER, "Buffer")? {
+      0 => state.current_buffer(),
+      buffer => buffer,
+    };
+    if state.buffers.contains_key(&buffer) {
+      Ok(buffer)
+    } else {
+      Err(error(VALIDATION, format!("Invalid buffer id: {buffer}")))
+    }
+  }
+
+  fn window(&self, i: usize, state: &State) -> Result<i64, Value> {
+    let window = match self.handle(i, WINDOW, "Window")? {
+      0 => state.current_window(),
+      window => window,
+    };
+    if state.windows.contains_key(&window) {
+      Ok(window)
+    } else {
+      Err(error(VALIDATION, format!("Invalid window id: {window}")))
+    }
+  }
+
+  fn tabpage(&self, i: usize, state: &State) -> Result<i64, Value> {
+    let tabpage = match self.handle(i, TABPAGE, "Tabpage")? {
+      0 => state.current_tabpage,
+      tabpage => tabpage,
+    };
+    if state.tabpages.contains_key(&tabpage) {
+      Ok(tabpage)
+    } else {
+      Err(error(VALIDATION, format!("Invalid tabpage id: {tabpage}")))
+    }
+  }
+}
+
+/// Convert a line index as used by `nvim_buf_get_lines` to an index into the
+/// lines. Negative indices count from the end, -1 being the index after the
+/// last line.
+fn line_index(index: i64, len: usize, strict: bool) -> Result<usize, Value> {
+  let len = len as i64;
+  let index = if index < 0 { len + index + 1 } else { index };
+
+  if (0..=len).contains(&index) {
+    Ok(index as usize)
+  } else if strict {
+    Err(error(VALIDATION, "Index out of bounds"))
+  } else {
+    Ok(index.clamp(0, len) as usize)
+  }
+}
+
+/// Convert a row as used by `nvim_buf_set_text` to an index into the lines.
+/// Negative rows count from the end, -1 being the last line.
+fn row_index(row: i64, len: usize) -> Result<usize, Value> {
+  let len = len as i64;
+  let row = if row < 0 { len + row } else { row };
+
+  if (0..len).contains(&row) {
+    Ok(row as usize)
+  } else {
+    Err(error(VALIDATION, "Index out of bounds"))
+  }
+}
+
+fn col_index(line: &str, col: i64, name: &str) -> Result<usize, Value> {
+  usize::try_from(col)
+    .ok()
+    .filter(|&col| line.is_char_boundary(col))
+    .ok_or_else(|| error(VALIDATION, format!("'{name}' is out of bounds")))
+}
+
+fn get_var(vars: &HashMap<String, Value>, name: &str) -> RequestResult {
+  vars
+    .get(name)
+    .cloned()
+    .ok_or_else(|| error(VALIDATION, format!("Key not found: {name}")))
+}
+
+fn del_var(vars: &mut HashMap<String, Value>, name: &str) -> RequestResult {
+  vars
+    .remove(name)
+    .map(|_| Value::Nil)
+    .ok_or_else(|| error(VALIDATION, format!("Key not found: {name}")))
+}
+
+/// An in-memory stand-in for neovim, see the [module docs](self).
+///
+/// Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct FakeNeovim {
+  state: Arc<Mutex<State>>,
+}
+
+impl FakeNeovim {
+  /// The API methods a `FakeNeovim` implements.
+  pub const METHODS: &'static [&'static str] = &[
+    "nvim_get_api_info",
+    "nvim_get_var",
+    "nvim_set_var",
+    "nvim_del_var",
+    "nvim_list_bufs",
+    "nvim_get_current_buf",
+    "nvim_set_current_buf",
+    "nvim_create_buf",
+    "nvim_get_current_line",
+    "nvim_set_current_line",
+    "nvim_del_current_line",
+    "nvim_buf_is_valid",
+    "nvim_buf_line_count",
+    "nvim_buf_get_lines",
+    "nvim_buf_set_lines",
+    "nvim_buf_set_text",
+    "nvim_buf_get_name",
+    "nvim_buf_set_name",
+    "nvim_buf_get_changedtick",
+    "nvim_buf_get_var",
+    "nvim_buf_set_var",
+    "nvim_buf_del_var",
+    "nvim_buf_attach",
+    "nvim_buf_detach",
+    "nvim_list_wins",
+    "nvim_get_current_win",
+    "nvim_set_current_win",
+    "nvim_open_win",
+    "nvim_win_close",
+    "nvim_win_is_valid",
+    "nvim_win_get_buf",
+    "nvim_win_set_buf",
+    "nvim_win_get_cursor",
+    "nvim_win_set_cursor",
+    "nvim_win_get_tabpage",
+    "nvim_win_get_number",
+    "nvim_win_get_var",
+    "nvim_win_set_var",
+    "nvim_win_del_var",
+    "nvim_list_tabpages",
+    "nvim_get_current_tabpage",
+    "nvim_set_current_tabpage",
+    "nvim_tabpage_is_valid",
+    "nvim_tabpage_list_wins",
+    "nvim_tabpage_get_win",
+    "nvim_tabpage_get_number",
+    "nvim_tabpage_get_var",
+    "nvim_tabpage_set_var",
+    "nvim_tabpage_del_var",
+  ];
+
+  /// Create a fake neovim with a single tabpage, containing a single window
+  /// showing an empty buffer.
+  #[must_use]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn state(&self) -> std::sync::MutexGuard<'_, State> {
+    self.state.lock().unwrap_or_else(|e| e.into_inner())
+  }
+
+  /// The handle of the current buffer.
+  #[must_use]
+  pub fn current_buffer(&self) -> i64 {
+    self.state().current_buffer()
+  }
+
+  /// The handle of the current window.
+  #[must_use]
+  pub fn current_window(&self) -> i64 {
+    self.state().current_window()
+  }
+
+  /// The handle of the current tabpage.
+  #[must_use]
+  pub fn current_tabpage(&self) -> i64 {
+    self.state().current_tabpage
+  }
+
+  /// Create a buffer containing `lines`, and return its handle.
+  pub fn create_buffer(&self, lines: &[&str]) -> i64 {
+    let mut state = self.state();
+    let buffer = state.create_buffer();
+    if !lines.is_empty() {
+      state
+        .buffers
+        .get_mut(&buffer)
+        .expect("Was just created")
+        .lines = lines.iter().map(|&l| l.to_owned()).collect();
+    }
+    buffer
+  }
+
+  /// Open a new tabpage with a window showing `buffer`, and make it the
+  /// current one. Returns the handles of the tabpage and the window, or
+  /// `None` if `buffer` doesn't exist.
+  pub fn create_tabpage(&self, buffer: i64) -> Option<(i64, i64)> {
+    let mut state = self.state();
+    if !state.buffers.contains_key(&buffer) {
+      return None;
+    }
+    let (tabpage, window) = state.create_tabpage(buffer);
+    state.current_tabpage = tabpage;
+    Some((tabpage, window))
+  }
+
+  /// The lines of `buffer`, or `None` if it doesn't exist.
+  #[must_use]
+  pub fn lines(&self, buffer: i64) -> Option<Vec<String>> {
+    self.state().buffers.get(&buffer).map(|b| b.lines.clone())
+  }
+
+  /// Replace all lines of `buffer`, notifying attached channels. Returns
+  /// `false` if `buffer` doesn't exist.
+  pub fn set_lines(&self, buffer: i64, lines: &[&str]) -> bool {
+    let mut state = self.state();
+    let Some(len) = state.buffers.get(&buffer).map(|b| b.lines.len()) else {
+      return false;
+    };
+    let lines = lines.iter().map(|&l| l.to_owned()).collect();
+    state.replace_lines(buffer, 0, len, lines);
+    true
+  }
+
+  /// The cursor of `window` as (row, col), with a 1-based row and a 0-based
+  /// byte column, or `None` if the window doesn't exist.
+  #[must_use]
+  pub fn cursor(&self, window: i64) -> Option<(i64, i64)> {
+    self.state().windows.get(&window).map(|w| w.cursor)
+  }
+
+  /// The global variable `name`.
+  #[must_use]
+  pub fn var(&self, name: &str) -> Option<Value> {
+    self.state().vars.get(name).cloned()
+  }
+
+  /// Set the global variable `name`.
+  pub fn set_var(&self, name: &str, value: Value) {
+    self.state().vars.insert(name.to_owned(), value);
+  }
+
+  /// Create an in-memory connection to a new channel, and return the
+  /// client's ends of it together with the future [serving](Self::serve) the
+  /// channel on the other ends.
+  pub fn start(
+    &self,
+  ) -> (
+    PipeReader,
+    PipeWriter,
+    impl Future<Output = Result<(), Box<ScriptError>>> + use<>,
+  ) {
+    let ((reader, writer), (their_reader, their_writer)) = duplex();
+
+    (
+      reader,
+      writer,
+      self.clone().serve(their_reader, their_writer),
+    )
+  }
+
+  /// Serve a new channel, reading requests from `reader` and writing
+  /// responses and notifications to `writer`. Returns `Ok(())` when the
+  /// client disconnects, and closes the writer.
+  ///
+  /// Only [`ReadError`](crate::error::ScriptError::ReadError) and
+  /// [`WriteError`](crate::error::ScriptError::WriteError) are returned.
+  pub async fn serve<R, W>(
+    self,
+    mut reader: R,
+    mut writer: W,
+  ) -> Result<(), Box<ScriptError>>
+  where
+    R: AsyncRead + Send + Unpin + 'static,
+    W: AsyncWrite + Send + Unpin + 'static,
+  {
+    let (sender, mut events) = unbounded();
+    let channel = {
+      let mut state = self.state();
+      let channel = state.next_channel;
+      state.next_channel += 1;
+      state.channels.insert(channel, sender);
+      channel
+    };
+
+    let res = self
+      .serve_channel(channel, &mut reader, &mut writer, &mut events)
+      .await;
+    self.state().channels.remove(&channel);
+    res?;
+
+    writer
+      .close()
+      .await
+      .map_err(|e| Box::new(ScriptError::WriteError(*Box::from(e))))
+  }
+
+  async fn serve_channel<R, W>(
+    &self,
+    channel: u64,
+    reader: &mut R,
+    writer: &mut W,
+    events: &mut UnboundedReceiver<RpcMessage>,
+  ) -> Result<(), Box<ScriptError>>
+  where
+    R: AsyncRead + Send + Unpin + 'static,
+    W: AsyncWrite + Send + Unpin + 'static,
+  {
+    let mut decoder = Decoder::new();
+
+    loop {
+      let next = {
+        let decode = decoder.decode(reader);
+        futures::pin_mut!(decode);
+        match future::select(decode, events.next()).await {
+          Either::Left((msg, _)) => Either::Left(msg),
+          Either::Right((event, _)) => Either::Right(event),
+        }
+      };
+
+      let msg = match next {
+        Either::Left(Ok(msg)) => msg,
+        Either::Left(Err(err)) => {
+          return match *err {
+            DecodeError::ReaderError(ref e)
+              if e.kind() == io::ErrorKind::UnexpectedEof =>
+            {
+              Ok(())
+            }
+            err => Err(Box::new(ScriptError::ReadError(err))),
+          };
+        }
+        Either::Right(Some(event)) => {
+          send(writer, event).await?;
+          continue;
+        }
+        Either::Right(None) => unreachable!("The state holds a sender"),
+      };
+
+      match msg {
+        RpcMessage::RpcRequest {
+          msgid,
+          method,
+          params,
+        } => {
+          let response = self.handle_request(channel, &method, params);
+          // Like neovim, send notifications caused by the request first
+          while let Ok(event) = events.try_recv() {
+            send(writer, event).await?;
+          }
+          let (error, result) = match response {
+            Ok(result) => (Value::Nil, result),
+            Err(error) => (error, Value::Nil),
+          };
+          send(
+            writer,
+            RpcMessage::RpcResponse {
+              msgid,
+              error,
+              result,
+            },
+          )
+          .await?;
+        }
+        RpcMessage::RpcNotification { method, .. } => {
+          debug!("Fake neovim ignoring notification '{method}'");
+        }
+        RpcMessage::RpcResponse { msgid, .. } => {
+          debug!("Fake neovim ignoring response to unknown request {msgid}");
+        }
+      }
+    }
+  }
+
+  fn handle_request(
+    &self,
+    channel: u64,
+    method: &str,
+    params: Vec<Value>,
+  ) -> RequestResult {
+    let args = Args { method, params };
+    let mut state = self.state();
+    let state = &mut *state;
+
+    match method {
+      "nvim_get_api_info" => {
+        args.expect_len(0)?;
+        Ok(Value::from(vec![Value::from(channel), Value::Map(vec![])]))
+      }
+      "nvim_get_var" => {
+        args.expect_len(1)?;
+        get_var(&state.vars, &args.string(0)?)
+      }
+      "nvim_set_var" => {
+        args.expect_len(2)?;
+        state.vars.insert(args.string(0)?, args.value(1)?);
+        Ok(Value::Nil)
+      }
+      "nvim_del_var" => {
+        args.expect_len(1)?;
+        del_var(&mut state.vars, &args.string(0)?)
+      }
+      "nvim_list_bufs" => {
+        args.expect_len(0)?;
+        Ok(Value::from(
+          state
+            .buffers
+            .keys()
+            .map(|&b| handle_value(BUFFER, b))
+            .collect::<Vec<_>>(),
+        ))
+      }
+      "nvim_get_current_buf" => {
+        args.expect_len(0)?;
+        Ok(handle_value(BUFFER, state.current_buffer()))
+      }
+      "nvim_set_current_buf" => {
+        args.expect_len(1)?;
+        let buffer = args.buffer(0, state)?;
+        let window = state.current_window();
+        set_window_buffer(state, window, buffer);
+        Ok(Value::Nil)
+      }
+      "nvim_create_buf" => {
+        args.expect_len(2)?;
+        args.bool(0)?;
+        args.bool(1)?;
+        Ok(handle_value(BUFFER, state.create_buffer()))
+      }
+      "nvim_get_current_line" => {
+        args.expect_len(0)?;
+        let win = &state.windows[&state.current_window()];
+        let line = &state.buffers[&win.buffer].lines[cursor_line(win)];
+        Ok(Value::from(line.as_str()))
+      }
+      "nvim_set_current_line" => {
+        args.expect_len(1)?;
+        let line = args.string(0)?;
+        let win = &state.windows[&state.current_window()];
+        let (buffer, row) = (win.buffer, cursor_line(win));
+        state.replace_lines(buffer, row, row + 1, vec![line]);
+        Ok(Value::Nil)
+      }
+      "nvim_del_current_line" => {
+        args.expect_len(0)?;
+        let win = &state.windows[&state.current_window()];
+        let (buffer, row) = (win.buffer, cursor_line(win));
+        state.replace_lines(buffer, row, row + 1, vec![]);
+        Ok(Value::Nil)
+      }
+      "nvim_buf_is_valid" => {
+        args.expect_len(1)?;
+        Ok(Value::from(args.buffer(0, state).is_ok()))
+      }
+      "nvim_buf_line_count" => {
+        args.expect_len(1)?;
+        let buffer = args.buffer(0, state)?;
+        Ok(Value::from(state.buffers[&buffer].lines.len()))
+      }
+      "nvim_buf_get_lines" => {
+        args.expect_len(4)?;
+        let buf = &state.buffers[&args.buffer(0, state)?];
+        let strict = args.bool(3)?;
+        let start = line_index(args.int(1)?, buf.lines.len(), strict)?;
+        let end = line_index(args.int(2)?, buf.lines.len(), strict)?;
+        let lines = buf.lines.get(start..end).unwrap_or_default();
+        Ok(Value::from(
+          lines
+            .iter()
+            .map(|l| Value::from(l.as_str()))
+            .collect::<Vec<_>>(),
+        ))
+      }
+      "nvim_buf_set_lines" => {
+        args.expect_len(5)?;
+        let buffer = args.buffer(0, state)?;
+        let len = state.buffers[&buffer].lines.len();
+        let strict = args.bool(3)?;
+        let start = line_index(args.int(1)?, len, strict)?;
+        let end = line_index(args.int(2)?, len, strict)?;
+        if start > end {
+          return Err(error(VALIDATION, "'start' is higher than 'end'"));
+        }
+        let replacement = args.lines(4)?;
+        state.replace_lines(buffer, start, end, replacement);
+        Ok(Value::Nil)
+      }
+      "nvim_buf_set_text" => {
+        args.expect_len(6)?;
+        let buffer = args.buffer(0, state)?;
+        let lines = &state.buffers[&buffer].lines;
+        let start_row = row_index(args.int(1)?, lines.len())?;
+        let end_row = row_index(args.int(3)?, lines.len())?;
+        let start_col =
+          col_index(&lines[start_row], args.int(2)?, "start_col")?;
+        let end_col = col_index(&lines[end_row], args.int(4)?, "end_col")?;
+        if (start_row, start_col) > (end_row, end_col) {
+          return Err(error(VALIDATION, "'start' is higher than 'end'"));
+        }
+
+        let mut replacement = args.lines(5)?;
+        if replacement.is_empty() {
+          replacement.push(String::new());
+        }
+        replacement[0].insert_str(0, &lines[start_row][..start_col]);
+        replacement
+          .last_mut()
+          .expect("Not empty")
+          .push_str(&lines[end_row][end_col..]);
+        state.replace_lines(buffer, start_row, end_row + 1, replacement);
+        Ok(Value::Nil)
+      }
+      "nvim_buf_get_name" => {
+        args.expect_len(1)?;
+        let buffer = args.buffer(0, state)?;
+        Ok(Value::from(state.buffers[&buffer].name.as_str()))
+      }
+      "nvim_buf_set_name" => {
+        args.expect_len(2)?;
+        let buffer = args.buffer(0, state)?;
+        let name = args.string(1)?;
+        state.buffers.get_mut(&buffer).expect("Was checked").name = name;
+        Ok(Value::Nil)
+      }
+      "nvim_buf_get_changedtick" => {
+        args.expect_len(1)?;
+        let buffer = args.buffer(0, state)?;
+        Ok(Value::from(state.buffers[&buffer].changedtick))
+      }
+      "nvim_buf_get_var" => {
+        args.expect_len(2)?;
+        let buffer = args.buffer(0, state)?;
+        get_var(&state.buffers[&buffer].vars, &args.string(1)?)
+      }
+      "nvim_buf_set_var" => {
+        args.expect_len(3)?;
+        let buffer = args.buffer(0, state)?;
+        let buf = state.buffers.get_mut(&buffer).expect("Was checked");
+        buf.vars.insert(args.string(1)?, args.value(2)?);
+        Ok(Value::Nil)
+      }
+      "nvim_buf_del_var" => {
+        args.expect_len(2)?;
+        let buffer = args.buffer(0, state)?;
+        let buf = state.buffers.get_mut(&buffer).expect("Was checked");
+        del_var(&mut buf.vars, &args.string(1)?)
+      }
+      "nvim_buf_attach" => {
+        args.expect_len(3)?;
+        let buffer = args.buffer(0, state)?;
+        let send_buffer = args.bool(1)?;
+        let buf = state.buffers.get_mut(&buffer).expect("Was checked");
+        if !buf.attached.contains(&channel) {
+          buf.attached.push(channel);
+        }
+        if send_buffer {
+          let event = RpcMessage::RpcNotification {
+            method: "nvim_buf_lines_event".to_owned(),
+            params: vec![
+              handle_value(BUFFER, buffer),
+              Value::from(buf.changedtick),
+              Value::from(0),
+              Value::from(-1),
+              Value::from(
+                buf
+                  .lines
+                  .iter()
+                  .map(|l| Value::from(l.as_str()))
+                  .collect::<Vec<_>>(),
+              ),
+              Value::from(false),
+            ],
+          };
+          // Ignoring errors: The channel is only removed after serving it
+          // finished
+          let _ = state.channels[&channel].unbounded_send(event);
+        }
+        Ok(Value::from(true))
+      }
+      "nvim_buf_detach" => {
+        args.expect_len(1)?;
+        let buffer = args.buffer(0, state)?;
+        let buf = state.buffers.get_mut(&buffer).expect("Was checked");
+        let Some(pos) = buf.attached.iter().position(|&c| c == channel) else {
+          return Ok(Value::from(false));
+        };
+        buf.attached.remove(pos);
+        let event = RpcMessage::RpcNotification {
+          method: "nvim_buf_detach_event".to_owned(),
+          params: vec![handle_value(BUFFER, buffer)],
+        };
+        // Ignoring errors: The channel is only removed after serving it
+        // finished
+        let _ = state.channels[&channel].unbounded_send(event);
+        Ok(Value::from(true))
+      }
+      "nvim_list_wins" => {
+        args.expect_len(0)?;
+        Ok(Value::from(
+          state
+            .tabpages
+            .values()
+            .flat_map(|tab| tab.windows.iter())
+            .map(|&w| handle_value(WINDOW, w))
+            .collect::<Vec<_>>(),
+        ))
+      }
+      "nvim_get_current_win" => {
+        args.expect_len(0)?;
+        Ok(handle_value(WINDOW, state.current_window()))
+      }
+      "nvim_set_current_win" => {
+        args.expect_len(1)?;
+        let window = args.window(0, state)?;
+        state.set_current_window(window);
+        Ok(Value::Nil)
+      }
+      "nvim_open_win" => {
+        args.expect_len(3)?;
+        let buffer = args.buffer(0, state)?;
+        let enter = args.bool(1)?;
+        let tabpage = state.current_tabpage;
+        let window = state.create_window(buffer, tabpage);
+        state
+          .tabpages
+          .get_mut(&tabpage)
+          .expect("The current tabpage exists")
+          .windows
+          .push(window);
+        if enter {
+          state.set_current_window(window);
+        }
+        Ok(handle_value(WINDOW, window))
+      }
+      "nvim_win_close" => {
+        args.expect_len(2)?;
+        let window = args.window(0, state)?;
+        args.bool(1)?;
+        close_window(state, window)?;
+        Ok(Value::Nil)
+      }
+      "nvim_win_is_valid" => {
+        args.expect_len(1)?;
+        Ok(Value::from(args.window(0, state).is_ok()))
+      }
+      "nvim_win_get_buf" => {
+        args.expect_len(1)?;
+        let window = args.window(0, state)?;
+        Ok(handle_value(BUFFER, state.windows[&window].buffer))
+      }
+      "nvim_win_set_buf" => {
+        args.expect_len(2)?;
+        let window = args.window(0, state)?;
+        let buffer = args.buffer(1, state)?;
+        set_window_buffer(state, window, buffer);
+        Ok(Value::Nil)
+      }
+      "nvim_win_get_cursor" => {
+        args.expect_len(1)?;
+        let (row, col) = state.windows[&args.window(0, state)?].cursor;
+        Ok(Value::from(vec![Value::from(row), Value::from(col)]))
+      }
+      "nvim_win_set_cursor" => {
+        args.expect_len(2)?;
+        let window = args.window(0, state)?;
+        let pos = match args.get(1)? {
+          Value::Array(pos) if pos.len() == 2 => {
+            pos[0].as_i64().zip(pos[1].as_i64())
+          }
+          _ => None,
+        };
+        let (row, col) =
+          pos.ok_or_else(|| args.invalid(1, "Array of 2 Integers"))?;
+        let win = state.windows.get_mut(&window).expect("Was checked");
+        let line_count = state.buffers[&win.buffer].lines.len() as i64;
+        if row < 1 || row > line_count {
+          return Err(error(VALIDATION, "Cursor position outside buffer"));
+        }
+        win.cursor = (row, col.max(0));
+        Ok(Value::Nil)
+      }
+      "nvim_win_get_tabpage" => {
+        args.expect_len(1)?;
+        let window = args.window(0, state)?;
+        Ok(handle_value(TABPAGE, state.windows[&window].tabpage))
+      }
+      "nvim_win_get_number" => {
+        args.expect_len(1)?;
+        let window = args.window(0, state)?;
+        let tabpage = &state.tabpages[&state.windows[&window].tabpage];
+        let pos = tabpage.windows.iter().position(|&w| w == window);
+        Ok(Value::from(pos.expect("Windows are in their tabpage") + 1))
+      }
+      "nvim_win_get_var" => {
+        args.expect_len(2)?;
+        let window = args.window(0, state)?;
+        get_var(&state.windows[&window].vars, &args.string(1)?)
+      }
+      "nvim_win_set_var" => {
+        args.expect_len(3)?;
+        let window = args.window(0, state)?;
+        let win = state.windows.get_mut(&window).expect("Was checked");
+        win.vars.insert(args.string(1)?, args.value(2)?);
+        Ok(Value::Nil)
+      }
+      "nvim_win_del_var" => {
+        args.expect_len(2)?;
+        let window = args.window(0, state)?;
+        let win = state.windows.get_mut(&window).expect("Was checked");
+        del_var(&mut win.vars, &args.string(1)?)
+      }
+      "nvim_list_tabpages" => {
+        args.expect_len(0)?;
+        Ok(Value::from(
+          state
+            .tabpages
+            .keys()
+            .map(|&t| handle_value(TABPAGE, t))
+            .collect::<Vec<_>>(),
+        ))
+      }
+      "nvim_get_current_tabpage" => {
+        args.expect_len(0)?;
+        Ok(handle_value(TABPAGE, state.current_tabpage))
+      }
+      "nvim_set_current_tabpage" => {
+        args.expect_len(1)?;
+        state.current_tabpage = args.tabpage(0, state)?;
+        Ok(Value::Nil)
+      }
+      "nvim_tabpage_is_valid" => {
+        args.expect_len(1)?;
+        Ok(Value::from(args.tabpage(0, state).is_ok()))
+      }
+      "nvim_tabpage_list_wins" => {
+        args.expect_len(1)?;
+        let tabpage = args.tabpage(0, state)?;
+        Ok(Value::from(
+          state.tabpages[&tabpage]
+            .windows
+            .iter()
+            .map(|&w| handle_value(WINDOW, w))
+            .collect::<Vec<_>>(),
+        ))
+      }
+      "nvim_tabpage_get_win" => {
+        args.expect_len(1)?;
+        let tabpage = args.tabpage(0, state)?;
+        Ok(handle_value(
+          WINDOW,
+          state.tabpages[&tabpage].current_window,
+        ))
+      }
+      "nvim_tabpage_get_number" => {
+        args.expect_len(1)?;
+        let tabpage = args.tabpage(0, state)?;
+        let pos = state.tabpages.keys().position(|&t| t == tabpage);
+        Ok(Value::from(pos.expect("Was checked") + 1))
+      }
+      "nvim_tabpage_get_var" => {
+        args.expect_len(2)?;
+        let tabpage = args.tabpage(0, state)?;
+        get_var(&state.tabpages[&tabpage].vars, &args.string(1)?)
+      }
+      "nvim_tabpage_set_var" => {
+        args.expect_len(3)?;
+        let tabpage = args.tabpage(0, state)?;
+        let tab = state.tabpages.get_mut(&tabpage).expect("Was checked");
+        tab.vars.insert(args.string(1)?, args.value(2)?);
+        Ok(Value::Nil)
+      }
+      "nvim_tabpage_del_var" => {
+        args.expect_len(2)?;
+        let tabpage = args.tabpage(0, state)?;
+        let tab = state.tabpages.get_mut(&tabpage).expect("Was checked");
+        del_var(&mut tab.vars, &args.string(1)?)
+      }
+      _ => Err(error(EXCEPTION, format!("Invalid method: {method}"))),
+    }
+  }
+}
+
+/// The index of the line the cursor of `win` is on.
+fn cursor_line(win: &WindowState) -> usize {
+  (win.cursor.0 - 1) as usize
+}
+
+fn set_window_buffer(state: &mut State, window: i64, buffer: i64) {
+  let win = state.windows.get_mut(&window).expect("Window was checked");
+  if win.buffer != buffer {
+    win.buffer = buffer;
+    win.cursor = (1, 0);
+  }
+}
+
+fn close_window(state: &mut State, window: i64) -> Result<(), Value> {
+  if state.windows.len() == 1 {
+    return Err(error(EXCEPTION, "Cannot close last window"));
+  }
+
+  let tabpage = state.windows.remove(&window).expect("Was checked").tabpage;
+  let tab = state.tabpages.get_mut(&tabpage).expect("Tabpage exists");
+  tab.windows.retain(|&w| w != window);
+
+  if let Some(&first) = tab.windows.first() {
+    if tab.current_window == window {
+      tab.current_window = first;
+    }
+  } else {
+    // Closing the last window of a tabpage closes the tabpage
+    state.tabpages.remove(&tabpage);
+    if state.current_tabpage == tabpage {
+      state.current_tabpage =
+        *state.tabpages.keys().next().expect("Another window exists");
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(all(test, feature = "use_tokio"))]
+mod tests {
+  use super::*;
+
+  use crate::{
+    error::CallError, neovim::Neovim, rpc::handler::Handler, rpc::IntoVal,
+  };
+
+  #[derive(Clone)]
+  struct NotifyHandler {
+    notifications: UnboundedSender<(String, Vec<Value>)>,
+  }
+
+  impl Handler for NotifyHandler {
+    type Writer = PipeWriter;
+
+    async fn handle_notify(
+      &self,
+      name: String,
+      args: Vec<Value>,
+      _neovim: Neovim<Self::Writer>,
+    ) {
+      self.notifications.unbounded_send((name, args)).unwrap();
+    }
+  }
+
+  fn connect(
+    fake: &FakeNeovim,
+  ) -> (Neovim<PipeWriter>, UnboundedReceiver<(String, Vec<Value>)>) {
+    let (reader, writer, serve) = fake.start();
+    tokio::spawn(serve);
+    let (notifications, receiver) = unbounded();
+    let (nvim, io) =
+      Neovim::new(reader, writer, NotifyHandler { notifications });
+    tokio::spawn(io);
+    (nvim, receiver)
+  }
+
+  fn lines(lines: &[&str]) -> Value {
+    Value::from(lines.iter().map(|&l| Value::from(l)).collect::<Vec<_>>())
+  }
+
+  fn is_validation_error(err: &CallError) -> bool {
+    matches!(err, CallError::NeovimError(Some(VALIDATION), _))
+  }
+
+  #[tokio::test]
+  async fn test_lines() {
+    let fake = FakeNeovim::new();
+    let (nvim, _notifications) = connect(&fake);
+    let buf = nvim.get_current_buf().await.unwrap();
+
+    let lines = vec!["one".to_owned(), "two".to_owned(), "three".to_owned()];
+    buf.set_lines(0, -1, true, lines).await.unwrap();
+    assert_eq!(3, buf.line_count().await.unwrap());
+    assert_eq!(
+      vec!["two", "three"],
+      buf.get_lines(1, -1, true).await.unwrap()
+    );
+    assert_eq!(vec!["three"], buf.get_lines(-2, 10, false).await.unwrap());
+    let err = buf.get_lines(0, 10, true).await.unwrap_err();
+    assert!(is_validation_error(&err));
+
+    buf
+      .set_text(0, 1, 2, 2, vec!["N".to_owned(), "X".to_owned()])
+      .await
+      .unwrap();
+    assert_eq!(vec!["oN", "Xree"], fake.lines(1).unwrap());
+
+    buf.set_lines(0, -1, false, vec![]).await.unwrap();
+    assert_eq!(vec![""], fake.lines(1).unwrap());
+    assert_eq!(4, buf.get_changedtick().await.unwrap());
+
+    nvim.set_current_line("current").await.unwrap();
+    assert_eq!("current", nvim.get_current_line().await.unwrap());
+  }
+
+  #[tokio::test]
+  async fn test_attach() {
+    let fake = FakeNeovim::new();
+    let (nvim, mut notifications) = connect(&fake);
+    let buf = nvim.get_current_buf().await.unwrap();
+
+    assert!(buf.attach(true, vec![]).await.unwrap());
+    let (method, args) = notifications.next().await.unwrap();
+    assert_eq!("nvim_buf_lines_event", method);
+    assert_eq!(
+      call_args![buf.get_value().clone(), 1, 0, -1, lines(&[""]), false],
+      args
+    );
+
+    buf
+      .set_lines(0, 0, true, vec!["a".to_owned()])
+      .await
+      .unwrap();
+    assert!(fake.set_lines(1, &["b", "c"]));
+    let (_, args) = notifications.next().await.unwrap();
+    assert_eq!(
+      call_args![buf.get_value().clone(), 2, 0, 0, lines(&["a"]), false],
+      args
+    );
+    let (_, args) = notifications.next().await.unwrap();
+    assert_eq!(
+      call_args![buf.get_value().clone(), 3, 0, 2, lines(&["b", "c"]), false],
+      args
+    );
+
+    assert!(buf.detach().await.unwrap());
+    let (method, _) = notifications.next().await.unwrap();
+    assert_eq!("nvim_buf_detach_event", method);
+    assert!(!buf.detach().await.unwrap());
+  }
+
+  #[tokio::test]
+  async fn test_windows() {
+    let fake = FakeNeovim::new();
+    let (nvim, _notifications) = connect(&fake);
+    let buffer = fake.create_buffer(&["a", "b", "c"]);
+    let buf = nvim.list_bufs().await.unwrap().pop().unwrap();
+
+    let win = nvim.open_win(&buf, true, vec![]).await.unwrap();
+    assert!(win == nvim.get_current_win().await.unwrap());
+    assert_eq!(2, win.get_number().await.unwrap());
+    win.set_cursor((3, 1)).await.unwrap();
+    assert!(is_validation_error(
+      &win.set_cursor((4, 0)).await.unwrap_err()
+    ));
+    assert_eq!((3, 1), win.get_cursor().await.unwrap());
+    assert_eq!("c", nvim.get_current_line().await.unwrap());
+
+    // Shrinking the buffer moves the cursor
+    nvim.del_current_line().await.unwrap();
+    assert_eq!((2, 1), win.get_cursor().await.unwrap());
+
+    let (tabpage, other) = fake.create_tabpage(buffer).unwrap();
+    assert_eq!(other, fake.current_window());
+    assert_eq!(2, nvim.list_tabpages().await.unwrap().len());
+    assert_eq!(3, nvim.list_wins().await.unwrap().len());
+
+    nvim.set_current_win(&win).await.unwrap();
+    assert_eq!(1, fake.current_tabpage());
+    win.close(false).await.unwrap();
+    assert!(!win.is_valid().await.unwrap());
+    assert_eq!(FIRST_WINDOW, fake.current_window());
+
+    let tab = nvim.get_current_tabpage().await.unwrap();
+    let wins = tab.list_wins().await.unwrap();
+    wins[0].close(false).await.unwrap();
+    assert_eq!(tabpage, fake.current_tabpage());
+    let err = nvim.get_current_win().await.unwrap().close(true).await;
+    assert!(err.is_err());
+  }
+
+  #[tokio::test]
+  async fn test_vars() {
+    let fake = FakeNeovim::new();
+    let (nvim, _notifications) = connect(&fake);
+
+    nvim.set_var("global", Value::from(1)).await.unwrap();
+    assert_eq!(Some(Value::from(1)), fake.var("global"));
+    fake.set_var("from_fake", Value::from("x"));
+    assert_eq!(Value::from("x"), nvim.get_var("from_fake").await.unwrap());
+
+    let buf = nvim.get_current_buf().await.unwrap();
+    buf.set_var("local", Value::from(true)).await.unwrap();
+    assert_eq!(Value::from(true), buf.get_var("local").await.unwrap());
+    buf.del_var("local").await.unwrap();
+    assert!(is_validation_error(
+      &buf.get_var("local").await.unwrap_err()
+    ));
+    assert!(nvim.get_var("local").await.is_err());
+  }
+
+  #[tokio::test]
+  async fn test_unknown_method() {
+    let fake = FakeNeovim::new();
+    let (nvim, _notifications) = connect(&fake);
+
+    match *nvim.command("echo 1").await.unwrap_err() {
+      CallError::NeovimError(Some(EXCEPTION), ref msg) => {
+        assert_eq!("Invalid method: nvim_command", msg)
+      }
+      ref e => panic!("Unexpected error {e}"),
+    }
+    // Wrong arguments are answered with an error
+    let res = nvim
+      .call("nvim_buf_get_lines", call_args![1])
+      .await
+      .unwrap();
+    assert!(res.is_err());
+  }
+}
@@ -0,0 +1,209 @@
+//! Tools for testing plugins without a running neovim.
+//!
+//! Two stand-ins can play the part of neovim in a session:
+//!
+//!   * A [`Script`](crate::testing::script::Script) follows a recorded or
+//!     hand-written transcript.
+//!   * A [`FakeNeovim`](crate::testing::fake::FakeNeovim) keeps a model of
+//!     buffers, windows and tabpages, and implements a subset of the API on
+//!     it.
+//!
+//! Connect them to a [`Neovim`](crate::neovim::Neovim) via the in-memory
+//! [`pipe`].
+//!
+//! This module is only available with the `testing` feature, e.g.
+//!
+//! ```toml
+//! [dev-dependencies]
+//! nvim-rs = { version = "0.9", features = ["testing"] }
+//! ```
+use std::{
+  io,
+  pin::Pin,
+  task::{Context, Poll},
+};
+
+use futures::{
+  channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+  io::{AsyncRead, AsyncWrite, AsyncWriteExt},
+  stream::Stream,
+};
+
+use crate::{
+  error::{EncodeError, ScriptError},
+  rpc::model::{self, RpcMessage},
+};
+
+pub mod fake;
+pub mod script;
+
+/// Create an in-memory pipe. Everything written into the [`PipeWriter`] can be
+/// read from the [`PipeReader`]. The reader sees EOF after the writer has been
+/// closed or dropped, and writing fails with
+/// [`BrokenPipe`](std::io::ErrorKind::BrokenPipe) after the reader has been
+/// dropped.
+///
+/// The pipe doesn't depend on a runtime, and is unbounded.
+#[must_use]
+pub fn pipe() -> (PipeReader, PipeWriter) {
+  let (sender, receiver) = unbounded();
+
+  (
+    PipeReader {
+      receiver,
+      buf: Vec::new(),
+      pos: 0,
+    },
+    PipeWriter {
+      sender: Some(sender),
+    },
+  )
+}
+
+/// Create two [`pipe`]s to connect two peers. Each gets the reader of one
+/// pipe and the writer of the other.
+#[must_use]
+pub fn duplex() -> ((PipeReader, PipeWriter), (PipeReader, PipeWriter)) {
+  let (reader1, writer1) = pipe();
+  let (reader2, writer2) = pipe();
+
+  ((reader1, writer2), (reader2, writer1))
+}
+
+/// The reading end of a [`pipe`].
+#[derive(Debug)]
+pub struct PipeReader {
+  receiver: UnboundedReceiver<Vec<u8>>,
+  /// The chunk currently being read
+  buf: Vec<u8>,
+  /// The start of the unread part of `buf`
+  pos: usize,
+}
+
+impl AsyncRead for PipeReader {
+  fn poll_read(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    out: &mut [u8],
+  ) -> Poll<io::Result<usize>> {
+    while self.pos == self.buf.len() {
+      match Pin::new(&mut self.receiver).poll_next(cx) {
+        Poll::Ready(Some(chunk)) => {
+          self.buf = chunk;
+          self.pos = 0;
+        }
+        Poll::Ready(None) => return Poll::Ready(Ok(0)),
+        Poll::Pending => return Poll::Pending,
+      }
+    }
+
+    let n = out.len().min(self.buf.len() - self.pos);
+    out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+    self.pos += n;
+
+    Poll::Ready(Ok(n))
+  }
+}
+
+/// The writing end of a [`pipe`].
+#[derive(Debug)]
+pub struct PipeWriter {
+  /// `None` after the writer has been closed
+  sender: Option<UnboundedSender<Vec<u8>>>,
+}
+
+impl AsyncWrite for PipeWriter {
+  fn poll_write(
+    mut self: Pin<&mut Self>,
+    _cx: &mut Context<'_>,
+    data: &[u8],
+  ) -> Poll<io::Result<usize>> {
+    let Some(sender) = self.sender.as_mut() else {
+      return Poll::Ready(Err(io::Error::new(
+        io::ErrorKind::NotConnected,
+        "Pipe closed",
+      )));
+    };
+
+    if data.is_empty() {
+      return Poll::Ready(Ok(0));
+    }
+
+    match sender.unbounded_send(data.to_vec()) {
+      Ok(()) => Poll::Ready(Ok(data.len())),
+      Err(_) => Poll::Ready(Err(io::Error::new(
+        io::ErrorKind::BrokenPipe,
+        "Pipe reader dropped",
+      ))),
+    }
+  }
+
+  fn poll_flush(
+    self: Pin<&mut Self>,
+    _cx: &mut Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    Poll::Ready(Ok(()))
+  }
+
+  fn poll_close(
+    mut self: Pin<&mut Self>,
+    _cx: &mut Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    self.sender = None;
+    Poll::Ready(Ok(()))
+  }
+}
+
+/// Encode `msg` and send it to the client.
+async fn send<W: AsyncWrite + Unpin>(
+  writer: &mut W,
+  msg: RpcMessage,
+) -> Result<(), Box<ScriptError>> {
+  let mut data = Vec::new();
+  let res: Result<(), Box<EncodeError>> = async {
+    model::encode_sync(&mut data, msg)?;
+    writer.write_all(&data).await?;
+    writer.flush().await?;
+    Ok(())
+  }
+  .await;
+
+  res.map_err(|e| Box::new(ScriptError::WriteError(*e)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use futures::{executor::block_on, AsyncReadExt, AsyncWriteExt};
+
+  #[test]
+  fn test_pipe() {
+    let (mut reader, mut writer) = pipe();
+
+    block_on(async {
+      writer.write_all(b"abc").await.unwrap();
+      writer.write_all(b"defg").await.unwrap();
+
+      let mut buf = [0; 5];
+      reader.read_exact(&mut buf).await.unwrap();
+      assert_eq!(&buf, b"abcde");
+
+      writer.close().await.unwrap();
+      let mut rest = Vec::new();
+      reader.read_to_end(&mut rest).await.unwrap();
+      assert_eq!(rest, b"fg");
+
+      assert!(writer.write_all(b"x").await.is_err());
+    });
+  }
+
+  #[test]
+  fn test_broken_pipe() {
+    let (reader, mut writer) = pipe();
+    drop(reader);
+
+    let err = block_on(writer.write_all(b"abc")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+  }
+}
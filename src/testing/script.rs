@@ -0,0 +1,421 @@
+//! A scripted stand-in for neovim.
+//!
+//! A [`Script`] is a list of messages to send to the client, and messages the
+//! client is expected to send. It can be written by hand, or created from a
+//! transcript recorded by a [`Recorder`](crate::rpc::recorder::Recorder). When
+//! served, the steps are played strictly in order: Messages are sent, and for
+//! each expected message, the next message from the client is read and
+//! compared to it. Any deviation ends the script with a
+//! [`ScriptError`](crate::error::ScriptError).
+//!
+//! Msgids of requests from the client are not compared, since they depend on
+//! the order requests are made in. Instead, the response to a request is sent
+//! with the msgid the client actually used.
+//!
+//! ```
+//! # #[cfg(feature = "use_tokio")]
+//! # #[tokio::main]
+//! # async fn main() {
+//! use nvim_rs::{
+//!   rpc::handler::Dummy, testing::script::Script, Neovim, ShutdownReason,
+//!   Value,
+//! };
+//!
+//! let mut script = Script::new();
+//! script.expect_request(
+//!   "nvim_get_current_line",
+//!   vec![],
+//!   Ok(Value::from("Hello!")),
+//! );
+//! let (reader, writer, serve) = script.start();
+//!
+//! let (nvim, io) = Neovim::new(reader, writer, Dummy::new());
+//! let io = tokio::spawn(io);
+//! let serve = tokio::spawn(serve);
+//!
+//! assert_eq!(nvim.get_current_line().await.unwrap(), "Hello!");
+//! serve.await.unwrap().unwrap();
+//! assert_eq!(io.await.unwrap().unwrap(), ShutdownReason::Disconnected);
+//! # }
+//! # #[cfg(not(feature = "use_tokio"))]
+//! # fn main() {}
+//! ```
+use std::{collections::HashMap, future::Future, io};
+
+use futures::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+
+use crate::{
+  error::{DecodeError, ScriptError},
+  rpc::{
+    model::{Decoder, RpcMessage},
+    recorder::Direction,
+  },
+  testing::{duplex, send, PipeReader, PipeWriter},
+  Value,
+};
+
+/// A step of a [`Script`].
+#[derive(Debug, Clone)]
+enum Step {
+  /// Send the message to the client
+  Send(RpcMessage),
+  /// Read a message from the client, which must match this one
+  Expect(RpcMessage),
+}
+
+/// A scripted session with a client, see the [module docs](self).
+#[derive(Debug, Clone, Default)]
+pub struct Script {
+  steps: Vec<Step>,
+  /// The next msgid to use for requests to the client
+  next_msgid: u64,
+  /// The msgids of the requests sent to the client that don't have an
+  /// expected response yet
+  unanswered: Vec<u64>,
+}
+
+impl Script {
+  #[must_use]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Create a script from a transcript, e.g. one read by
+  /// [`read_transcript`](crate::rpc::recorder::read_transcript). Incoming
+  /// messages (those sent by neovim) are sent to the client, outgoing
+  /// messages are expected from the client.
+  #[must_use]
+  pub fn from_transcript(
+    entries: impl IntoIterator<Item = (Direction, RpcMessage)>,
+  ) -> Self {
+    let mut script = Self::new();
+
+    for (direction, msg) in entries {
+      if let (Direction::Incoming, RpcMessage::RpcRequest { msgid, .. }) =
+        (direction, &msg)
+      {
+        script.next_msgid = script.next_msgid.max(msgid + 1);
+      }
+      script.steps.push(match direction {
+        Direction::Incoming => Step::Send(msg),
+        Direction::Outgoing => Step::Expect(msg),
+      });
+    }
+
+    script
+  }
+
+  /// Expect the client to send a request, and answer it with `response`.
+  pub fn expect_request(
+    &mut self,
+    method: &str,
+    params: Vec<Value>,
+    response: Result<Value, Value>,
+  ) -> &mut Self {
+    // The msgid is a placeholder, mapped to the actual msgid when the request
+    // is received
+    let msgid = u64::MAX - self.steps.len() as u64;
+
+    self.steps.push(Step::Expect(RpcMessage::RpcRequest {
+      msgid,
+      method: method.to_owned(),
+      params,
+    }));
+    self
+      .steps
+      .push(Step::Send(response_message(msgid, response)));
+    self
+  }
+
+  /// Expect the client to send a notification.
+  pub fn expect_notification(
+    &mut self,
+    method: &str,
+    params: Vec<Value>,
+  ) -> &mut Self {
+    self.steps.push(Step::Expect(RpcMessage::RpcNotification {
+      method: method.to_owned(),
+      params,
+    }));
+    self
+  }
+
+  /// Send a notification to the client.
+  pub fn notify(&mut self, method: &str, params: Vec<Value>) -> &mut Self {
+    self.steps.push(Step::Send(RpcMessage::RpcNotification {
+      method: method.to_owned(),
+      params,
+    }));
+    self
+  }
+
+  /// Send a request to the client. Use
+  /// [`expect_response`](Script::expect_response) to expect the answer, which
+  /// allows scripting requests the client makes while handling this one.
+  pub fn request(&mut self, method: &str, params: Vec<Value>) -> &mut Self {
+    let msgid = self.next_msgid;
+    self.next_msgid += 1;
+    self.unanswered.push(msgid);
+
+    self.steps.push(Step::Send(RpcMessage::RpcRequest {
+      msgid,
+      method: method.to_owned(),
+      params,
+    }));
+    self
+  }
+
+  /// Expect the client to answer the last [`request`](Script::request) that
+  /// doesn't have an expected response yet.
+  ///
+  /// # Panics
+  ///
+  /// Panics if all requests already have an expected response.
+  pub fn expect_response(
+    &mut self,
+    response: Result<Value, Value>,
+  ) -> &mut Self {
+    let msgid = self
+      .unanswered
+      .pop()
+      .expect("No request left to expect a response to");
+
+    self
+      .steps
+      .push(Step::Expect(response_message(msgid, response)));
+    self
+  }
+
+  /// Play the script, reading the client's messages from `reader` and
+  /// sending messages to `writer`. The writer is closed when the script has
+  /// finished successfully, so the client will see a
+  /// [`Disconnected`](crate::neovim::ShutdownReason::Disconnected) session.
+  ///
+  /// Messages the client sends after the last expected one are not read.
+  pub async fn serve<R, W>(
+    self,
+    mut reader: R,
+    mut writer: W,
+  ) -> Result<(), Box<ScriptError>>
+  where
+    R: AsyncRead + Send + Unpin + 'static,
+    W: AsyncWrite + Send + Unpin + 'static,
+  {
+    let mut decoder = Decoder::new();
+    // Maps msgids of expected requests to the msgids the client used
+    let mut msgids = HashMap::new();
+
+    for step in self.steps {
+      match step {
+        Step::Send(mut msg) => {
+          if let RpcMessage::RpcResponse { ref mut msgid, .. } = msg {
+            *msgid = msgids.remove(msgid).unwrap_or(*msgid);
+          }
+          send(&mut writer, msg).await?;
+        }
+        Step::Expect(expected) => {
+          let got = match decoder.decode(&mut reader).await {
+            Ok(got) => got,
+            Err(err) => {
+              return Err(Box::new(match *err {
+                DecodeError::ReaderError(ref e)
+                  if e.kind() == io::ErrorKind::UnexpectedEof =>
+                {
+                  ScriptError::Disconnected(expected)
+                }
+                err => ScriptError::ReadError(err),
+              }));
+            }
+          };
+
+          match (&expected, &got) {
+            (
+              RpcMessage::RpcRequest {
+                msgid: expected_msgid,
+                method: expected_method,
+                params: expected_params,
+              },
+              RpcMessage::RpcRequest {
+                msgid,
+                method,
+                params,
+              },
+            ) if expected_method == method && expected_params == params => {
+              msgids.insert(*expected_msgid, *msgid);
+            }
+            _ if expected == got => {}
+            _ => {
+              return Err(Box::new(ScriptError::UnexpectedMessage(
+                expected, got,
+              )));
+            }
+          }
+        }
+      }
+    }
+
+    writer
+      .close()
+      .await
+      .map_err(|e| Box::new(ScriptError::WriteError(*Box::from(e))))
+  }
+
+  /// Create an in-memory connection, and return the client's ends of it
+  /// together with the future [serving](Script::serve) the script on the
+  /// other ends.
+  pub fn start(
+    self,
+  ) -> (
+    PipeReader,
+    PipeWriter,
+    impl Future<Output = Result<(), Box<ScriptError>>>,
+  ) {
+    let ((reader, writer), (their_reader, their_writer)) = duplex();
+
+    (reader, writer, self.serve(their_reader, their_writer))
+  }
+}
+
+fn response_message(msgid: u64, response: Result<Value, Value>) -> RpcMessage {
+  let (error, result) = match response {
+    Ok(result) => (Value::Nil, result),
+    Err(error) => (error, Value::Nil),
+  };
+
+  RpcMessage::RpcResponse {
+    msgid,
+    error,
+    result,
+  }
+}
+
+#[cfg(all(test, feature = "use_tokio"))]
+mod tests {
+  use super::*;
+
+  use futures::{
+    channel::mpsc::{unbounded, UnboundedSender},
+    StreamExt,
+  };
+
+  use crate::{
+    neovim::Neovim, rpc::handler::Handler, rpc::IntoVal, ShutdownReason,
+  };
+
+  #[derive(Clone)]
+  struct EchoHandler {
+    notifications: UnboundedSender<(String, Vec<Value>)>,
+  }
+
+  impl Handler for EchoHandler {
+    type Writer = PipeWriter;
+
+    async fn handle_request(
+      &self,
+      name: String,
+      args: Vec<Value>,
+      neovim: Neovim<Self::Writer>,
+    ) -> Result<Value, Value> {
+      match name.as_ref() {
+        "echo" => Ok(Value::from(args)),
+        "line" => neovim
+          .get_current_line()
+          .await
+          .map(Value::from)
+          .map_err(|e| Value::from(e.to_string())),
+        _ => Err(Value::from("Unknown request")),
+      }
+    }
+
+    async fn handle_notify(
+      &self,
+      name: String,
+      args: Vec<Value>,
+      _neovim: Neovim<Self::Writer>,
+    ) {
+      self.notifications.unbounded_send((name, args)).unwrap();
+    }
+  }
+
+  #[tokio::test]
+  async fn test_script() {
+    let mut script = Script::new();
+    script
+      .expect_request("nvim_command", call_args!["echo 1"], Ok(Value::Nil))
+      .request("echo", call_args!["a"])
+      .expect_response(Ok(Value::from(call_args!["a"])))
+      .request("line", call_args![])
+      .expect_request(
+        "nvim_get_current_line",
+        call_args![],
+        Ok(Value::from("current")),
+      )
+      .expect_response(Ok(Value::from("current")))
+      .notify("note", call_args![1, 2])
+      .expect_notification("nvim_command", call_args!["echo 2"]);
+
+    let (reader, writer, serve) = script.start();
+    let (notifications, mut notification_receiver) = unbounded();
+    let (nvim, io) = Neovim::new(reader, writer, EchoHandler { notifications });
+    let io = tokio::spawn(io);
+    let serve = tokio::spawn(serve);
+
+    nvim.command("echo 1").await.unwrap();
+    notification_receiver.next().await.unwrap();
+    nvim
+      .send_notification("nvim_command", call_args!["echo 2"])
+      .await
+      .unwrap();
+
+    serve.await.unwrap().unwrap();
+    assert_eq!(io.await.unwrap().unwrap(), ShutdownReason::Disconnected);
+  }
+
+  #[tokio::test]
+  async fn test_unexpected_message() {
+    let mut script = Script::new();
+    script.expect_request("nvim_command", call_args!["echo 1"], Ok(Value::Nil));
+
+    let (reader, writer, serve) = script.start();
+    let (notifications, _notification_receiver) = unbounded();
+    let (nvim, io) = Neovim::new(reader, writer, EchoHandler { notifications });
+    let _io = tokio::spawn(io);
+    let serve = tokio::spawn(serve);
+
+    let call = tokio::spawn(async move { nvim.command("echo 2").await });
+
+    match *serve.await.unwrap().unwrap_err() {
+      ScriptError::UnexpectedMessage(
+        RpcMessage::RpcRequest { .. },
+        RpcMessage::RpcRequest { params, .. },
+      ) => assert_eq!(params, call_args!["echo 2"]),
+      e => panic!("Unexpected error {e}"),
+    }
+    // The script has ended and dropped its ends of the connection
+    assert!(call.await.unwrap().unwrap_err().is_channel_closed());
+  }
+
+  #[tokio::test]
+  async fn test_from_transcript() {
+    let transcript = concat!(
+      r#"{"time":0.1,"direction":"out","type":"request","msgid":0,"#,
+      r#""method":"nvim_get_current_buf","params":[]}"#,
+      "\n",
+      r#"{"time":0.2,"direction":"in","type":"response","msgid":0,"#,
+      r#""error":null,"result":{"buffer":3}}"#,
+      "\n",
+    );
+    let entries =
+      crate::rpc::recorder::read_transcript(transcript.as_bytes()).unwrap();
+    let (reader, writer, serve) = Script::from_transcript(entries).start();
+
+    let (notifications, _notification_receiver) = unbounded();
+    let (nvim, io) = Neovim::new(reader, writer, EchoHandler { notifications });
+    let _io = tokio::spawn(io);
+    let serve = tokio::spawn(serve);
+
+    let buf = nvim.get_current_buf().await.unwrap();
+    assert_eq!(buf.get_value(), &Value::Ext(0, vec![3]));
+    serve.await.unwrap().unwrap();
+  }
+}
@@ -0,0 +1,80 @@
+use nvim_rs::rpc::handler::Dummy as DummyHandler;
+
+#[cfg(feature = "use_tokio")]
+use nvim_rs::create::tokio as create;
+
+#[cfg(feature = "use_smol")]
+use nvim_rs::create::smol as create;
+
+use nvim_rs::create::child::{ChildBuilder, ChildConfig};
+
+use super::atest;
+
+use std::path::Path;
+
+use super::common::*;
+
+atest!{
+async fn spawns_isolated_child() {
+  let mut builder = ChildBuilder::new();
+  builder
+    .set_program(nvim_path())
+    .set_config(ChildConfig::Clean)
+    .set_headless(true)
+    .set_isolated(true)
+    .set_kill_on_drop(true)
+    .set_capture_stderr(true)
+    .env("NVIM_RS_TEST", "child")
+    .file("some_file.txt");
+
+  let (nvim, _io_handle, mut child) =
+    create::spawn_child(&builder, DummyHandler::new())
+      .await
+      .expect("Cannot start neovim");
+
+  assert!(child.handshake_info().is_none());
+
+  let isolated = child.isolated_dir().expect("No isolated dir").to_owned();
+  assert!(isolated.is_dir());
+
+  let config = nvim.call_function("stdpath", vec!["config".into()]).await;
+  let config = config.expect("Cannot get stdpath");
+  assert!(Path::new(config.as_str().unwrap()).starts_with(&isolated));
+
+  let var = nvim.eval("$NVIM_RS_TEST").await.unwrap();
+  assert_eq!(Some("child"), var.as_str());
+  let name = nvim.eval("expand('%')").await.unwrap();
+  assert_eq!(Some("some_file.txt"), name.as_str());
+
+  // Neovim quits before answering
+  let _ = nvim.command("qa!").await;
+  let status = child.wait().await.expect("Cannot wait for neovim");
+  assert!(status.success());
+
+  drop(child);
+  assert!(!isolated.exists());
+}
+}
+
+atest!{
+async fn spawns_child_with_handshake() {
+  let mut builder = ChildBuilder::new();
+  builder
+    .set_program(nvim_path())
+    .set_config(ChildConfig::None)
+    .set_kill_on_drop(true)
+    .set_handshake("handshake_message");
+
+  let (nvim, _io_handle, child) =
+    create::spawn_child(&builder, DummyHandler::new())
+      .await
+      .expect("Cannot start neovim");
+
+  let info = child.handshake_info().expect("No handshake info");
+  let api_info = nvim.get_api_info().await.unwrap();
+  assert_eq!(api_info[0].as_u64(), Some(info.channel_id));
+
+  let res = nvim.eval("1 + 1").await.unwrap();
+  assert_eq!(Some(2), res.as_i64());
+}
+}
@@ -19,9 +19,7 @@ use std::{
 #[cfg(unix)]
 use tempfile::{Builder, TempDir};
 
-#[path = "../common/mod.rs"]
-mod common;
-use common::*;
+use super::common::*;
 
 const HOST: &str = "127.0.0.1";
 const PORT: u16 = 6666;
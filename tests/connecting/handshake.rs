@@ -8,13 +8,11 @@ use tokio::process::Command;
 #[cfg(feature = "use_smol")]
 use nvim_rs::create::smol as create;
 #[cfg(feature = "use_smol")]
-use std::process::Command;
+use smol::process::Command;
 
 use super::atest;
 
-#[path = "../common/mod.rs"]
-mod common;
-use common::*;
+use super::common::*;
 
 use nvim_rs::error::HandshakeError;
 
@@ -22,13 +20,17 @@ atest!{
 async fn successful_handshake() {
   let handler = DummyHandler::new();
 
-  create::new_child_handshake_cmd(
-    Command::new(nvim_path()).args(&["-u", "NONE", "--embed"]),
+  let (nvim, _io_handle, _child, info) = create::new_child_handshake_cmd(
+    Command::new(nvim_path()).args(["-u", "NONE", "--embed"]),
     handler,
     "handshake_message",
   )
   .await
   .expect("Should launch correctly");
+
+  let api_info = nvim.get_api_info().await.expect("Cannot get api info");
+  assert_eq!(api_info[0].as_u64(), Some(info.channel_id));
+  assert_eq!(api_info[1], info.api_info);
 }
 }
 
@@ -38,8 +40,8 @@ async fn successful_handshake_with_extra_output() {
   let handler = DummyHandler::new();
   let nvim = nvim_path();
 
-  create::new_child_handshake_cmd(
-    Command::new("/bin/sh").args(&[
+  let _session = create::new_child_handshake_cmd(
+    Command::new("/bin/sh").args([
       "-c",
       &format!(
         "echo 'extra output';{} -u NONE --embed",
@@ -59,13 +61,13 @@ atest!{
 async fn unsuccessful_handshake_with_wrong_output() {
   let handler = DummyHandler::new();
 
-  // NOTE: This has to match the exact length of the message sent
-  let expected_request_len = 46;
+  // NOTE: This has to match the exact length of the messages sent
+  let expected_request_len = 70;
 
   // Make sure that the command is alive for long enough by reading the request
   // message from stdin with dd
   let res = create::new_child_handshake_cmd(
-    Command::new("/bin/sh").args(&[
+    Command::new("/bin/sh").args([
         "-c",
         &format!("echo 'wrong output';
                   timeout 5 dd bs=1 count={expected_request_len} > /dev/null 2>&1")]),
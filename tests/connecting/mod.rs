@@ -1,10 +1,26 @@
+#[cfg(any(feature = "use_tokio", feature = "use_smol"))]
+#[path = "../common/mod.rs"]
+mod common;
+
 #[cfg(feature = "use_tokio")]
 pub mod conns;
 #[cfg(feature = "use_smol")]
 pub mod conns;
 
+#[cfg(feature = "use_tokio")]
+pub mod child;
+#[cfg(feature = "use_smol")]
+pub mod child;
+
 #[cfg(feature = "use_tokio")]
 pub mod handshake;
+#[cfg(feature = "use_smol")]
+pub mod handshake;
+
+#[cfg(feature = "use_tokio")]
+pub mod server;
+#[cfg(feature = "use_smol")]
+pub mod server;
 #[cfg(feature = "use_tokio")]
 macro_rules! atest { // taken from smol::lib.rs
     // Special case to get around bug in macro engine.
@@ -0,0 +1,192 @@
+//! Connect to our own servers with the client constructors, so no neovim is
+//! needed.
+use std::time::Duration;
+
+use nvim_rs::{
+  create::address::ServerAddress, rpc::handler::Dummy as DummyHandler, Backoff,
+  Handler, Neovim, Value,
+};
+
+#[cfg(feature = "use_tokio")]
+use nvim_rs::create::tokio as create;
+
+#[cfg(feature = "use_smol")]
+use nvim_rs::create::smol as create;
+
+use super::atest;
+
+#[cfg(feature = "use_tokio")]
+type TcpWriter =
+  nvim_rs::compat::tokio::Compat<tokio::io::WriteHalf<tokio::net::TcpStream>>;
+#[cfg(feature = "use_smol")]
+type TcpWriter = smol::net::TcpStream;
+
+#[cfg(all(unix, feature = "use_tokio"))]
+type PathWriter = nvim_rs::compat::tokio::Compat<
+  tokio::io::WriteHalf<create::ServerConnection>,
+>;
+#[cfg(all(unix, feature = "use_smol"))]
+type PathWriter = smol::net::unix::UnixStream;
+
+#[cfg(feature = "use_tokio")]
+type AnyWriter =
+  nvim_rs::compat::tokio::Compat<tokio::io::WriteHalf<create::AnyConnection>>;
+#[cfg(all(unix, feature = "use_smol"))]
+type AnyWriter = create::AnyConnection;
+
+/// Answers each request with the number of the connection it was made on.
+struct CountingHandler<W> {
+  connection: u64,
+  _writer: std::marker::PhantomData<fn() -> W>,
+}
+
+impl<W> Clone for CountingHandler<W> {
+  fn clone(&self) -> Self {
+    CountingHandler {
+      connection: self.connection,
+      _writer: std::marker::PhantomData,
+    }
+  }
+}
+
+impl<W> Handler for CountingHandler<W>
+where
+  W: futures::AsyncWrite + Send + Unpin + 'static,
+{
+  type Writer = W;
+
+  async fn handle_request(
+    &self,
+    _name: String,
+    _args: Vec<Value>,
+    _neovim: Neovim<W>,
+  ) -> Result<Value, Value> {
+    Ok(Value::from(self.connection))
+  }
+}
+
+fn counting_factory<W>() -> impl FnMut() -> CountingHandler<W> {
+  let mut connections = 0;
+  move || {
+    connections += 1;
+    CountingHandler {
+      connection: connections,
+      _writer: std::marker::PhantomData,
+    }
+  }
+}
+
+atest! {
+async fn accepts_tcp_connections() {
+  let mut server = create::listen_tcp("127.0.0.1:0", counting_factory())
+    .await
+    .expect("Cannot listen");
+  let addr = server.local_addr().unwrap();
+
+  for i in 1..=2u64 {
+    let (client, _client_io) =
+      create::new_tcp(addr, DummyHandler::<TcpWriter>::new())
+        .await
+        .expect("Cannot connect");
+    let (server_side, _server_io) = server.accept().await.unwrap();
+
+    let res = client.call("which", vec![]).await.unwrap();
+    assert_eq!(Ok(Value::from(i)), res);
+
+    // The server side can make requests as well
+    let res = server_side.call("foo", vec![]).await.unwrap();
+    assert_eq!(Err(Value::from("Not implemented")), res);
+  }
+}
+}
+
+atest! {
+async fn reconnects() {
+  let mut server = create::listen_tcp("127.0.0.1:0", counting_factory())
+    .await
+    .expect("Cannot listen");
+  let addr = server.local_addr().unwrap();
+
+  let mut backoff = Backoff::new();
+  backoff.set_initial_delay(Duration::from_millis(10));
+  let (client, _client_io) = create::new_tcp_reconnecting(
+    addr,
+    DummyHandler::<TcpWriter>::new(),
+    backoff,
+  )
+  .await
+  .expect("Cannot connect");
+
+  let (server_side, _server_io) = server.accept().await.unwrap();
+  let res = client.call("which", vec![]).await.unwrap();
+  assert_eq!(Ok(Value::from(1u64)), res);
+
+  server_side.close().await;
+  let (_server_side, _server_io) = server.accept().await.unwrap();
+  let res = client.call("which", vec![]).await.unwrap();
+  assert_eq!(Ok(Value::from(2u64)), res);
+}
+}
+
+#[cfg(unix)]
+atest! {
+async fn accepts_path_connections() {
+  let dir = tempfile::Builder::new()
+    .prefix("nvim-rs.test")
+    .tempdir()
+    .expect("Cannot create temporary directory for test.");
+  let path = dir.path().join("server_socket");
+
+  let mut server = create::listen_path(&path, counting_factory::<PathWriter>())
+    .await
+    .expect("Cannot listen");
+
+  let (client, _client_io) = create::new_path(&path, DummyHandler::new())
+    .await
+    .expect("Cannot connect");
+  let (_server_side, _server_io) = server.accept().await.unwrap();
+
+  let res = client.call("which", vec![]).await.unwrap();
+  assert_eq!(Ok(Value::from(1u64)), res);
+}
+}
+
+#[cfg(unix)]
+atest! {
+async fn connects_to_addresses() {
+  let dir = tempfile::Builder::new()
+    .prefix("nvim-rs.test")
+    .tempdir()
+    .expect("Cannot create temporary directory for test.");
+  let path = dir.path().join("nvim.1.0");
+
+  let mut tcp_server =
+    create::listen_tcp("127.0.0.1:0", counting_factory::<TcpWriter>())
+      .await
+      .expect("Cannot listen");
+  let mut path_server =
+    create::listen_path(&path, counting_factory::<PathWriter>())
+      .await
+      .expect("Cannot listen");
+
+  let tcp_addr: ServerAddress =
+    tcp_server.local_addr().unwrap().to_string().parse().unwrap();
+  let path_addr: ServerAddress = path.to_str().unwrap().parse().unwrap();
+  assert_eq!(ServerAddress::Path(path.clone()), path_addr);
+
+  for addr in [tcp_addr, path_addr] {
+    let (client, _client_io) =
+      create::connect(&addr, DummyHandler::<AnyWriter>::new())
+        .await
+        .expect("Cannot connect");
+    // Keep the server side running
+    let _server_io = match addr {
+      ServerAddress::Tcp(_) => tcp_server.accept().await.unwrap().1,
+      ServerAddress::Path(_) => path_server.accept().await.unwrap().1,
+    };
+
+    let res = client.call("which", vec![]).await.unwrap();
+    assert_eq!(Ok(Value::from(1u64)), res);
+  }
+}
+}
@@ -2,3 +2,5 @@
 pub mod buffering;
 #[cfg(feature = "use_smol")]
 pub mod buffering;
+#[cfg(feature = "use_tokio")]
+pub mod protocol;
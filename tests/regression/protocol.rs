@@ -0,0 +1,241 @@
+//! A misbehaving peer should end the session with an error, not a panic.
+use futures::io::AsyncWriteExt;
+use rmpv::Value;
+use tokio::io::{duplex, split, DuplexStream, ReadHalf, WriteHalf};
+use tokio_util::compat::{
+  Compat, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
+};
+
+use nvim_rs::{
+  error::{DecodeError, HandshakeError, InvalidMessage, LoopError},
+  rpc::{handler::Dummy, model::Decoder, RpcMessage},
+  Neovim, SessionOptions, ShutdownReason,
+};
+
+type Writer = Compat<WriteHalf<DuplexStream>>;
+type Reader = Compat<ReadHalf<DuplexStream>>;
+
+fn pipe() -> ((Reader, Writer), (Reader, Writer)) {
+  let (ours, theirs) = duplex(1024);
+  let (reader, writer) = split(ours);
+  let (their_reader, their_writer) = split(theirs);
+
+  (
+    (reader.compat(), writer.compat_write()),
+    (their_reader.compat(), their_writer.compat_write()),
+  )
+}
+
+fn encode(val: Value) -> Vec<u8> {
+  let mut data = Vec::new();
+  rmpv::encode::write_value(&mut data, &val).unwrap();
+  data
+}
+
+/// Read the two handshake requests, and return the message.
+async fn read_handshake(reader: &mut Reader) -> String {
+  let mut decoder = Decoder::new();
+  let RpcMessage::RpcRequest { msgid, params, .. } =
+    decoder.decode(reader).await.unwrap()
+  else {
+    panic!("No handshake request");
+  };
+  assert_eq!(0, msgid);
+  let RpcMessage::RpcRequest { msgid, method, .. } =
+    decoder.decode(reader).await.unwrap()
+  else {
+    panic!("No api info request");
+  };
+  assert_eq!((1, "nvim_get_api_info"), (msgid, method.as_str()));
+
+  params[1].as_array().unwrap()[0]
+    .as_str()
+    .unwrap()
+    .to_owned()
+}
+
+#[tokio::test]
+async fn handshake_skips_noise() {
+  let ((reader, writer), (mut their_reader, mut their_writer)) = pipe();
+  let message = "twenty-five bytes long...";
+
+  let theirs = tokio::spawn(async move {
+    assert_eq!(message, read_handshake(&mut their_reader).await);
+
+    // Output from the configuration, including something that looks like
+    // the start of a message
+    let noise = b"some output\n\x94\x01";
+    their_writer.write_all(noise).await.unwrap();
+    // Neovim encodes strings of 20-31 bytes with a str8 marker
+    let mut response = vec![0x94, 0x01, 0x00, 0xc0, 0xd9, 25];
+    response.extend_from_slice(message.as_bytes());
+    their_writer.write_all(&response).await.unwrap();
+    // A notification before the second response is kept for the session
+    let notification = Value::from(vec![
+      Value::from(2),
+      Value::from("nvim_error_event"),
+      Value::from(vec![Value::from(0), Value::from("oops")]),
+    ]);
+    let api_info = Value::from(vec![
+      Value::from(1),
+      Value::from(1),
+      Value::Nil,
+      Value::from(vec![Value::from(3), Value::Map(vec![])]),
+    ]);
+    let mut data = encode(notification);
+    data.extend(encode(api_info));
+    their_writer.write_all(&data).await.unwrap();
+    their_writer.close().await.unwrap();
+    let written = noise.len() + response.len() + data.len();
+    (their_reader, written as u64)
+  });
+
+  let mut options = SessionOptions::new();
+  options.set_metrics(true);
+  let (nvim, io, info) = Neovim::handshake_with_options(
+    reader,
+    writer,
+    Dummy::new(),
+    message,
+    &options,
+  )
+  .await
+  .unwrap();
+  assert_eq!(3, info.channel_id);
+  assert_eq!(Value::Map(vec![]), info.api_info);
+
+  let (_their_reader, written) = theirs.await.unwrap();
+  assert_eq!(ShutdownReason::Disconnected, io.await.unwrap());
+
+  // The noise and the handshake responses count as received, and the
+  // notification only once
+  let stats = nvim.stats().unwrap();
+  assert_eq!(written, stats.bytes_received);
+  assert_eq!(1, stats.notifications_received);
+}
+
+#[tokio::test]
+async fn handshake_skips_invalid_markers() {
+  let ((reader, writer), (mut their_reader, mut their_writer)) = pipe();
+
+  let theirs = tokio::spawn(async move {
+    read_handshake(&mut their_reader).await;
+
+    // Lots of output that looks like the start of huge strings, and a
+    // response to another request, neither of which is ever completed
+    let mut noise = vec![0xdb; 256 * 1024];
+    noise.extend_from_slice(&[0x94, 0x01, 0x05, 0xc0, 0xdb, 0xff]);
+    their_writer.write_all(&noise).await.unwrap();
+    let response = [0x94, 0x01, 0x00, 0xc0, 0xa5, b'h', b'e', b'l', b'l', b'o'];
+    their_writer.write_all(&response).await.unwrap();
+    let api_info = Value::from(vec![
+      Value::from(1),
+      Value::from(1),
+      Value::Nil,
+      Value::from(vec![Value::from(3), Value::Map(vec![])]),
+    ]);
+    their_writer.write_all(&encode(api_info)).await.unwrap();
+    (their_reader, their_writer)
+  });
+
+  let (_nvim, _io, info) =
+    Neovim::handshake(reader, writer, Dummy::new(), "hello")
+      .await
+      .unwrap();
+  assert_eq!(3, info.channel_id);
+  let _theirs = theirs.await.unwrap();
+}
+
+#[tokio::test]
+async fn handshake_reports_noise() {
+  let ((reader, writer), (mut their_reader, mut their_writer)) = pipe();
+
+  let theirs = tokio::spawn(async move {
+    read_handshake(&mut their_reader).await;
+    their_writer.write_all(b"wrong output\n").await.unwrap();
+    their_writer.close().await.unwrap();
+    their_reader
+  });
+
+  let res = Neovim::handshake(reader, writer, Dummy::new(), "hello").await;
+  let _their_reader = theirs.await.unwrap();
+
+  match res.map(|_| ()).unwrap_err().as_ref() {
+    HandshakeError::UnexpectedResponse(output) => {
+      assert_eq!("wrong output\n", output);
+    }
+    e => panic!("Unexpected error {e}"),
+  }
+}
+
+#[tokio::test]
+async fn response_to_unknown_request() {
+  let ((reader, writer), (_their_reader, mut their_writer)) = pipe();
+  let (_nvim, io) = Neovim::new(reader, writer, Dummy::new());
+  let io = tokio::spawn(io);
+
+  let response = Value::from(vec![
+    Value::from(1),
+    Value::from(17),
+    Value::Nil,
+    Value::Nil,
+  ]);
+  their_writer.write_all(&encode(response)).await.unwrap();
+
+  match *io.await.unwrap().unwrap_err() {
+    LoopError::MsgidNotFound(17) => {}
+    ref e => panic!("Unexpected error {e}"),
+  }
+}
+
+#[tokio::test]
+async fn invalid_method_name() {
+  let ((reader, writer), (mut their_reader, mut their_writer)) = pipe();
+  let (_nvim, io) = Neovim::new(reader, writer, Dummy::new());
+  let io = tokio::spawn(io);
+
+  // A request [0, 1, method, []] where the method name isn't valid UTF-8
+  let request = [0x94, 0x00, 0x01, 0xa2, 0xff, 0xfe, 0x90];
+  their_writer.write_all(&request).await.unwrap();
+
+  // The request is answered with an error, and the session goes on
+  let response = Decoder::new().decode(&mut their_reader).await.unwrap();
+  match response {
+    RpcMessage::RpcResponse {
+      msgid,
+      error,
+      result,
+    } => {
+      assert_eq!(1, msgid);
+      assert_eq!(Value::from("Invalid method name"), error);
+      assert_eq!(Value::Nil, result);
+    }
+    msg => panic!("Unexpected message {msg:?}"),
+  }
+
+  their_writer.close().await.unwrap();
+  assert_eq!(ShutdownReason::Disconnected, io.await.unwrap().unwrap());
+}
+
+#[tokio::test]
+async fn declared_length_too_large() {
+  // Nested array32 headers, and a str32 header, declaring gigabytes of data
+  let arrays = [0xdd, 0xff, 0xff, 0xff, 0xff].repeat(6);
+  let string = vec![0xdb, 0xff, 0xff, 0xff, 0xff];
+
+  for data in [arrays, string] {
+    let ((reader, writer), (_their_reader, mut their_writer)) = pipe();
+    let (_nvim, io) = Neovim::new(reader, writer, Dummy::new());
+    let io = tokio::spawn(io);
+
+    their_writer.write_all(&data).await.unwrap();
+
+    match *io.await.unwrap().unwrap_err() {
+      LoopError::DecodeError(ref e, _) => match e.as_ref() {
+        DecodeError::InvalidMessage(InvalidMessage::TooLarge(..)) => {}
+        e => panic!("Unexpected error {e}"),
+      },
+      ref e => panic!("Unexpected error {e}"),
+    }
+  }
+}